@@ -6,8 +6,15 @@ function cdir_cd {
     if [ $# -eq 0 ]; then
         DIR=$HOME;
     fi;
+    # measure the time spent in the directories, if enabled
+    if [ -n "$CDIR_RECORD_DWELL" ]; then
+        cdir leave "`pwd`"
+    fi
     builtin cd "${DIR}"
     cdir add-path "`pwd`"
+    if [ -n "$CDIR_RECORD_DWELL" ]; then
+        cdir enter "`pwd`"
+    fi
 }
 
 # Mimic default auto-complete of the default cd command
@@ -24,9 +31,13 @@ function p {
    cdir add-shortcut $1 "`pwd`" "${2}"
 }
 
-# c command to change the current directory using shortcuts
+# c command to change the current directory using shortcuts, or to the directory chosen
+# the last time in the GUI with "c -"
 function c {
-    if [ $# -eq 0 ]; then
+    if [ "$1" = "-" ]; then
+        DIR="`cdir --last`"
+        [[ ! -z $DIR ]] && cd "$DIR"
+    elif [ $# -eq 0 ]; then
         TMP_FILE=`mktemp`
         cdir gui $TMP_FILE
         DIR="`cat $TMP_FILE`"
@@ -0,0 +1,2 @@
+-- Index the paths for the prefix searches (LIKE ignores the ASCII case, as NOCASE)
+CREATE INDEX IF NOT EXISTS paths_path ON paths (path COLLATE NOCASE);
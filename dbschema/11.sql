@@ -0,0 +1,9 @@
+-- Store the frecency score of the paths, to sort them with an index (see Store::reage_scores)
+ALTER TABLE paths
+    ADD COLUMN score REAL NOT NULL DEFAULT 0;
+UPDATE paths SET score = visits * CASE
+    WHEN CAST(strftime('%s','now') AS INTEGER) - date < 3600 THEN 4.0
+    WHEN CAST(strftime('%s','now') AS INTEGER) - date < 86400 THEN 2.0
+    WHEN CAST(strftime('%s','now') AS INTEGER) - date < 604800 THEN 0.5
+    ELSE 0.25 END;
+CREATE INDEX IF NOT EXISTS paths_score ON paths (score);
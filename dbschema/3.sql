@@ -0,0 +1,5 @@
+-- Add the meta table (key/value pairs used by the application)
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
@@ -0,0 +1,3 @@
+-- Add the number of visits to the paths table
+ALTER TABLE paths
+    ADD COLUMN visits INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,4 @@
+-- Add the date when the path was first seen to the paths table
+ALTER TABLE paths
+    ADD COLUMN created INTEGER NOT NULL DEFAULT 0;
+UPDATE paths SET created = date;
@@ -0,0 +1,3 @@
+-- Add the cumulative time spent in the path to the paths table
+ALTER TABLE paths
+    ADD COLUMN dwell_seconds INTEGER NOT NULL DEFAULT 0;
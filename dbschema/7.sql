@@ -0,0 +1,3 @@
+-- Add the hidden flag to the paths table (the hidden paths are not listed by default)
+ALTER TABLE paths
+    ADD COLUMN hidden INTEGER NOT NULL DEFAULT 0;
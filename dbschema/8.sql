@@ -0,0 +1,3 @@
+-- Add the command which was running when the path was visited (null if not given)
+ALTER TABLE paths
+    ADD COLUMN via TEXT;
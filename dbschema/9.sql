@@ -0,0 +1,3 @@
+-- Add the date when the shortcut was created to the shortcuts table (0 if unknown)
+ALTER TABLE shortcuts
+    ADD COLUMN created INTEGER NOT NULL DEFAULT 0;
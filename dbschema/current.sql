@@ -9,15 +9,30 @@ CREATE TABLE IF NOT EXISTS version (
 CREATE TABLE IF NOT EXISTS paths (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     path TEXT NOT NULL,
-    date INTEGER NOT NULL
+    date INTEGER NOT NULL,
+    visits INTEGER NOT NULL DEFAULT 1,
+    created INTEGER NOT NULL DEFAULT 0,
+    dwell_seconds INTEGER NOT NULL DEFAULT 0,
+    hidden INTEGER NOT NULL DEFAULT 0,
+    via TEXT,
+    score REAL NOT NULL DEFAULT 0
 );
 CREATE INDEX IF NOT EXISTS paths_date ON paths (date);
+CREATE INDEX IF NOT EXISTS paths_path ON paths (path COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS paths_score ON paths (score);
 
 -- Shortcuts table
 CREATE TABLE IF NOT EXISTS shortcuts (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     name TEXT NOT NULL,
     path TEXT NOT NULL,
-    description TEXT
+    description TEXT,
+    created INTEGER NOT NULL DEFAULT 0
 );
 CREATE INDEX IF NOT EXISTS shortcuts_name ON shortcuts (name);
+
+-- Meta table (key/value pairs used by the application)
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
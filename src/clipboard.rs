@@ -0,0 +1,70 @@
+use std::io::Write;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A clipboard where a text can be copied.
+pub(crate) trait Clipboard {
+    /// Copies a text to the clipboard.
+    ///
+    /// ### Returns
+    /// Ok(()) if the text was sent to the clipboard, otherwise the error
+    fn copy(&self, text: &str) -> std::io::Result<()>;
+}
+
+/// The clipboard of the terminal, set with the OSC 52 escape sequence. It works through ssh,
+/// provided the terminal (or tmux, with `set-clipboard on`) supports it.
+pub(crate) struct Osc52Clipboard;
+
+impl Clipboard for Osc52Clipboard {
+    fn copy(&self, text: &str) -> std::io::Result<()> {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(osc52_sequence(text).as_bytes())?;
+        stdout.flush()
+    }
+}
+
+/// Returns the escape sequence setting the clipboard to a text.
+pub(crate) fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+/// Encodes bytes in base64, with padding.
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"/home/user\n/tmp"), "L2hvbWUvdXNlcgovdG1w");
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("/tmp"), "\x1b]52;c;L3RtcA==\x07");
+    }
+}
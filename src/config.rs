@@ -1,22 +1,41 @@
-use std::{env, fs, io::Write, path::PathBuf};
+use std::{collections::HashMap, env, fs, io::Write, path::PathBuf, sync::Once};
 
 use chrono::{DateTime, Local};
 use log::{debug, error, info, trace};
 use serde::{Deserialize, Serialize};
 
-use crate::theme::{Theme, ThemeStyles};
+use crate::{
+    store::ListOrder,
+    theme::{Theme, ThemeStyles},
+};
 
 pub(crate) const CDIR_CONFIG_VAR: &str = "CDIR_CONFIG";
+pub(crate) const CDIR_PROFILE_VAR: &str = "CDIR_PROFILE";
+
+// The profile using the configured `db_path`
+pub(crate) const DEFAULT_PROFILE_NAME: &str = "default";
+
+// The error when the database or the shell files have no default place
+pub(crate) const NO_HOME_ERROR: &str = "no home directory, set db_path";
+
+/// Returns a directory of the user (e.g. the home directory), or the temporary directory if it
+/// is unknown, e.g. in a container without HOME nor user entry.
+/// Only for the configuration, themes and log paths: the database and the shell files are not
+/// written to the temporary directory, see `NO_HOME_ERROR`.
+fn user_dir(dir: Option<PathBuf>) -> PathBuf {
+    dir.unwrap_or_else(|| {
+        static NO_HOME: Once = Once::new();
+        NO_HOME.call_once(|| debug!("No home directory, using {:?}", env::temp_dir()));
+        env::temp_dir()
+    })
+}
 
-const DEFAULT_DB_PATH: fn() -> Option<PathBuf> = || {
-    let mut path = dirs::data_dir().unwrap();
-    path.push("cdir");
-    path.push("cdir.db");
-    Some(path)
-};
+// None without home directory: the database must then be configured
+const DEFAULT_DB_PATH: fn() -> Option<PathBuf> =
+    || dirs::data_dir().map(|dir| dir.join("cdir").join("cdir.db"));
 
 const DEFAULT_LOG_CONFIG_PATH: fn() -> Option<PathBuf> = || {
-    let mut path = dirs::home_dir().unwrap();
+    let mut path = user_dir(dirs::home_dir());
     path.push(".config");
     path.push("cdir");
     path.push("log4rs.yaml");
@@ -24,7 +43,7 @@ const DEFAULT_LOG_CONFIG_PATH: fn() -> Option<PathBuf> = || {
 };
 
 const DEFAULT_THEMES_DIRECTORY_PATH: fn() -> Option<PathBuf> = || {
-    let mut path = dirs::home_dir().unwrap();
+    let mut path = user_dir(dirs::home_dir());
     path.push(".config");
     path.push("cdir");
     path.push("themes");
@@ -42,6 +61,170 @@ const DEFAULT_DATE_FORMATER: fn() -> Box<dyn Fn(i64) -> String> =
 
 const DEFAULT_NONE: fn() -> Option<String> = || None;
 
+const DEFAULT_EMPTY_MESSAGE: fn() -> String = || String::from("no entry");
+
+const DEFAULT_RECENT_SEARCHES_MAX: fn() -> usize = || 20;
+
+const DEFAULT_FALSE: fn() -> bool = || false;
+
+const DEFAULT_TRUE: fn() -> bool = || true;
+
+const DEFAULT_MIN_QUERY_LEN: fn() -> usize = || 0;
+
+const DEFAULT_SEARCH_DEBOUNCE_MS: fn() -> u64 = || 40;
+const DEFAULT_REVISIT_WINDOW_SECONDS: fn() -> u64 = || 5;
+const DEFAULT_SHORTCUT_SORT: fn() -> ShortcutSort = || ShortcutSort::Name;
+const DEFAULT_START_VIEW: fn() -> Option<StartView> = || None;
+const DEFAULT_RESERVED_SHORTCUT_NAMES: fn() -> Vec<String> = || {
+    [
+        "cd", "ls", "pwd", "cp", "mv", "rm", "mkdir", "cat", "less", "git", "vi", "vim",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect()
+};
+const DEFAULT_SHORTCUT_NAME_MIN_WIDTH: fn() -> u16 = || 8;
+const DEFAULT_SHORTCUT_NAME_MAX_WIDTH: fn() -> u16 = || 30;
+
+const DEFAULT_PAGE_OVERLAP: fn() -> u16 = || 1;
+
+const DEFAULT_SHORTCUT_COLORS: fn() -> HashMap<String, String> = HashMap::new;
+const DEFAULT_JUMP_ROWS: fn() -> JumpRows = || JumpRows::Rows(10);
+
+const DEFAULT_NAVIGATION: fn() -> Navigation = || Navigation {
+    jump_rows: DEFAULT_JUMP_ROWS(),
+    page_overlap: DEFAULT_PAGE_OVERLAP(),
+};
+
+const DEFAULT_OPEN_COMMAND: fn() -> String = || {
+    if cfg!(target_os = "macos") {
+        String::from("open {}")
+    } else {
+        String::from("xdg-open {}")
+    }
+};
+
+const DEFAULT_PROFILE: fn() -> String = || String::from(DEFAULT_PROFILE_NAME);
+
+/// The number of rows moved by a jump (shift+up/down): a number of rows, or `half-page`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "JumpRowsValue", into = "JumpRowsValue")]
+pub enum JumpRows {
+    Rows(u16),
+    /// Half of the visible rows, computed at each jump as the terminal may be resized
+    HalfPage,
+}
+
+const HALF_PAGE: &str = "half-page";
+
+/// The value of `jump_rows` in the configuration file
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum JumpRowsValue {
+    Rows(i64),
+    Keyword(String),
+}
+
+impl TryFrom<JumpRowsValue> for JumpRows {
+    type Error = String;
+
+    fn try_from(value: JumpRowsValue) -> Result<Self, Self::Error> {
+        match value {
+            JumpRowsValue::Rows(rows) if rows > 0 && rows <= u16::MAX as i64 => {
+                Ok(JumpRows::Rows(rows as u16))
+            }
+            JumpRowsValue::Keyword(keyword) if keyword == HALF_PAGE => Ok(JumpRows::HalfPage),
+            _ => Err(format!(
+                "jump_rows must be a number of rows greater than 0 or '{}'",
+                HALF_PAGE
+            )),
+        }
+    }
+}
+
+impl From<JumpRows> for JumpRowsValue {
+    fn from(jump_rows: JumpRows) -> Self {
+        match jump_rows {
+            JumpRows::Rows(rows) => JumpRowsValue::Rows(rows as i64),
+            JumpRows::HalfPage => JumpRowsValue::Keyword(String::from(HALF_PAGE)),
+        }
+    }
+}
+
+/// The order of the shortcuts, the initial one being set by the configuration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShortcutSort {
+    #[default]
+    Name,
+    Path,
+    /// The most recently created first
+    Recent,
+}
+
+/// The view shown when the GUI is launched
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartView {
+    History,
+    Shortcuts,
+}
+
+impl StartView {
+    /// The name of the view, as in the configuration
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            StartView::History => "history",
+            StartView::Shortcuts => "shortcuts",
+        }
+    }
+
+    /// Returns the view of a name, if it is one.
+    pub(crate) fn from_name(name: &str) -> Option<StartView> {
+        [StartView::History, StartView::Shortcuts]
+            .into_iter()
+            .find(|view| view.name() == name)
+    }
+}
+
+impl ListOrder for ShortcutSort {
+    fn next(self) -> ShortcutSort {
+        match self {
+            ShortcutSort::Name => ShortcutSort::Path,
+            ShortcutSort::Path => ShortcutSort::Recent,
+            ShortcutSort::Recent => ShortcutSort::Name,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ShortcutSort::Name => "name",
+            ShortcutSort::Path => "path",
+            ShortcutSort::Recent => "recent",
+        }
+    }
+}
+
+impl JumpRows {
+    /// Returns the number of rows of a jump (at least one) in a table of `table_rows` rows.
+    pub fn rows(self, table_rows: u16) -> usize {
+        match self {
+            JumpRows::Rows(rows) => rows as usize,
+            JumpRows::HalfPage => (table_rows / 2).max(1) as usize,
+        }
+    }
+}
+
+/// The navigation settings of the lists
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Navigation {
+    #[serde(default = "DEFAULT_JUMP_ROWS")]
+    pub jump_rows: JumpRows,
+
+    #[serde(default = "DEFAULT_PAGE_OVERLAP")]
+    pub page_overlap: u16,
+}
+
 /// Application configuration structure.
 /// The configuration can be loaded from a YAML file.
 #[derive(Serialize, Deserialize)]
@@ -76,12 +259,120 @@ pub struct Config {
     #[serde(default = "DEFAULT_COLORS")]
     pub inline_theme_light: Theme,
 
+    #[serde(default = "DEFAULT_EMPTY_MESSAGE")]
+    pub empty_message: String,
+
+    #[serde(default = "DEFAULT_RECENT_SEARCHES_MAX")]
+    pub recent_searches_max: usize,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub no_match_bell: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub no_match_flash: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub dim_missing_paths: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub skip_network_paths: bool,
+
+    #[serde(default = "DEFAULT_MIN_QUERY_LEN")]
+    pub min_query_len: usize,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub type_ahead: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub show_visits: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub show_created: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub compact: bool,
+
+    #[serde(default = "DEFAULT_TRUE")]
+    pub show_header: bool,
+
+    #[serde(default = "DEFAULT_TRUE")]
+    pub show_more_hint: bool,
+
+    #[serde(default = "DEFAULT_SEARCH_DEBOUNCE_MS")]
+    pub search_debounce_ms: u64,
+
+    #[serde(default = "DEFAULT_REVISIT_WINDOW_SECONDS")]
+    pub revisit_window_seconds: u64,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub stored_frecency: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub normalize_unicode: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub exact_shortcut_match: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub lenient_shortcut_lookup: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub fuzzy_search: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub confirm_default_yes: bool,
+
+    #[serde(default = "DEFAULT_SHORTCUT_SORT")]
+    pub shortcut_sort: ShortcutSort,
+
+    /// The view shown at launch, otherwise the one the GUI was last quit from
+    #[serde(default = "DEFAULT_START_VIEW")]
+    pub start_view: Option<StartView>,
+
+    #[serde(default = "DEFAULT_RESERVED_SHORTCUT_NAMES")]
+    pub reserved_shortcut_names: Vec<String>,
+
+    #[serde(default = "DEFAULT_TRUE")]
+    pub shared_filter: bool,
+
+    #[serde(default = "DEFAULT_TRUE")]
+    pub substitute_home: bool,
+
+    #[serde(default = "DEFAULT_TRUE")]
+    pub shorten_paths: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub remember_toggles: bool,
+
+    #[serde(default = "DEFAULT_FALSE")]
+    pub print_abbreviated: bool,
+
+    #[serde(default = "DEFAULT_SHORTCUT_NAME_MIN_WIDTH")]
+    pub shortcut_name_min_width: u16,
+
+    #[serde(default = "DEFAULT_SHORTCUT_NAME_MAX_WIDTH")]
+    pub shortcut_name_max_width: u16,
+
+    /// The colors of the names of some shortcuts, by shortcut name
+    #[serde(default = "DEFAULT_SHORTCUT_COLORS")]
+    pub shortcut_colors: HashMap<String, String>,
+
+    #[serde(default = "DEFAULT_NAVIGATION")]
+    pub navigation: Navigation,
+
+    #[serde(default = "DEFAULT_OPEN_COMMAND")]
+    pub open_command: String,
+
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     pub styles: ThemeStyles,
 
     #[serde(skip, default = "DEFAULT_DATE_FORMATER")]
     pub date_formater: Box<dyn Fn(i64) -> String>,
+
+    /// The active profile, selected at launch
+    #[serde(skip, default = "DEFAULT_PROFILE")]
+    pub profile: String,
 }
 
 // Not really true, but good enough for our use case as it is the case after initialization (immutable config)
@@ -99,8 +390,8 @@ impl Config {
         }
     }
 
-    fn build_default_config_path() -> PathBuf {
-        let mut path = dirs::home_dir().unwrap();
+    pub(crate) fn build_default_config_path() -> PathBuf {
+        let mut path = user_dir(dirs::home_dir());
         path.push(".config");
         path.push("cdir");
         path.push("config.yaml");
@@ -111,7 +402,7 @@ impl Config {
         let path = Self::build_config_file_path(config_file_path);
 
         if !path.exists() {
-            Self::initialize(path.clone());
+            Self::initialize(path.clone())?;
             Self::install_themes(path.clone());
         }
 
@@ -123,11 +414,57 @@ impl Config {
         }
     }
 
+    /// Selects the profile, i.e. the database holding the history and the shortcuts.
+    /// The profile is given on the command line, else by the `CDIR_PROFILE` environment
+    /// variable, else it is the default one.
+    /// The default profile uses `db_path`; another profile uses the `<profile>.db` file of the
+    /// same directory.
+    ///
+    /// ### Parameters
+    /// - `profile`: the profile given on the command line, if any
+    ///
+    /// ### Returns
+    /// An error if the profile name is not valid.
+    pub fn select_profile(self: &mut Config, profile: Option<String>) -> Result<&Config, String> {
+        let profile = match profile {
+            Some(profile) => profile,
+            None => env::var(CDIR_PROFILE_VAR).unwrap_or_else(|_| DEFAULT_PROFILE()),
+        };
+        debug!("profile={}", profile);
+        if profile != DEFAULT_PROFILE_NAME {
+            self.db_path = Some(Self::build_profile_db_path(
+                self.db_path.as_deref(),
+                &profile,
+            )?);
+        }
+        self.profile = profile;
+        Ok(self)
+    }
+
+    fn build_profile_db_path(
+        db_path: Option<&std::path::Path>,
+        profile: &str,
+    ) -> Result<PathBuf, String> {
+        if profile.is_empty()
+            || profile.starts_with('.')
+            || profile.contains(std::path::is_separator)
+        {
+            return Err(format!("Invalid profile name '{}'", profile));
+        }
+        let mut path = match db_path.and_then(|db_path| db_path.parent()) {
+            Some(dir) => dir.to_path_buf(),
+            None => dirs::data_dir().ok_or(NO_HOME_ERROR)?.join("cdir"),
+        };
+        path.push(format!("{}.db", profile));
+        Ok(path)
+    }
+
     pub fn process(self: &mut Config) -> &Config {
         let actual_theme = Self::process_themes(self);
 
         // compute the styles fom the current inline_theme
         self.styles = ThemeStyles::from(&actual_theme);
+        self.styles.set_shortcut_colors(&self.shortcut_colors);
 
         let date_format = self.date_format.clone();
         self.date_formater = Box::from(move |s: i64| {
@@ -157,14 +494,18 @@ impl Config {
             match dl {
                 dark_light::Mode::Dark => {
                     trace!("dark mode detected");
-                    if let Some(theme) = config.load_theme(config.theme_dark.as_ref().unwrap()) {
+                    if let Some(theme_dark) = config.theme_dark.as_ref()
+                        && let Some(theme) = config.load_theme(theme_dark)
+                    {
                         external_theme = theme.merge(&external_theme);
                     }
                     actual_theme = config.inline_theme_dark.merge(&external_theme);
                 }
                 dark_light::Mode::Light => {
                     trace!("light mode detected");
-                    if let Some(theme) = config.load_theme(config.theme_light.as_ref().unwrap()) {
+                    if let Some(theme_light) = config.theme_light.as_ref()
+                        && let Some(theme) = config.load_theme(theme_light)
+                    {
                         external_theme = theme.merge(&external_theme);
                     }
                     actual_theme = config.inline_theme_light.merge(&external_theme);
@@ -191,7 +532,7 @@ impl Config {
         config.inline_theme.merge(&external_theme)
     }
 
-    fn initialize(config_file_path: PathBuf) {
+    fn initialize(config_file_path: PathBuf) -> Result<(), String> {
         if config_file_path.exists() {
             panic!(
                 "Error: Configuration file {:?} already exists",
@@ -206,8 +547,9 @@ impl Config {
             config_file_path
         );
 
+        let home_dir = dirs::home_dir().ok_or(NO_HOME_ERROR)?;
         let config_dir = config_file_path.parent().unwrap();
-        let data_dir = dirs::data_dir().unwrap().join("cdir");
+        let data_dir = dirs::data_dir().ok_or(NO_HOME_ERROR)?.join("cdir");
 
         // ensure the data directory exists
         println!("→ Creating data directory {:?}", data_dir);
@@ -244,7 +586,7 @@ impl Config {
         }
 
         // create the .cdirsh file in the home directory
-        let cdirsh_path = dirs::home_dir().unwrap().join(".cdirsh");
+        let cdirsh_path = home_dir.join(".cdirsh");
         let mut cdirsh_content =
             String::from("# cdir shell configuration\n# Do not edit this file manually.\n");
         // get the path to the current binary and add it to the PATH
@@ -271,7 +613,7 @@ impl Config {
 
         // Ensure .cdirsh is sourced in .bashrc and .zshrc
         for shellrc_name in [".bashrc", ".zshrc"] {
-            let shellrc = dirs::home_dir().unwrap().join(shellrc_name);
+            let shellrc = home_dir.join(shellrc_name);
             let source_line = format!("source {}\n", cdirsh_path.to_str().unwrap());
             let mut needs_source = false;
             if shellrc.exists() {
@@ -296,6 +638,7 @@ impl Config {
         println!(
             "✓ Configuration is ready. Please restart your shell or run 'source ~/.cdirsh' to apply the changes."
         );
+        Ok(())
     }
 
     fn load_theme(&self, theme: &str) -> Option<Theme> {
@@ -393,6 +736,41 @@ impl Default for Config {
             db_path: Default::default(),
             log_config_path: Default::default(),
             date_format: Default::default(),
+            empty_message: DEFAULT_EMPTY_MESSAGE(),
+            recent_searches_max: DEFAULT_RECENT_SEARCHES_MAX(),
+            no_match_bell: DEFAULT_FALSE(),
+            no_match_flash: DEFAULT_FALSE(),
+            dim_missing_paths: DEFAULT_FALSE(),
+            skip_network_paths: DEFAULT_FALSE(),
+            min_query_len: DEFAULT_MIN_QUERY_LEN(),
+            type_ahead: DEFAULT_FALSE(),
+            show_visits: DEFAULT_FALSE(),
+            show_created: DEFAULT_FALSE(),
+            compact: DEFAULT_FALSE(),
+            show_header: DEFAULT_TRUE(),
+            show_more_hint: DEFAULT_TRUE(),
+            search_debounce_ms: DEFAULT_SEARCH_DEBOUNCE_MS(),
+            revisit_window_seconds: DEFAULT_REVISIT_WINDOW_SECONDS(),
+            stored_frecency: DEFAULT_FALSE(),
+            normalize_unicode: DEFAULT_FALSE(),
+            exact_shortcut_match: DEFAULT_FALSE(),
+            lenient_shortcut_lookup: DEFAULT_FALSE(),
+            fuzzy_search: DEFAULT_FALSE(),
+            confirm_default_yes: DEFAULT_FALSE(),
+            shortcut_sort: DEFAULT_SHORTCUT_SORT(),
+            start_view: DEFAULT_START_VIEW(),
+            reserved_shortcut_names: DEFAULT_RESERVED_SHORTCUT_NAMES(),
+            shared_filter: DEFAULT_TRUE(),
+            substitute_home: DEFAULT_TRUE(),
+            shorten_paths: DEFAULT_TRUE(),
+            remember_toggles: DEFAULT_FALSE(),
+            print_abbreviated: DEFAULT_FALSE(),
+            shortcut_name_min_width: DEFAULT_SHORTCUT_NAME_MIN_WIDTH(),
+            shortcut_name_max_width: DEFAULT_SHORTCUT_NAME_MAX_WIDTH(),
+            shortcut_colors: DEFAULT_SHORTCUT_COLORS(),
+            navigation: DEFAULT_NAVIGATION(),
+            open_command: DEFAULT_OPEN_COMMAND(),
+            profile: DEFAULT_PROFILE(),
         }
     }
 }
@@ -412,8 +790,160 @@ impl Clone for Config {
             db_path: self.db_path.clone(),
             log_config_path: self.log_config_path.clone(),
             date_format: self.date_format.clone(),
+            empty_message: self.empty_message.clone(),
+            recent_searches_max: self.recent_searches_max,
+            no_match_bell: self.no_match_bell,
+            no_match_flash: self.no_match_flash,
+            dim_missing_paths: self.dim_missing_paths,
+            skip_network_paths: self.skip_network_paths,
+            min_query_len: self.min_query_len,
+            type_ahead: self.type_ahead,
+            show_visits: self.show_visits,
+            show_created: self.show_created,
+            compact: self.compact,
+            show_header: self.show_header,
+            show_more_hint: self.show_more_hint,
+            search_debounce_ms: self.search_debounce_ms,
+            revisit_window_seconds: self.revisit_window_seconds,
+            stored_frecency: self.stored_frecency,
+            normalize_unicode: self.normalize_unicode,
+            exact_shortcut_match: self.exact_shortcut_match,
+            lenient_shortcut_lookup: self.lenient_shortcut_lookup,
+            fuzzy_search: self.fuzzy_search,
+            confirm_default_yes: self.confirm_default_yes,
+            shortcut_sort: self.shortcut_sort,
+            start_view: self.start_view,
+            reserved_shortcut_names: self.reserved_shortcut_names.clone(),
+            shared_filter: self.shared_filter,
+            substitute_home: self.substitute_home,
+            shorten_paths: self.shorten_paths,
+            remember_toggles: self.remember_toggles,
+            print_abbreviated: self.print_abbreviated,
+            shortcut_name_min_width: self.shortcut_name_min_width,
+            shortcut_name_max_width: self.shortcut_name_max_width,
+            shortcut_colors: self.shortcut_colors.clone(),
+            navigation: self.navigation.clone(),
+            open_command: self.open_command.clone(),
+            profile: self.profile.clone(),
             // Provide a new default closure for date_formater
             date_formater: Box::new(|date| date.to_string()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+
+    #[test]
+    fn test_profile_db_path() {
+        let db_path = PathBuf::from("/data/cdir/cdir.db");
+        assert_eq!(
+            Config::build_profile_db_path(Some(&db_path), "work").unwrap(),
+            PathBuf::from("/data/cdir/work.db")
+        );
+        assert_eq!(
+            Config::build_profile_db_path(None, "work").unwrap(),
+            dirs::data_dir().unwrap().join("cdir").join("work.db")
+        );
+        for profile in ["", "..", "../work", "a/b"] {
+            assert!(Config::build_profile_db_path(Some(&db_path), profile).is_err());
+        }
+
+        // the default profile keeps the configured database
+        let mut config = Config {
+            db_path: Some(db_path.clone()),
+            ..Config::default()
+        };
+        config
+            .select_profile(Some(DEFAULT_PROFILE_NAME.to_string()))
+            .unwrap();
+        assert_eq!(config.db_path, Some(db_path));
+
+        config.select_profile(Some("personal".to_string())).unwrap();
+        assert_eq!(config.profile, "personal");
+        assert_eq!(
+            config.db_path,
+            Some(PathBuf::from("/data/cdir/personal.db"))
+        );
+    }
+
+    #[test]
+    fn test_profiles_do_not_share_rows() {
+        let dir = env::temp_dir().join(format!("cdir-profiles-{}", std::process::id()));
+        let db_path = dir.join("cdir.db");
+        let store_of = |profile: &str| {
+            let mut config = Config {
+                db_path: Some(db_path.clone()),
+                ..Config::default()
+            };
+            config.select_profile(Some(profile.to_string())).unwrap();
+            Store::new(config.db_path.as_ref().unwrap())
+        };
+
+        let work = store_of("work");
+        let personal = store_of("personal");
+        work.add_path("/work/project").unwrap();
+        personal.add_path("/home/photos").unwrap();
+
+        let paths = |store: &Store| -> Vec<String> {
+            store
+                .list_paths(0, 10, "", false, false)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.path)
+                .collect()
+        };
+        assert_eq!(paths(&work), vec!["/work/project"]);
+        assert_eq!(paths(&personal), vec!["/home/photos"]);
+
+        drop(work);
+        drop(personal);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_shortcut_sort() {
+        let config: Config = serde_yaml::from_str("shortcut_sort: recent").unwrap();
+        assert_eq!(config.shortcut_sort, ShortcutSort::Recent);
+        let config: Config = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config.shortcut_sort, ShortcutSort::Name);
+        assert!(serde_yaml::from_str::<Config>("shortcut_sort: size").is_err());
+    }
+
+    #[test]
+    fn test_start_view() {
+        let config: Config = serde_yaml::from_str("start_view: shortcuts").unwrap();
+        assert_eq!(config.start_view, Some(StartView::Shortcuts));
+        let config: Config = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config.start_view, None);
+        assert_eq!(StartView::from_name("history"), Some(StartView::History));
+        assert_eq!(
+            StartView::from_name(StartView::Shortcuts.name()),
+            Some(StartView::Shortcuts)
+        );
+        assert_eq!(StartView::from_name("help"), None);
+    }
+
+    #[test]
+    fn test_navigation() {
+        let navigation: Navigation = serde_yaml::from_str("jump_rows: half-page").unwrap();
+        assert_eq!(navigation.jump_rows, JumpRows::HalfPage);
+        assert_eq!(navigation.page_overlap, 1);
+        assert_eq!(navigation.jump_rows.rows(21), 10);
+        assert_eq!(navigation.jump_rows.rows(1), 1);
+
+        let navigation: Navigation = serde_yaml::from_str("jump_rows: 5\npage_overlap: 0").unwrap();
+        assert_eq!(navigation.jump_rows, JumpRows::Rows(5));
+        assert_eq!(navigation.page_overlap, 0);
+        assert_eq!(navigation.jump_rows.rows(21), 5);
+
+        let navigation: Navigation = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(navigation.jump_rows, JumpRows::Rows(10));
+
+        for value in ["0", "-3", "foo"] {
+            assert!(serde_yaml::from_str::<Navigation>(&format!("jump_rows: {}", value)).is_err());
+        }
+    }
+}
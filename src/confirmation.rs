@@ -11,6 +11,8 @@ use crate::{
     tui::{EventCaptured, ManagerAction, View, ViewBuilder, ViewManager},
 };
 
+/// A yes/no question in a centered box: `y` or `n` answer it, Enter answers the highlighted
+/// button, and Esc answers no. The caller reads the answer in the close callback.
 pub struct Confirmation {
     styles: ThemeStyles,
     pub message: String,
@@ -18,23 +20,51 @@ pub struct Confirmation {
     result: bool,
 }
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
 enum ConfirmationButton {
     Yes,
-    Cancel,
+    No,
 }
 
 impl Confirmation {
-    pub fn builder(message: String, styles: ThemeStyles) -> ViewBuilder {
-        ViewBuilder::from(Box::new(Self {
+    /// Builds a confirmation.
+    ///
+    /// ### Parameters
+    /// - `message`: the question, on one or several lines
+    /// - `default_yes`: whether the button highlighted first is Yes, rather than the safe No
+    ///   (see the `confirm_default_yes` option)
+    /// - `styles`: the styles of the dialog, the highlighted button having the accent color
+    pub fn builder(message: String, default_yes: bool, styles: ThemeStyles) -> ViewBuilder {
+        ViewBuilder::from(Box::new(Self::new(message, default_yes, styles)))
+    }
+
+    fn new(message: String, default_yes: bool, styles: ThemeStyles) -> Self {
+        Self {
             styles,
             message,
-            selected: ConfirmationButton::Yes,
+            selected: if default_yes {
+                ConfirmationButton::Yes
+            } else {
+                ConfirmationButton::No
+            },
             result: false,
-        }))
+        }
     }
 
     pub fn is_yes(&self) -> bool { self.result }
+
+    /// The style of a button: the highlighted one is reversed in the accent color.
+    fn button_style(&self, button: ConfirmationButton) -> Style {
+        let accent = self.styles.accent_color.unwrap_or(Color::Reset);
+        if self.selected == button {
+            Style::default()
+                .fg(Color::Black)
+                .bg(accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(accent)
+        }
+    }
 }
 
 impl View for Confirmation {
@@ -70,8 +100,8 @@ impl View for Confirmation {
         let inner = ratatui::layout::Rect {
             x: modal_area.x + 1,
             y: modal_area.y + 1,
-            width: modal_area.width - 2,
-            height: modal_area.height - 2,
+            width: modal_area.width.saturating_sub(2),
+            height: modal_area.height.saturating_sub(2),
         };
         let vchunks = Layout::default()
             .direction(Direction::Vertical)
@@ -106,30 +136,14 @@ impl View for Confirmation {
                 Constraint::Length(10),
             ])
             .split(vchunks[2]);
-        let yes_style = if self.selected == ConfirmationButton::Yes {
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Green)
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default().fg(Color::Green)
-        };
-        let cancel_style = if self.selected == ConfirmationButton::Cancel {
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Red)
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default().fg(Color::Red)
-        };
         let yes = Paragraph::new(" Yes ")
-            .style(yes_style)
+            .style(self.button_style(ConfirmationButton::Yes))
             .alignment(ratatui::layout::Alignment::Center);
-        let cancel = Paragraph::new(" Cancel ")
-            .style(cancel_style)
+        let no = Paragraph::new(" No ")
+            .style(self.button_style(ConfirmationButton::No))
             .alignment(ratatui::layout::Alignment::Center);
         frame.render_widget(yes, button_layout[0]);
-        frame.render_widget(cancel, button_layout[2]);
+        frame.render_widget(no, button_layout[2]);
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) -> (EventCaptured, ManagerAction) {
@@ -139,8 +153,8 @@ impl View for Confirmation {
             KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
                 // Toggle selection
                 self.selected = match self.selected {
-                    ConfirmationButton::Yes => ConfirmationButton::Cancel,
-                    ConfirmationButton::Cancel => ConfirmationButton::Yes,
+                    ConfirmationButton::Yes => ConfirmationButton::No,
+                    ConfirmationButton::No => ConfirmationButton::Yes,
                 };
                 redraw = true;
             }
@@ -148,6 +162,14 @@ impl View for Confirmation {
                 self.result = self.selected == ConfirmationButton::Yes;
                 close = true;
             }
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                self.result = true;
+                close = true;
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                self.result = false;
+                close = true;
+            }
             _ => {}
         }
         (
@@ -156,3 +178,103 @@ impl View for Confirmation {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+
+    fn press(confirmation: &mut Confirmation, code: KeyCode) -> bool {
+        let (_, action) = confirmation.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+        action.close()
+    }
+
+    /// Draws the confirmation on a screen, returning the screen to check the cells
+    fn render(confirmation: &mut Confirmation) -> Terminal<TestBackend> {
+        let mut terminal = Terminal::new(TestBackend::new(40, 9)).unwrap();
+        terminal
+            .draw(|frame| confirmation.draw(frame, frame.area(), true))
+            .unwrap();
+        terminal
+    }
+
+    /// The text of a line of the screen
+    fn line(terminal: &Terminal<TestBackend>, y: u16) -> String {
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.width)
+            .map(|x| buffer[(x, y)].symbol())
+            .collect::<String>()
+    }
+
+    /// The position of a text on the screen
+    fn find(terminal: &Terminal<TestBackend>, text: &str) -> Option<(u16, u16)> {
+        (0..terminal.backend().buffer().area.height).find_map(|y| {
+            let line = line(terminal, y);
+            line.find(text)
+                .map(|byte| (line[..byte].chars().count() as u16, y))
+        })
+    }
+
+    fn styles() -> ThemeStyles {
+        ThemeStyles {
+            accent_color: Some(Color::Magenta),
+            ..ThemeStyles::default()
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut confirmation =
+            Confirmation::new(String::from("Deletion of?\n/tmp/a"), false, styles());
+        let terminal = render(&mut confirmation);
+        assert!(find(&terminal, "Confirmation").is_some());
+        assert!(find(&terminal, "Deletion of?").is_some());
+        assert!(find(&terminal, "/tmp/a").is_some());
+
+        // the safe answer is highlighted with the accent color
+        let buffer = terminal.backend().buffer();
+        let (x, y) = find(&terminal, "No").unwrap();
+        assert_eq!(buffer[(x, y)].bg, Color::Magenta);
+        let (x, y) = find(&terminal, "Yes").unwrap();
+        assert_eq!(buffer[(x, y)].fg, Color::Magenta);
+        assert_ne!(buffer[(x, y)].bg, Color::Magenta);
+
+        // once switched, Yes is highlighted
+        press(&mut confirmation, KeyCode::Tab);
+        let terminal = render(&mut confirmation);
+        let (x, y) = find(&terminal, "Yes").unwrap();
+        assert_eq!(terminal.backend().buffer()[(x, y)].bg, Color::Magenta);
+    }
+
+    #[test]
+    fn test_default_answer() {
+        let mut confirmation = Confirmation::new(String::from("?"), false, styles());
+        assert!(press(&mut confirmation, KeyCode::Enter));
+        assert!(!confirmation.is_yes());
+
+        let mut confirmation = Confirmation::new(String::from("?"), true, styles());
+        assert!(press(&mut confirmation, KeyCode::Enter));
+        assert!(confirmation.is_yes());
+    }
+
+    #[test]
+    fn test_keys() {
+        let answer = |default_yes, code| {
+            let mut confirmation = Confirmation::new(String::from("?"), default_yes, styles());
+            assert!(press(&mut confirmation, code));
+            confirmation.is_yes()
+        };
+        assert!(answer(false, KeyCode::Char('y')));
+        assert!(!answer(true, KeyCode::Char('n')));
+        assert!(!answer(true, KeyCode::Esc));
+
+        // switching the button doesn't answer
+        let mut confirmation = Confirmation::new(String::from("?"), false, styles());
+        assert!(!press(&mut confirmation, KeyCode::Left));
+        assert!(!press(&mut confirmation, KeyCode::Char('x')));
+        assert!(press(&mut confirmation, KeyCode::Char(' ')));
+        assert!(confirmation.is_yes());
+    }
+}
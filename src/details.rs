@@ -0,0 +1,125 @@
+use crossterm::event::KeyEvent;
+use log::debug;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::{
+    theme::ThemeStyles,
+    tui::{EventCaptured, ManagerAction, View, ViewBuilder, ViewManager, overlay::clear_overlay},
+};
+
+// the columns left around the popup
+const DETAILS_MARGIN: u16 = 2;
+
+/// A popup showing the complete fields of an entry (e.g. a path too long for the list),
+/// wrapped over the whole width of the screen. Any key closes it.
+pub struct Details {
+    styles: ThemeStyles,
+    // the label and the value of each field
+    fields: Vec<(String, String)>,
+}
+
+impl Details {
+    pub fn builder(fields: Vec<(String, String)>, styles: ThemeStyles) -> ViewBuilder {
+        ViewBuilder::from(Box::new(Self { styles, fields }))
+    }
+
+    /// Returns the number of lines needed to display the fields, wrapped to a width.
+    fn wrapped_height(&self, width: u16) -> u16 {
+        let width = width.max(1) as usize;
+        self.fields
+            .iter()
+            .map(|(label, value)| {
+                let len = label.chars().count() + 2 + value.chars().count();
+                match len.div_ceil(width) {
+                    0 | 1 => 1,
+                    // the words moved to the next line may need one more
+                    lines => lines as u16 + 1,
+                }
+            })
+            .sum()
+    }
+}
+
+impl View for Details {
+    fn handle_key_event(&mut self, _key_event: KeyEvent) -> (EventCaptured, ManagerAction) {
+        (
+            EventCaptured::Yes,
+            ManagerAction::new(false).with_close(true),
+        )
+    }
+
+    fn draw(&mut self, frame: &mut ratatui::Frame, _area: Rect, _active: bool) {
+        debug!("Drawing details");
+
+        let area = frame.area();
+        let modal_width = area.width.saturating_sub(2 * DETAILS_MARGIN);
+        // borders included
+        let modal_height =
+            (self.wrapped_height(modal_width.saturating_sub(2)) + 2).min(area.height);
+        let modal_area = ViewManager::centered_rect(area, modal_width, modal_height);
+
+        clear_overlay(frame, modal_area, self.styles.background_color);
+
+        let lines: Vec<Line> = self
+            .fields
+            .iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{}: ", label), self.styles.text_em_style),
+                    Span::styled(value.clone(), self.styles.text_style),
+                ])
+            })
+            .collect();
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .title(Span::styled(" details ", self.styles.title_style))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.styles.border_color.unwrap())),
+        );
+        frame.render_widget(paragraph, modal_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_long_path_is_wrapped() {
+        let path = format!("/{}", "very_long_directory/".repeat(5));
+        let mut details = Details {
+            styles: Config::default().styles,
+            fields: vec![
+                (String::from("path"), path.clone()),
+                (String::from("visits"), String::from("3")),
+            ],
+        };
+        let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+        terminal
+            .draw(|frame| details.draw(frame, frame.area(), true))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let content: String = (0..12)
+            .map(|y| {
+                (0..40)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .replace('│', "")
+                    .trim()
+                    .to_string()
+            })
+            .collect();
+
+        // the whole path is displayed
+        assert!(content.contains(&path));
+        assert!(content.contains("visits: 3"));
+    }
+}
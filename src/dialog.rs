@@ -0,0 +1,253 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use log::debug;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use tui_textarea::{Input, TextArea};
+
+use crate::{
+    theme::ThemeStyles,
+    tui::{EventCaptured, ManagerAction, View, ViewBuilder, ViewManager},
+};
+
+// The width of the dialog, shrunk to the width of the screen
+const DIALOG_WIDTH: u16 = 64;
+
+// The height of the dialog: its border, the input with its border, and the message
+const DIALOG_HEIGHT: u16 = 6;
+
+/// A function type that validates the text typed in an input dialog: the value returned to the
+/// caller, or the message shown under the input
+pub type ValidateFn = Box<dyn Fn(&str) -> Result<String, String>>;
+
+/// A one-line text prompt in a centered box: Enter validates the text and closes the dialog if
+/// it is valid, Esc closes it without value. The caller reads the value in the close callback.
+pub struct InputDialog {
+    styles: ThemeStyles,
+    title: String,
+    label: String,
+    textarea: TextArea<'static>,
+    validate: ValidateFn,
+    /// the reason why the text was rejected
+    error: Option<String>,
+    value: Option<String>,
+}
+
+impl InputDialog {
+    /// Builds an input dialog.
+    ///
+    /// ### Parameters
+    /// - `title`: the title of the dialog
+    /// - `label`: the label of the input
+    /// - `initial`: the text the input is prefilled with
+    /// - `validate`: the function validating the text when Enter is pressed
+    /// - `styles`: the styles of the dialog
+    pub fn builder(
+        title: &str,
+        label: &str,
+        initial: &str,
+        validate: ValidateFn,
+        styles: ThemeStyles,
+    ) -> ViewBuilder {
+        ViewBuilder::from(Box::new(Self::new(title, label, initial, validate, styles)))
+    }
+
+    pub(crate) fn new(
+        title: &str,
+        label: &str,
+        initial: &str,
+        validate: ValidateFn,
+        styles: ThemeStyles,
+    ) -> Self {
+        let mut textarea = TextArea::default();
+        textarea.set_cursor_line_style(styles.text_style);
+        textarea.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        textarea.insert_str(initial);
+        Self {
+            styles,
+            title: title.to_string(),
+            label: label.to_string(),
+            textarea,
+            validate,
+            error: None,
+            value: None,
+        }
+    }
+
+    /// The validated value, or `None` if the dialog was cancelled.
+    pub fn value(&self) -> Option<&str> { self.value.as_deref() }
+
+    fn text(&self) -> &str {
+        self.textarea
+            .lines()
+            .first()
+            .map(|line| line.as_str())
+            .unwrap_or("")
+    }
+}
+
+impl View for InputDialog {
+    fn draw(&mut self, frame: &mut Frame, _area: Rect, _active: bool) {
+        debug!("Drawing input dialog");
+        let modal_area = ViewManager::centered_rect(frame.area(), DIALOG_WIDTH, DIALOG_HEIGHT);
+
+        frame.render_widget(Clear, modal_area);
+        if let Some(bg_color) = &self.styles.background_color {
+            let background = Paragraph::new("").style(Style::default().bg(*bg_color));
+            frame.render_widget(background, modal_area);
+        }
+
+        let border_style = Style::default().fg(self.styles.border_color.unwrap_or(Color::Reset));
+        let block = Block::default()
+            .title(self.title.as_str())
+            .title_style(self.styles.title_style)
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .style(self.styles.text_style);
+        let inner = block.inner(modal_area);
+        frame.render_widget(block, modal_area);
+
+        let chunks = Layout::vertical([
+            Constraint::Length(3), // input
+            Constraint::Length(1), // error
+        ])
+        .split(inner);
+
+        self.textarea.set_block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(self.label.clone())
+                .title_style(self.styles.text_style)
+                .border_style(border_style),
+        );
+        frame.render_widget(&self.textarea, chunks[0]);
+        if let Some(error) = self.error.as_ref() {
+            let error = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
+            frame.render_widget(error, chunks[1]);
+        }
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> (EventCaptured, ManagerAction) {
+        debug!("Handling key event: {:?}", key_event);
+        let mut close = false;
+        match key_event.code {
+            KeyCode::Esc => {
+                self.value = None;
+                close = true;
+            }
+            KeyCode::Enter => match (self.validate)(self.text()) {
+                // the dialog stays open until the text is valid
+                Ok(value) => {
+                    self.value = Some(value);
+                    close = true;
+                }
+                Err(message) => self.error = Some(message),
+            },
+            // a single line
+            KeyCode::Tab | KeyCode::BackTab => {}
+            _ => {
+                self.textarea.input(Input::from(key_event));
+                self.error = None;
+            }
+        }
+        (
+            EventCaptured::Yes,
+            ManagerAction::new(true).with_close(close),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::config::Config;
+
+    /// Draws the dialog on a screen, returning its lines
+    fn render(dialog: &mut InputDialog) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(70, 8)).unwrap();
+        terminal
+            .draw(|frame| dialog.draw(frame, frame.area(), true))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    fn press(dialog: &mut InputDialog, code: KeyCode) -> bool {
+        let (_, action) = dialog.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+        action.close()
+    }
+
+    fn dialog(initial: &str) -> InputDialog {
+        InputDialog::new(
+            "New shortcut",
+            "Name",
+            initial,
+            Box::new(|text: &str| match text.trim() {
+                "" => Err(String::from("the name is empty")),
+                name => Ok(name.to_string()),
+            }),
+            Config::default().styles,
+        )
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut dialog = dialog("src");
+        assert_eq!(dialog.text(), "src");
+        press(&mut dialog, KeyCode::Char('2'));
+        assert!(press(&mut dialog, KeyCode::Enter));
+        assert_eq!(dialog.value(), Some("src2"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        // an invalid text keeps the dialog open with the reason
+        let mut dialog = dialog(" ");
+        assert!(!press(&mut dialog, KeyCode::Enter));
+        assert_eq!(dialog.error.as_deref(), Some("the name is empty"));
+        assert_eq!(dialog.value(), None);
+
+        // typing clears the reason
+        press(&mut dialog, KeyCode::Char('a'));
+        assert_eq!(dialog.error, None);
+        assert!(press(&mut dialog, KeyCode::Enter));
+        assert_eq!(dialog.value(), Some("a"));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut dialog = dialog("src");
+        let lines = render(&mut dialog);
+        assert!(lines[1].contains("New shortcut"), "{:?}", lines);
+        assert!(lines[2].contains("Name"), "{:?}", lines);
+        assert!(lines[3].contains("│src"), "{:?}", lines);
+        assert!(!lines[5].contains("empty"), "{:?}", lines);
+
+        // the reason of the rejection is shown under the input
+        for _ in 0..3 {
+            press(&mut dialog, KeyCode::Backspace);
+        }
+        press(&mut dialog, KeyCode::Enter);
+        let lines = render(&mut dialog);
+        assert!(lines[5].contains("the name is empty"), "{:?}", lines);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut dialog = dialog("src");
+        assert!(press(&mut dialog, KeyCode::Esc));
+        assert_eq!(dialog.value(), None);
+    }
+}
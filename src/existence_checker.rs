@@ -0,0 +1,170 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use log::{debug, error};
+use tokio::sync::broadcast;
+
+use crate::{
+    mounts::Mounts,
+    tui::{GenericEvent, event::ViewManagerEvent},
+};
+
+/// The existence of a path on the file system, as known by the checker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Existence {
+    /// Not checked yet (or being checked)
+    Unknown,
+    Exists,
+    Missing,
+    /// On a network file system, not checked (see `skip_network`)
+    Network,
+}
+
+/// Checks the existence of paths on a background thread, so that the UI is not slowed down
+/// by the file system (e.g. a slow network mount).
+/// The results are cached, and a redraw is requested each time a batch of checks completes.
+pub(crate) struct ExistenceChecker {
+    tx: broadcast::Sender<GenericEvent>,
+    states: Arc<Mutex<HashMap<String, Existence>>>,
+    network_mounts: Option<Mounts>,
+}
+
+impl ExistenceChecker {
+    pub(crate) fn new(tx: broadcast::Sender<GenericEvent>) -> ExistenceChecker {
+        ExistenceChecker {
+            tx,
+            states: Arc::new(Mutex::new(HashMap::new())),
+            network_mounts: None,
+        }
+    }
+
+    /// Skips the paths on network file systems, which may be slow or hang when unreachable:
+    /// they are `Existence::Network` without being checked.
+    ///
+    /// ### Parameters
+    /// - `mounts`: the mounted file systems, telling the network paths
+    pub(crate) fn skip_network(mut self, mounts: Mounts) -> ExistenceChecker {
+        self.network_mounts = Some(mounts);
+        self
+    }
+
+    /// Returns what is currently known about the existence of a path.
+    pub(crate) fn existence(&self, path: &str) -> Existence {
+        self.states
+            .lock()
+            .unwrap()
+            .get(path)
+            .copied()
+            .unwrap_or(Existence::Unknown)
+    }
+
+    /// Starts checking, off-thread, the paths which were never checked.
+    /// Returns immediately: the results are available through `existence` once the redraw
+    /// event is received.
+    ///
+    /// ### Parameters
+    /// - `paths`: the paths to check (typically the visible ones)
+    pub(crate) fn check<'a>(&self, paths: impl IntoIterator<Item = &'a str>) {
+        let to_check: Vec<String> = {
+            let mut states = self.states.lock().unwrap();
+            let mut to_check = vec![];
+            for path in paths {
+                if states.contains_key(path) {
+                    continue;
+                }
+                if let Some(mounts) = &self.network_mounts
+                    && mounts.is_network_path(path)
+                {
+                    states.insert(path.to_string(), Existence::Network);
+                } else {
+                    // registered right away, to be checked only once
+                    states.insert(path.to_string(), Existence::Unknown);
+                    to_check.push(path.to_string());
+                }
+            }
+            to_check
+        };
+        if to_check.is_empty() {
+            return;
+        }
+
+        debug!("checking the existence of {} paths", to_check.len());
+        let states = self.states.clone();
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            for path in to_check {
+                let existence = if Path::new(&path).exists() {
+                    Existence::Exists
+                } else {
+                    Existence::Missing
+                };
+                states.lock().unwrap().insert(path, existence);
+            }
+            if let Err(e) = tx.send(GenericEvent::ViewManagerEvent(ViewManagerEvent::Redraw)) {
+                error!("Failed to send the redraw event: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let (tx, mut rx) = broadcast::channel::<GenericEvent>(16);
+        let checker = ExistenceChecker::new(tx);
+
+        let dir = std::env::temp_dir().join(format!("cdir-existence-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.to_string_lossy().to_string();
+        let missing = dir.join("missing").to_string_lossy().to_string();
+
+        assert_eq!(checker.existence(&existing), Existence::Unknown);
+        checker.check([existing.as_str(), missing.as_str()]);
+
+        // the results come with the redraw event
+        assert!(matches!(
+            rx.blocking_recv(),
+            Ok(GenericEvent::ViewManagerEvent(ViewManagerEvent::Redraw))
+        ));
+        assert_eq!(checker.existence(&existing), Existence::Exists);
+        assert_eq!(checker.existence(&missing), Existence::Missing);
+
+        // already checked paths are not checked again
+        checker.check([existing.as_str(), missing.as_str()]);
+        assert!(rx.try_recv().is_err());
+
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_skip_network() {
+        let (tx, mut rx) = broadcast::channel::<GenericEvent>(16);
+        let mounts =
+            Mounts::parse("/dev/sda1 / ext4 rw 0 0\nserver:/export /net/projects nfs4 rw 0 0\n");
+        let checker = ExistenceChecker::new(tx).skip_network(mounts);
+
+        // known right away, without a check in the background
+        checker.check(["/net/projects/missing"]);
+        assert_eq!(
+            checker.existence("/net/projects/missing"),
+            Existence::Network
+        );
+        assert!(rx.try_recv().is_err());
+
+        // the local paths are still checked
+        let local = std::env::temp_dir().to_string_lossy().to_string();
+        checker.check([local.as_str()]);
+        assert!(matches!(
+            rx.blocking_recv(),
+            Ok(GenericEvent::ViewManagerEvent(ViewManagerEvent::Redraw))
+        ));
+        assert_eq!(checker.existence(&local), Existence::Exists);
+    }
+}
@@ -1,57 +1,173 @@
 use std::{
-    env,
+    cell::{Ref, RefCell},
+    io::{self, IsTerminal, Write},
     rc::Rc,
     sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use log::debug;
+use chrono::{DateTime, Local};
+use log::{debug, warn};
 use ratatui::{
-    layout::Constraint,
-    style::Style,
+    layout::{Alignment, Constraint},
+    style::{Color, Style, Stylize},
     text::{Line, Span},
     widgets::Row,
 };
 
 use crate::{
-    config::Config,
+    config::{Config, StartView},
+    dialog::InputDialog,
+    existence_checker::{Existence, ExistenceChecker},
     help::Help,
     history_view_container::HistoryViewContainer,
+    mounts::Mounts,
+    path_syntax::{
+        WINDOWS, is_absolute, is_separator, recorded_form, strip_directory, trim_separators,
+    },
     search_text_view::SearchTextState,
-    shortcut_editor::ShortcutEditor,
+    shortcut_editor::{ShortcutEditMode, ShortcutEditor},
     shortcut_view_container::ShortcutViewContainer,
     store::{self, Path, Shortcut, Store},
-    tableview::{RowifyFn, TableViewState},
-    tui::{ViewBuilder, ViewManager},
+    tableview::{
+        Action, ActionOutcome, EditMode, EditorViewBuilder, ExactChoiceFn, LastChoiceFn, RefreshFn,
+        RowifyFn, TableViewState, canonical_path, home_directory, printed_path,
+    },
+    tui::{GenericEvent, ViewBuilder, ViewManager, event::ApplicationEvent},
+    unicode::normalize_unicode,
 };
 
 const HISTORY_VIEW_CONTAINER: u16 = 0;
 const SHORTCUT_VIEW_ID: u16 = 1;
 
+const SHORTCUTS_NOT_HIDDEN_MESSAGE: &str = "shortcuts can't be hidden";
+const SHORTCUT_OF_SHORTCUT_MESSAGE: &str = "already a shortcut";
+const PATH_SENT_MESSAGE: &str = "path sent";
+const NO_OUTPUT_MESSAGE: &str = "no output for the paths (see --out-fd)";
+const OUTPUT_FAILED_MESSAGE: &str = "failed to send the path";
+const NOT_A_TERMINAL_MESSAGE: &str =
+    "the GUI needs a terminal: its input and output must not be redirected";
+
+// The date before the path in the compact layout, shorter than the usual one
+const COMPACT_DATE_FORMAT: &str = "%d-%b %H:%M";
+
+// The mark before the path of the current directory in the history
+const CURRENT_DIRECTORY_MARKER: &str = "● ";
+
+// The names of the display toggles remembered between launches, as their options
+const SHORTEN_PATHS_TOGGLE: &str = "shorten_paths";
+const SUBSTITUTE_HOME_TOGGLE: &str = "substitute_home";
+
+// The period of the check for the paths recorded by another shell, in watch mode
+const WATCH_PERIOD: Duration = Duration::from_secs(1);
+
+/// The output of the paths chosen while keeping the GUI open, one per line
+type PathOutput = Rc<RefCell<Box<dyn Write>>>;
+
+/// A function loading all the shortcuts
+type ShortcutsLoader = Box<dyn Fn() -> Result<Vec<Shortcut>, rusqlite::Error>>;
+
+/// The shortcuts substituted in the displayed paths, loaded once rather than at each draw,
+/// and reloaded when they may have changed.
+#[derive(Clone)]
+pub(crate) struct ShortcutsCache {
+    loader: Rc<ShortcutsLoader>,
+    shortcuts: Rc<RefCell<Vec<Shortcut>>>,
+}
+
+impl ShortcutsCache {
+    /// Builds the cache of the shortcuts of a store, loaded right away.
+    pub(crate) fn new(store: &Store) -> ShortcutsCache {
+        let store = store.clone();
+        ShortcutsCache::with_loader(Box::new(move || store.list_all_shortcuts()))
+    }
+
+    fn with_loader(loader: ShortcutsLoader) -> ShortcutsCache {
+        let cache = ShortcutsCache {
+            loader: Rc::new(loader),
+            shortcuts: Rc::new(RefCell::new(vec![])),
+        };
+        cache.reload();
+        cache
+    }
+
+    /// Reloads the shortcuts, e.g. once they were modified. On error, the paths are displayed
+    /// without shortcut names until the next reload.
+    pub(crate) fn reload(&self) {
+        let shortcuts = (self.loader)().unwrap_or_else(|e| {
+            warn!("Failed to load the shortcuts: {}", e);
+            vec![]
+        });
+        self.shortcuts.replace(shortcuts);
+    }
+
+    /// Returns a function reloading the shortcuts, e.g. when a list is refreshed.
+    pub(crate) fn reload_fn(&self) -> RefreshFn {
+        let cache = self.clone();
+        Box::new(move || cache.reload())
+    }
+
+    /// Returns the shortcuts loaded.
+    pub(crate) fn shortcuts(&self) -> Ref<'_, Vec<Shortcut>> { self.shortcuts.borrow() }
+}
+
 /// The main application structure
 pub(crate) struct Gui {
     table_view_state: Arc<Mutex<TableViewState>>,
     history_view_container: Option<ViewBuilder>,
     shortcut_view_container: Option<ViewBuilder>,
+    /// The search of each top level view, by view index: the same one if the filter is shared
+    search_text_states: Vec<Arc<Mutex<SearchTextState>>>,
+    existence_checker: Option<Arc<ExistenceChecker>>,
+    output: Option<PathOutput>,
+    store: Store,
+    /// The shortcuts displayed in the paths of both views
+    shortcuts: ShortcutsCache,
+    /// The view shown at launch
+    start_view: StartView,
+    /// Whether the display toggles are recorded when the GUI is quit
+    remember_toggles: bool,
 }
 
 impl Gui {
-    /// Return a Line with where HOME is replaced by '~'
-    pub(crate) fn reduce_path(path: String, size: u16, home_tild_style: Style) -> Line<'static> {
+    /// Return a Line with where the home directory, if any, is replaced by '~'
+    fn reduce_path_with_home(
+        path: String,
+        home: Option<String>,
+        size: u16,
+        home_tilde_style: Style,
+    ) -> Line<'static> {
         if size == 0 {
             return Line::from("");
         }
 
-        let home = env::var("HOME");
         match home {
-            Ok(home) => {
-                let spm = home.clone() + "/";
-                if path.starts_with(&spm) || path == home.as_str() {
-                    Self::do_reduce_path(&path, home, size, home_tild_style)
+            Some(home) => {
+                if strip_directory(&path, &home, WINDOWS).is_some() {
+                    Self::do_reduce_path(&path, home, size, home_tilde_style)
                 } else {
                     Self::reduce_string(&path, size as usize)
                 }
             }
-            Err(_) => Self::reduce_string(&path, size as usize),
+            None => Self::reduce_string(&path, size as usize),
+        }
+    }
+
+    /// Return a Line with the path reduced to the size, where the home directory, if any, is
+    /// replaced by '~' if `home_tilde` is set
+    pub(crate) fn display_path(
+        path: String,
+        home: Option<String>,
+        size: u16,
+        home_tilde_style: Style,
+        home_tilde: bool,
+    ) -> Line<'static> {
+        if home_tilde {
+            Self::reduce_path_with_home(path, home, size, home_tilde_style)
+        } else if size == 0 {
+            Line::from("")
+        } else {
+            Self::reduce_string(&path, size as usize)
         }
     }
 
@@ -65,21 +181,24 @@ impl Gui {
     }
 
     fn do_reduce_path(
-        path: &String,
+        path: &str,
         home: String,
         size: u16,
-        home_tild_style: Style,
+        home_tilde_style: Style,
     ) -> Line<'static> {
-        if path == &home {
-            return Line::from(Span::from("~").style(home_tild_style));
+        // the path is in the home directory, which may differ by its case on Windows
+        if path.len() == home.len() {
+            return Line::from(Span::from("~").style(home_tilde_style));
         }
+        // the separator after the home directory, `/` or `\` on Windows
+        let separator = path[home.len()..home.len() + 1].to_string();
 
         if size == 1 {
             return Line::from("*");
         } else if size == 2 {
-            return Span::from("~").style(home_tild_style) + Span::from("*");
+            return Span::from("~").style(home_tilde_style) + Span::from("*");
         } else if size == 3 {
-            return Span::from("~").style(home_tild_style) + Span::from("/*");
+            return Span::from("~").style(home_tilde_style) + Span::from(separator + "*");
         }
 
         let path_suffix = &path[home.len() + 1..];
@@ -87,20 +206,78 @@ impl Gui {
         if path_suffix.len() > remaining_size {
             let start_index = path_suffix.len() - remaining_size + 1;
             let path_suffix = format!("*{}", &path_suffix[start_index..]);
-            return Span::from("~").style(home_tild_style)
-                + Span::from("/")
+            return Span::from("~").style(home_tilde_style)
+                + Span::from(separator)
                 + Span::from(path_suffix);
         }
 
-        Span::from("~").style(home_tild_style) + Span::from(path[home.len()..].to_string())
+        Span::from("~").style(home_tilde_style) + Span::from(path[home.len()..].to_string())
+    }
+
+    /// Return the Line with the occurrences of the terms of a search (ignoring ASCII case)
+    /// styled with a style patched over the style of their span
+    ///
+    /// ### Parameters
+    /// - `line`: the line displayed
+    /// - `terms`: the texts to highlight, see `store::highlighted_terms`
+    /// - `match_style`: the style of the occurrences
+    pub(crate) fn highlight_matches(
+        line: Line<'static>,
+        terms: &[String],
+        match_style: Style,
+    ) -> Line<'static> {
+        let terms: Vec<String> = terms
+            .iter()
+            .filter(|term| !term.is_empty())
+            .map(|term| term.to_ascii_lowercase())
+            .collect();
+        if terms.is_empty() {
+            return line;
+        }
+        let mut spans = vec![];
+        for span in line.spans {
+            // the ASCII lowercase keeps the byte offsets of the content
+            let lower = span.content.to_ascii_lowercase();
+            let mut occurrences: Vec<(usize, usize)> = terms
+                .iter()
+                .flat_map(|term| lower.match_indices(term.as_str()))
+                .map(|(i, text)| (i, i + text.len()))
+                .collect();
+            occurrences.sort();
+            // the overlapping occurrences of several terms are a single highlighted span
+            let mut merged: Vec<(usize, usize)> = vec![];
+            for (i, end) in occurrences {
+                match merged.last_mut() {
+                    Some(last) if i <= last.1 => last.1 = last.1.max(end),
+                    _ => merged.push((i, end)),
+                }
+            }
+            let mut start = 0;
+            for (i, end) in merged {
+                if i > start {
+                    spans.push(Span::styled(span.content[start..i].to_string(), span.style));
+                }
+                spans.push(Span::styled(
+                    span.content[i..end].to_string(),
+                    span.style.patch(match_style),
+                ));
+                start = end;
+            }
+            if start < span.content.len() {
+                spans.push(Span::styled(span.content[start..].to_string(), span.style));
+            }
+        }
+        Line { spans, ..line }
     }
 
     /// Return a Line where the longest matching shortcut path is replaced by the shortcut name
     /// If no substitution is possible, return None
+    /// The trailing slashes of the shortcut path and of the path are ignored, e.g. a shortcut
+    /// to `/home/me/work/` matches `/home/me/work` and `/home/me/work/src`.
     pub(crate) fn shorten_path(
         config: &Config,
         shortcuts: &[Shortcut],
-        path: &String,
+        path: &str,
         size: u16,
         allow_shortcut_exact_match: bool,
     ) -> Option<Line<'static>> {
@@ -109,19 +286,29 @@ impl Gui {
         }
 
         let mut shortened_line: Option<Line> = None;
-        let mut cpath = "";
+        let mut cpath: Option<&str> = None;
         for shortcut in shortcuts {
-            if !allow_shortcut_exact_match && path == &shortcut.path {
+            if shortcut.path.is_empty() {
                 continue;
             }
-            let spm = format!("{}/", shortcut.path);
-            if (path.starts_with(&spm) || path == shortcut.path.as_str())
-                && shortcut.path.len() > cpath.len()
-            {
-                cpath = shortcut.path.as_str();
+            let directory = Self::directory_prefix(&shortcut.path);
+            let Some(rest) = strip_directory(path, directory, WINDOWS) else {
+                continue;
+            };
+            // the path is the directory, with trailing slashes or not
+            let rest = if rest.chars().all(|c| is_separator(c, WINDOWS)) {
+                ""
+            } else {
+                rest
+            };
+            if !allow_shortcut_exact_match && rest.is_empty() {
+                continue;
+            }
+            if cpath.is_none_or(|cpath| directory.len() > cpath.len()) {
+                cpath = Some(directory);
                 shortened_line = Some(Self::do_shorten_path(
-                    path,
-                    &config.styles.shortcut_name_style,
+                    rest,
+                    &config.styles.shortcut_name_style_of(&shortcut.name),
                     shortcut,
                     size,
                 ));
@@ -130,12 +317,15 @@ impl Gui {
         shortened_line
     }
 
-    fn do_shorten_path(
-        path: &String,
-        style: &Style,
-        shortcut: &Shortcut,
-        size: u16,
-    ) -> Line<'static> {
+    /// Returns a directory without its trailing slashes, the prefix of the paths in it: empty
+    /// for the root (`C:` on Windows).
+    fn directory_prefix(directory: &str) -> &str {
+        directory.trim_end_matches(|c| is_separator(c, WINDOWS))
+    }
+
+    /// Return a Line with the shortcut name followed by the rest of the path after the shortcut
+    /// path, i.e. empty or starting with a slash.
+    fn do_shorten_path(rest: &str, style: &Style, shortcut: &Shortcut, size: u16) -> Line<'static> {
         if shortcut.name.len() + 3 == size as usize {
             return Span::from("[").style(*style)
                 + Span::from(shortcut.name.clone()).style(*style)
@@ -149,19 +339,19 @@ impl Gui {
             + Span::from("]").style(*style);
 
         // if the path is an exact match of the shortcut, return it directly
-        if path == shortcut.path.as_str() {
+        if rest.is_empty() {
             return result_path;
         }
 
         // else we need to adjust the text if it's too long...
 
-        // We want to keep the / after the shortcut name
-        result_path += Span::from("/");
+        // We want to keep the / (or \ on Windows) after the shortcut name
+        result_path += Span::from(rest[..1].to_string());
 
         let remaining_size = size as usize - (shortcut.name.len() + 3);
 
         // take the suffix of the path after the shortcut path and after '/'
-        let path_suffix = &path[shortcut.path.len() + 1..];
+        let path_suffix = &rest[1..];
 
         if path_suffix.len() > remaining_size {
             let start_index = path_suffix.len() - remaining_size + 1;
@@ -175,27 +365,92 @@ impl Gui {
         result_path
     }
 
-    /// Return a function that formats a row for the history view
+    /// Return the style of a path: dimmed if the path is known to be missing.
+    /// The existence of paths not checked yet is checked in the background.
+    fn missing_path_style(
+        existence_checker: &Option<Arc<ExistenceChecker>>,
+        path: &str,
+        style: Style,
+    ) -> Style {
+        match existence_checker {
+            Some(checker) if checker.existence(path) == Existence::Missing => style.dim(),
+            _ => style,
+        }
+    }
+
+    /// Returns the directory the GUI is launched from, in the form of the recorded paths: the
+    /// `PWD` of the shell (symbolic links kept, as `cd` records it), else the process one.
+    fn current_directory(config: &Config) -> Option<String> {
+        let directory = std::env::var("PWD")
+            .ok()
+            .filter(|pwd| is_absolute(pwd, WINDOWS))
+            .or_else(|| {
+                std::env::current_dir()
+                    .ok()
+                    .map(|dir| dir.to_string_lossy().to_string())
+            })?;
+        let directory = recorded_form(trim_separators(&directory, WINDOWS), WINDOWS);
+        Some(if config.normalize_unicode {
+            normalize_unicode(&directory)
+        } else {
+            directory
+        })
+    }
+
+    /// Return a function that formats a row for the history view. The current directory, if
+    /// any, is marked before its path, and the home directory, if any, may be replaced by '~'.
     fn build_format_history_row_builder(
-        store: store::Store,
+        shortcuts: ShortcutsCache,
         config: Arc<Config>,
         table_view_state: Arc<Mutex<TableViewState>>,
+        existence_checker: Option<Arc<ExistenceChecker>>,
+        current_directory: Option<String>,
+        home: Option<String>,
     ) -> RowifyFn<store::Path> {
         let table_view_state = table_view_state.clone();
-        let store = store.clone();
         Box::new(move |paths: &[Path], size: &[u16]| {
-            let shortcuts: Vec<Shortcut> = store.list_all_shortcuts().unwrap();
+            if let Some(checker) = &existence_checker {
+                checker.check(paths.iter().map(|path| path.path.as_str()));
+            }
+            let shortcuts = shortcuts.shortcuts();
+            let (match_terms, display_with_home_tilde) = {
+                let table_view_state = table_view_state.lock().unwrap();
+                (
+                    store::highlighted_terms(&table_view_state.match_text),
+                    table_view_state.display_with_home_tilde,
+                )
+            };
             let table_view_state = table_view_state.clone();
             let config = config.clone();
-            paths
+            let existence_checker = existence_checker.clone();
+            let current_directory = current_directory.clone();
+            let home = home.clone();
+            Ok(paths
                 .iter()
                 .map(move |path| {
                     let path = path.clone();
-                    // format the date
-                    let date: Line = Line::from(
-                        Span::from((config.date_formater)(path.date))
-                            .style(config.styles.date_style),
-                    );
+                    let path_visits = path.visits;
+                    let path_created = path.created;
+                    let marker = (current_directory.as_deref()
+                        == Some(trim_separators(&path.path, WINDOWS)))
+                    .then(|| {
+                        Span::from(CURRENT_DIRECTORY_MARKER).style(
+                            Style::default().fg(config.styles.accent_color.unwrap_or(Color::Reset)),
+                        )
+                    });
+                    // format the date, before the path in the compact layout
+                    let date = Span::from(if config.compact {
+                        format!("{} ", Self::format_compact_date(path.date))
+                    } else {
+                        (config.date_formater)(path.date)
+                    })
+                    .style(config.styles.date_style);
+                    let path_width = if config.compact {
+                        size[0].saturating_sub(date.width() as u16)
+                    } else {
+                        size[1]
+                    }
+                    .saturating_sub(marker.as_ref().map_or(0, |marker| marker.width() as u16));
 
                     // format the path
                     let shortened_line =
@@ -204,24 +459,155 @@ impl Gui {
                                 config.as_ref(),
                                 &shortcuts,
                                 &path.path,
-                                size[1],
+                                path_width,
                                 true,
                             ),
                             false => None,
                         };
-                    let path = shortened_line
-                        .unwrap_or_else(|| {
-                            Self::reduce_path(path.path, size[1], config.styles.home_tilde_style)
-                        })
-                        .style(config.styles.path_style);
-
-                    vec![date, path]
+                    let path_style = Self::missing_path_style(
+                        &existence_checker,
+                        &path.path,
+                        config.styles.path_style,
+                    );
+                    let path_hidden = path.hidden;
+                    let path = Self::highlight_matches(
+                        shortened_line.unwrap_or_else(|| {
+                            Self::display_path(
+                                path.path,
+                                home.clone(),
+                                path_width,
+                                config.styles.home_tilde_style,
+                                display_with_home_tilde,
+                            )
+                        }),
+                        &match_terms,
+                        config.styles.match_style,
+                    )
+                    .style(path_style);
+                    // the marker is put before the spans of the shortcuts and of the matches
+                    let path = match marker {
+                        Some(marker) => {
+                            let mut spans = vec![marker];
+                            spans.extend(path.spans);
+                            Line::from(spans).style(path.style)
+                        }
+                        None => path,
+                    };
+
+                    let mut cells = if config.compact {
+                        let mut spans = vec![date];
+                        spans.extend(path.spans);
+                        vec![Line::from(spans).style(path.style)]
+                    } else {
+                        vec![Line::from(date), path]
+                    };
+                    if config.show_visits {
+                        cells.push(
+                            Line::from(path_visits.to_string())
+                                .style(config.styles.date_style)
+                                .alignment(Alignment::Right),
+                        );
+                    }
+                    if config.show_created {
+                        cells.push(
+                            Line::from((config.date_formater)(path_created))
+                                .style(config.styles.date_style),
+                        );
+                    }
+                    // the hidden paths (listed on demand) are dimmed
+                    let row = Row::new(cells);
+                    if path_hidden { row.dim() } else { row }
                 })
-                .map(Row::new)
-                .collect()
+                .collect())
         })
     }
 
+    /// Formats a date in short, e.g. `18-Oct 14:05`, for the compact layout
+    fn format_compact_date(date: i64) -> String {
+        DateTime::from_timestamp(date, 0)
+            .unwrap_or_default()
+            .with_timezone(&Local::now().timezone())
+            .format(COMPACT_DATE_FORMAT)
+            .to_string()
+    }
+
+    /// Returns a function giving the path to print for a chosen entry, following the
+    /// `print_abbreviated` option and the current display of the home directory.
+    fn printed_path_fn(
+        config: &Config,
+        table_view_state: &Arc<Mutex<TableViewState>>,
+    ) -> impl Fn(&str) -> String + use<> {
+        let print_abbreviated = config.print_abbreviated;
+        let table_view_state = table_view_state.clone();
+        let home = home_directory();
+        move |path| {
+            printed_path(
+                path,
+                print_abbreviated,
+                &table_view_state.lock().unwrap(),
+                home.clone(),
+            )
+        }
+    }
+
+    /// Returns a function giving the path of the shortcut named as the search text, chosen
+    /// with Enter whatever the selected entry, if the `exact_shortcut_match` option is set.
+    fn exact_shortcut_choice_fn(
+        store: &Store,
+        config: &Config,
+        table_view_state: &Arc<Mutex<TableViewState>>,
+    ) -> Option<ExactChoiceFn> {
+        if !config.exact_shortcut_match {
+            return None;
+        }
+        let store = store.clone();
+        let printed = Gui::printed_path_fn(config, table_view_state);
+        Some(Box::new(move |search_string: &str| {
+            let name = search_string.trim();
+            if name.is_empty() {
+                return None;
+            }
+            match store.resolve_shortcut(name) {
+                Ok(shortcut) => shortcut.map(|shortcut| printed(&shortcut.path)),
+                Err(e) => {
+                    warn!("Failed to find the shortcut '{}': {}", name, e);
+                    None
+                }
+            }
+        }))
+    }
+
+    /// Returns a function giving the path chosen the last time the GUI exited with one, if any
+    /// (see `record_last_path`).
+    fn last_choice_fn(store: &Store) -> LastChoiceFn {
+        let store = store.clone();
+        Box::new(move || match store.last_path() {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("Failed to read the last path: {}", e);
+                None
+            }
+        })
+    }
+
+    /// Writes a path chosen while keeping the GUI open into the output, if any.
+    ///
+    /// ### Returns
+    /// The outcome of the choice: a message, the GUI stays open.
+    fn write_chosen_path(output: &Option<PathOutput>, path: &str) -> ActionOutcome {
+        let Some(output) = output else {
+            return ActionOutcome::Message(String::from(NO_OUTPUT_MESSAGE));
+        };
+        let mut output = output.borrow_mut();
+        match writeln!(output, "{}", path).and_then(|_| output.flush()) {
+            Ok(_) => ActionOutcome::Message(String::from(PATH_SENT_MESSAGE)),
+            Err(e) => {
+                warn!("Failed to write the path '{}': {}", path, e);
+                ActionOutcome::Message(String::from(OUTPUT_FAILED_MESSAGE))
+            }
+        }
+    }
+
     /// Build the history view
     fn build_history_view(
         &mut self,
@@ -230,91 +616,295 @@ impl Gui {
         config: Arc<Config>,
         search_text_state: Arc<Mutex<SearchTextState>>,
     ) {
+        // the compact layout shows the date and the path in a single cell
+        let (mut column_names, mut column_constraints) = if config.compact {
+            (vec!["path".to_string()], vec![Constraint::Fill(1)])
+        } else {
+            (
+                vec!["date".to_string(), "path".to_string()],
+                vec![Constraint::Length(20), Constraint::Fill(1)],
+            )
+        };
+        let mut optional_columns = 0;
+        // the optional columns are hidden when the terminal is narrow
+        if config.show_visits {
+            column_names.push("visits".to_string());
+            column_constraints.push(Constraint::Length(6));
+            optional_columns += 1;
+        }
+        if config.show_created {
+            column_names.push("first seen".to_string());
+            column_constraints.push(Constraint::Length(20));
+            optional_columns += 1;
+        }
+
         self.history_view_container = Some(HistoryViewContainer::builder(
             view_manager.clone(),
-            vec!["date".to_string(), "path".to_string()],
-            vec![Constraint::Length(20), Constraint::Fill(1)],
+            column_names,
+            column_constraints,
+            optional_columns,
+            {
+                let store = store.clone();
+                Box::new(move |pos, len, text, fuzzy, sort_mode, include_hidden| {
+                    if fuzzy && !text.is_empty() {
+                        // ranked by score
+                        Ok(store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+                    } else {
+                        Ok(store.list_paths_sorted(pos, len, text, sort_mode, include_hidden)?)
+                    }
+                })
+            },
             {
                 let store = store.clone();
-                Box::new(move |pos, len, text, fuzzy| store.list_paths(pos, len, text, fuzzy))
+                Box::new(move |text, fuzzy, include_hidden| {
+                    Ok(store.count_paths(text, fuzzy, include_hidden)?)
+                })
             },
             Box::new(Gui::build_format_history_row_builder(
-                store.clone(),
+                self.shortcuts.clone(),
                 config.clone(),
                 self.table_view_state.clone(),
+                self.existence_checker.clone(),
+                Gui::current_directory(&config),
+                home_directory(),
             )),
-            |path: &Path| path.path.clone(),
+            Box::new(|path: &Path| path.path.clone()),
             config.clone(),
             self.table_view_state.clone(),
             {
                 let store = store.clone();
-                Box::new(move |path| {
-                    debug!("delete path: {}", path.path);
-                    store.delete_path_by_id(path.id).unwrap();
+                let shortcuts = self.shortcuts.clone();
+                let output = self.output.clone();
+                let printed = Gui::printed_path_fn(&config, &self.table_view_state);
+                Box::new(move |action, path: &Path| match action {
+                    Action::Choose => Ok(ActionOutcome::Exit(Some(printed(&path.path)))),
+                    Action::ChooseAndContinue => {
+                        Ok(Gui::write_chosen_path(&output, &printed(&path.path)))
+                    }
+                    // in full, whatever the way it is displayed
+                    Action::ChooseCanonical => {
+                        Ok(ActionOutcome::Exit(Some(canonical_path(&path.path))))
+                    }
+                    Action::Delete => {
+                        debug!("delete path: {}", path.path);
+                        store.delete_path_by_id(path.id)?;
+                        Ok(ActionOutcome::Refresh)
+                    }
+                    Action::ToggleHidden => {
+                        debug!("hide path: {} hidden={}", path.path, !path.hidden);
+                        store.set_path_hidden(path.id, !path.hidden)?;
+                        Ok(ActionOutcome::Refresh)
+                    }
+                    // to the path of the dialog, even if the list changed meanwhile
+                    Action::CreateShortcut { name, path } => {
+                        debug!("create shortcut: {} {}", name, path);
+                        store.add_shortcut(&name, &path, None)?;
+                        shortcuts.reload();
+                        Ok(ActionOutcome::Message(format!("shortcut {} created", name)))
+                    }
                 })
             },
             //search_string,
-            None,
+            Some(Gui::new_shortcut_dialog_builder(&store, &config)),
+            {
+                let config = config.clone();
+                Box::new(move |path: &Path| {
+                    let mut fields = vec![
+                        (String::from("path"), path.path.clone()),
+                        (
+                            String::from("last visit"),
+                            (config.date_formater)(path.date),
+                        ),
+                        (String::from("visits"), path.visits.to_string()),
+                        (
+                            String::from("first seen"),
+                            (config.date_formater)(path.created),
+                        ),
+                    ];
+                    if let Some(via) = &path.via {
+                        fields.push((String::from("via"), via.clone()));
+                    }
+                    fields
+                })
+            },
+            Gui::exact_shortcut_choice_fn(&store, &config, &self.table_view_state),
+            Some(Gui::last_choice_fn(&store)),
+            // the shortcuts are loaded again once modified, or refreshed
+            Some(self.shortcuts.reload_fn()),
             search_text_state,
         ));
     }
 
-    /// Return a function that formats a row for the history view
+    /// Returns the function building the dialog where the name of a new shortcut to a path of
+    /// the history is typed, prefilled with the last component of the path.
+    fn new_shortcut_dialog_builder(store: &Store, config: &Arc<Config>) -> EditorViewBuilder<Path> {
+        let store = store.clone();
+        let config = config.clone();
+        Box::new(move |path: Path, mode| match mode {
+            EditMode::Shortcut => {
+                let initial = std::path::Path::new(&path.path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let store = store.clone();
+                let reserved_names = config.reserved_shortcut_names.clone();
+                Some(Box::new(InputDialog::builder(
+                    "New shortcut",
+                    &format!("Name of the shortcut to {}", path.path),
+                    &initial,
+                    Box::new(move |name: &str| {
+                        Gui::validate_shortcut_name(&store, &reserved_names, name)
+                    }),
+                    config.styles.clone(),
+                )))
+            }
+            EditMode::Fields | EditMode::Path => None,
+        })
+    }
+
+    /// Validates the name of a new shortcut typed in the GUI.
+    ///
+    /// ### Parameters
+    /// - `store`: the store of the existing shortcuts
+    /// - `reserved_names`: the names of the commands, which the shortcuts can't shadow
+    /// - `name`: the typed name
+    ///
+    /// ### Returns
+    /// The trimmed name, or the reason why it is rejected: empty, with spaces, reserved or
+    /// already used (the GUI never replaces a shortcut), or if it could not be checked.
+    fn validate_shortcut_name(
+        store: &Store,
+        reserved_names: &[String],
+        name: &str,
+    ) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            Err(String::from("the name is empty"))
+        } else if name.contains(char::is_whitespace) {
+            Err(String::from("the name contains spaces"))
+        } else if reserved_names.iter().any(|reserved| reserved == name) {
+            Err(format!("the name '{}' shadows a command", name))
+        } else {
+            match store.find_shortcut(name) {
+                Ok(Some(shortcut)) => Err(format!(
+                    "'{}' is already a shortcut to {}",
+                    name, shortcut.path
+                )),
+                Ok(None) => Ok(name.to_string()),
+                Err(e) => {
+                    warn!("Failed to find the shortcut '{}': {}", name, e);
+                    Err(format!("database error: {}", e))
+                }
+            }
+        }
+    }
+
+    /// Returns the width of the longest shortcut name, within the configured limits
+    fn shortcut_name_width(config: &Config, shortcuts: &[Shortcut]) -> u16 {
+        let longest = shortcuts
+            .iter()
+            .map(|shortcut| Span::raw(shortcut.name.as_str()).width())
+            .max()
+            .unwrap_or(0);
+        (longest as u16)
+            .min(config.shortcut_name_max_width)
+            .max(config.shortcut_name_min_width)
+    }
+
+    /// Formats the creation date of a shortcut, empty if unknown (created before it was recorded)
+    fn format_shortcut_created(config: &Config, created: i64) -> String {
+        if created == 0 {
+            String::new()
+        } else {
+            (config.date_formater)(created)
+        }
+    }
+
+    /// Return a function that formats a row for the shortcut view, where the home directory, if
+    /// any, may be replaced by '~'
     fn build_format_shortcut_row_builder(
-        store: Store,
+        shortcuts_cache: ShortcutsCache,
         config: Arc<Config>,
         table_view_state: Arc<Mutex<TableViewState>>,
+        existence_checker: Option<Arc<ExistenceChecker>>,
+        home: Option<String>,
     ) -> RowifyFn<store::Shortcut> {
         let table_view_state = table_view_state.clone();
-        let store = store.clone();
         let config = config.clone();
         Box::new(move |shortcuts: &[Shortcut], size: &[u16]| {
-            shortcuts
+            if let Some(checker) = &existence_checker {
+                checker.check(shortcuts.iter().map(|shortcut| shortcut.path.as_str()));
+            }
+            let (display_with_shortcuts, display_with_home_tilde, match_terms) = {
+                let table_view_state = table_view_state.lock().unwrap();
+                (
+                    table_view_state.display_with_shortcuts,
+                    table_view_state.display_with_home_tilde,
+                    store::highlighted_terms(&table_view_state.match_text),
+                )
+            };
+            let all_shortcuts = shortcuts_cache.shortcuts();
+            Ok(shortcuts
                 .iter()
                 .map(|shortcut| {
                     // format the path
                     let shortcut = shortcut.clone();
-                    let shortened_line =
-                        match table_view_state.lock().unwrap().display_with_shortcuts {
-                            true => {
-                                let all_shortcuts: Vec<Shortcut> =
-                                    store.list_all_shortcuts().unwrap();
-                                Self::shorten_path(
-                                    config.as_ref(),
-                                    &all_shortcuts,
-                                    &shortcut.path,
-                                    size[1],
-                                    false,
-                                )
-                            }
-                            false => None,
-                        };
-                    let path = shortened_line
-                        .unwrap_or_else(|| {
-                            Self::reduce_path(
+                    let shortened_line = match display_with_shortcuts {
+                        true => Self::shorten_path(
+                            config.as_ref(),
+                            &all_shortcuts,
+                            &shortcut.path,
+                            size[1],
+                            false,
+                        ),
+                        false => None,
+                    };
+                    let path_style = Self::missing_path_style(
+                        &existence_checker,
+                        &shortcut.path,
+                        config.styles.path_style,
+                    );
+                    let path = Self::highlight_matches(
+                        shortened_line.unwrap_or_else(|| {
+                            Self::display_path(
                                 shortcut.path,
+                                home.clone(),
                                 size[1],
                                 config.styles.home_tilde_style,
+                                display_with_home_tilde,
                             )
-                        })
-                        .style(config.styles.path_style);
+                        }),
+                        &match_terms,
+                        config.styles.match_style,
+                    )
+                    .style(path_style);
 
                     Row::new(vec![
-                        Line::from(
-                            Span::from(shortcut.name.clone())
-                                .style(config.styles.shortcut_name_style),
+                        Self::highlight_matches(
+                            Line::from(
+                                Span::from(shortcut.name.clone())
+                                    .style(config.styles.shortcut_name_style_of(&shortcut.name)),
+                            ),
+                            &match_terms,
+                            config.styles.match_style,
                         ),
                         path,
-                        Line::from(
-                            shortcut
-                                .description
-                                .clone()
-                                .unwrap_or_else(|| "".to_string()),
+                        Self::highlight_matches(
+                            Line::from(
+                                shortcut
+                                    .description
+                                    .clone()
+                                    .unwrap_or_else(|| "".to_string()),
+                            ),
+                            &match_terms,
+                            config.styles.match_style,
                         )
                         .style(config.styles.description_style),
+                        Line::from(Self::format_shortcut_created(&config, shortcut.created))
+                            .style(config.styles.date_style),
                     ])
                 })
-                .collect()
+                .collect())
         })
     }
 
@@ -328,12 +918,19 @@ impl Gui {
     ) {
         let modal_store = store.clone();
         let modal_config = config.clone();
-        let editor_modal_view_builder = Box::new(move |shortcut: Shortcut| {
-            Box::new(ShortcutEditor::builder(
+        let editor_modal_view_builder = Box::new(move |shortcut: Shortcut, mode| {
+            let mode = match mode {
+                EditMode::Fields => ShortcutEditMode::Fields,
+                EditMode::Path => ShortcutEditMode::Path,
+                // no shortcut to a shortcut
+                EditMode::Shortcut => return None,
+            };
+            Some(Box::new(ShortcutEditor::builder(
                 modal_store.clone(),
                 modal_config.clone(),
-                shortcut.clone(),
-            ))
+                shortcut,
+                mode,
+            )))
         });
 
         self.shortcut_view_container = Some(ShortcutViewContainer::builder(
@@ -342,63 +939,174 @@ impl Gui {
                 "shortcut".to_string(),
                 "path".to_string(),
                 "description".to_string(),
+                "created".to_string(),
             ],
             vec![
                 Constraint::Length(20),
                 Constraint::Fill(1),
                 Constraint::Fill(1),
+                Constraint::Length(20),
             ],
+            // the creation date is hidden when the terminal is narrow
+            1,
+            // the name column fits the names of the page
+            Some((0, {
+                let config = config.clone();
+                Box::new(move |shortcuts: &[Shortcut]| Gui::shortcut_name_width(&config, shortcuts))
+            })),
             {
                 let store = store.clone();
-                Box::new(move |pos, len, text, fuzzy| store.list_shortcuts(pos, len, text, fuzzy))
+                Box::new(move |pos, len, text, fuzzy, sort_mode, _| {
+                    if fuzzy && !text.is_empty() {
+                        // ranked by score
+                        Ok(store.list_shortcuts(pos, len, text, fuzzy)?)
+                    } else {
+                        Ok(store.list_shortcuts_sorted(pos, len, text, sort_mode)?)
+                    }
+                })
+            },
+            {
+                let store = store.clone();
+                Box::new(move |text, fuzzy, _| Ok(store.count_shortcuts(text, fuzzy)?))
             },
             Box::new(Gui::build_format_shortcut_row_builder(
-                store.clone(),
+                self.shortcuts.clone(),
                 config.clone(),
                 self.table_view_state.clone(),
+                self.existence_checker.clone(),
+                home_directory(),
             )),
-            |shortcut: &store::Shortcut| shortcut.path.clone(),
+            Box::new(|shortcut: &store::Shortcut| shortcut.path.clone()),
             config.clone(),
             self.table_view_state.clone(),
             {
                 let store = store.clone();
-                Box::new(move |path| {
-                    debug!("delete shortcut: {}", path.path);
-                    store.delete_shortcut_by_id(path.id).unwrap();
+                let output = self.output.clone();
+                let printed = Gui::printed_path_fn(&config, &self.table_view_state);
+                Box::new(move |action, shortcut: &store::Shortcut| match action {
+                    Action::Choose => Ok(ActionOutcome::Exit(Some(printed(&shortcut.path)))),
+                    Action::ChooseAndContinue => {
+                        Ok(Gui::write_chosen_path(&output, &printed(&shortcut.path)))
+                    }
+                    Action::ChooseCanonical => {
+                        Ok(ActionOutcome::Exit(Some(canonical_path(&shortcut.path))))
+                    }
+                    Action::Delete => {
+                        debug!("delete shortcut: {}", shortcut.path);
+                        store.delete_shortcut_by_id(shortcut.id)?;
+                        Ok(ActionOutcome::Refresh)
+                    }
+                    Action::ToggleHidden => Ok(ActionOutcome::Message(String::from(
+                        SHORTCUTS_NOT_HIDDEN_MESSAGE,
+                    ))),
+                    Action::CreateShortcut { .. } => Ok(ActionOutcome::Message(String::from(
+                        SHORTCUT_OF_SHORTCUT_MESSAGE,
+                    ))),
                 })
             },
             //search_string,
             Some(editor_modal_view_builder),
+            {
+                let config = config.clone();
+                Box::new(move |shortcut: &store::Shortcut| {
+                    let mut fields = vec![
+                        (String::from("shortcut"), shortcut.name.clone()),
+                        (String::from("path"), shortcut.path.clone()),
+                    ];
+                    if let Some(description) = &shortcut.description {
+                        fields.push((String::from("description"), description.clone()));
+                    }
+                    if shortcut.created != 0 {
+                        fields.push((
+                            String::from("created"),
+                            Gui::format_shortcut_created(&config, shortcut.created),
+                        ));
+                    }
+                    fields
+                })
+            },
+            Gui::exact_shortcut_choice_fn(&store, &config, &self.table_view_state),
+            Some(Gui::last_choice_fn(&store)),
+            // the shortcuts are loaded again once modified, or refreshed
+            Some(self.shortcuts.reload_fn()),
             search_text_state,
         ));
     }
 
-    /// Instantiate the application GUI
-    fn new(view_manager: Rc<ViewManager>, store: store::Store, config: Arc<Config>) -> Gui {
+    /// Instantiate the application GUI, the lists being filtered with the search text given,
+    /// if any
+    fn new(
+        view_manager: Rc<ViewManager>,
+        store: store::Store,
+        config: Arc<Config>,
+        output: Option<PathOutput>,
+        search_string: Option<&str>,
+    ) -> Gui {
+        let recent_searches = store.list_recent_searches().unwrap_or_else(|e| {
+            warn!("Failed to load the recent searches: {}", e);
+            vec![]
+        });
+        let search_text_states = if config.shared_filter {
+            let search_text_state = Arc::new(Mutex::new(
+                SearchTextState::new(view_manager.clone(), recent_searches)
+                    .with_fuzzy_match(config.fuzzy_search)
+                    .with_search_string(search_string.unwrap_or_default()),
+            ));
+            vec![search_text_state.clone(), search_text_state]
+        } else {
+            ["path", "shortcut"]
+                .iter()
+                .map(|objects_type| {
+                    Arc::new(Mutex::new(
+                        SearchTextState::new(view_manager.clone(), recent_searches.clone())
+                            .with_objects_type(objects_type)
+                            .with_fuzzy_match(config.fuzzy_search)
+                            .with_search_string(search_string.unwrap_or_default()),
+                    ))
+                })
+                .collect()
+        };
         let mut gui = Gui {
-            table_view_state: Arc::new(Mutex::new(TableViewState::new())),
+            table_view_state: Arc::new(Mutex::new(TableViewState {
+                display_with_shortcuts: config.shorten_paths,
+                display_with_home_tilde: config.substitute_home,
+                ..TableViewState::new()
+            })),
             history_view_container: None,
             shortcut_view_container: None,
+            search_text_states: search_text_states.clone(),
+            existence_checker: config.dim_missing_paths.then(|| {
+                let checker = ExistenceChecker::new(view_manager.tx());
+                Arc::new(if config.skip_network_paths {
+                    checker.skip_network(Mounts::read())
+                } else {
+                    checker
+                })
+            }),
+            output,
+            store: store.clone(),
+            shortcuts: ShortcutsCache::new(&store),
+            start_view: Gui::start_view(&store, &config),
+            remember_toggles: config.remember_toggles,
         };
-        let search_text_state = Arc::new(Mutex::new(SearchTextState::new(view_manager.clone())));
         gui.build_history_view(
             view_manager.clone(),
             store.clone(),
             config.clone(),
-            search_text_state.clone(),
+            search_text_states[HISTORY_VIEW_CONTAINER as usize].clone(),
         );
         gui.build_shortcut_view(
             view_manager.clone(),
             store.clone(),
             config.clone(),
-            search_text_state.clone(),
+            search_text_states[SHORTCUT_VIEW_ID as usize].clone(),
         );
 
         gui
     }
 
     /// Run the application GUI loop
-    async fn run(&mut self, view_manager: Rc<ViewManager>) -> Option<String> {
+    async fn run(&mut self, view_manager: Rc<ViewManager>) -> io::Result<Option<String>> {
         let vb = self.history_view_container.take().unwrap();
         view_manager.add_view(
             HISTORY_VIEW_CONTAINER,
@@ -408,28 +1116,242 @@ impl Gui {
 
         let vb = self.shortcut_view_container.take().unwrap();
         view_manager.add_view(SHORTCUT_VIEW_ID, vb, &[SHORTCUT_VIEW_ID as usize]);
+        view_manager.select_top_level_view(Gui::view_idx(self.start_view));
+
+        let result = view_manager.event_loop().await;
+
+        // the next launch shows the view quit from, unless configured otherwise
+        let last_view = match view_manager.top_level_view_idx() as u16 {
+            SHORTCUT_VIEW_ID => StartView::Shortcuts,
+            _ => StartView::History,
+        };
+        if let Err(e) = self.store.set_last_view(last_view.name()) {
+            warn!("Failed to record the last view: {}", e);
+        }
+        if self.remember_toggles {
+            self.record_toggles();
+        }
+        result
+    }
 
-        view_manager.event_loop().await
+    /// Records the display toggles, to restore them at the next launch (see
+    /// `with_remembered_toggles`).
+    fn record_toggles(&self) {
+        let (shorten_paths, substitute_home) = {
+            let table_view_state = self.table_view_state.lock().unwrap();
+            (
+                table_view_state.display_with_shortcuts,
+                table_view_state.display_with_home_tilde,
+            )
+        };
+        if let Err(e) = self
+            .store
+            .set_toggle(SHORTEN_PATHS_TOGGLE, shorten_paths)
+            .and_then(|_| {
+                self.store
+                    .set_toggle(SUBSTITUTE_HOME_TOGGLE, substitute_home)
+            })
+        {
+            warn!("Failed to record the display toggles: {}", e);
+        }
+    }
+
+    /// Returns the configuration where the display toggles (`shorten_paths` and
+    /// `substitute_home`) are the ones the GUI was last quit with, if the `remember_toggles`
+    /// option is set and they were recorded.
+    fn with_remembered_toggles(store: &Store, config: Arc<Config>) -> Arc<Config> {
+        if !config.remember_toggles {
+            return config;
+        }
+        let toggle = |name: &str, configured: bool| match store.toggle(name) {
+            Ok(value) => value.unwrap_or(configured),
+            Err(e) => {
+                warn!("Failed to read the toggle {}: {}", name, e);
+                configured
+            }
+        };
+        let mut remembered = config.as_ref().clone();
+        remembered.shorten_paths = toggle(SHORTEN_PATHS_TOGGLE, config.shorten_paths);
+        remembered.substitute_home = toggle(SUBSTITUTE_HOME_TOGGLE, config.substitute_home);
+        Arc::new(remembered)
+    }
+
+    /// Returns the view shown at launch: the configured one, else the one the GUI was last
+    /// quit from, else the history.
+    fn start_view(store: &Store, config: &Config) -> StartView {
+        config
+            .start_view
+            .or_else(|| match store.last_view() {
+                Ok(name) => name.as_deref().and_then(StartView::from_name),
+                Err(e) => {
+                    warn!("Failed to read the last view: {}", e);
+                    None
+                }
+            })
+            .unwrap_or(StartView::History)
+    }
+
+    /// Returns the index of a view among the top level views
+    fn view_idx(view: StartView) -> usize {
+        match view {
+            StartView::History => HISTORY_VIEW_CONTAINER as usize,
+            StartView::Shortcuts => SHORTCUT_VIEW_ID as usize,
+        }
     }
 }
 
-/// Launch the GUI. Returns the selected path or None if the user quit.
-pub(crate) async fn gui(store: store::Store, config: Arc<Config>) -> Option<String> {
+/// Returns the path to print for the only directory of the history matching a search text,
+/// if exactly one matches, e.g. to go there without launching the GUI.
+/// The search is fuzzy if the GUI starts in fuzzy mode (`fuzzy_search` option).
+pub(crate) fn single_match(
+    store: &Store,
+    config: &Config,
+    search_string: &str,
+) -> Result<Option<String>, rusqlite::Error> {
+    let paths = store.list_paths(0, 2, search_string, config.fuzzy_search, false)?;
+    debug!("{} paths match '{}'", paths.len(), search_string);
+    let table_view_state = TableViewState {
+        display_with_home_tilde: config.substitute_home,
+        ..TableViewState::new()
+    };
+    Ok(match paths.as_slice() {
+        [path] => Some(printed_path(
+            &path.path,
+            config.print_abbreviated,
+            &table_view_state,
+            home_directory(),
+        )),
+        _ => None,
+    })
+}
+
+/// Records the path chosen when the GUI exits, in full even if it is printed with `~`, to be
+/// selected again with ctrl+g or printed by `cdir --last`.
+pub(crate) fn record_last_path(store: &Store, chosen: &str) {
+    let path = match (chosen.strip_prefix('~'), home_directory()) {
+        (Some(rest), Some(home))
+            if rest.is_empty() || rest.starts_with(|c| is_separator(c, WINDOWS)) =>
+        {
+            home + rest
+        }
+        _ => chosen.to_string(),
+    };
+    if let Err(e) = store.set_last_path(&path) {
+        warn!("Failed to record the last path: {}", e);
+    }
+}
+
+/// Detects the paths recorded by another shell while the GUI is open, from the date of the
+/// most recent visit of the history.
+pub(crate) struct DateWatcher {
+    last: Option<i64>,
+}
+
+impl DateWatcher {
+    /// Builds a watcher of the history, whose most recent visit is currently at a date.
+    pub(crate) fn new(date: Option<i64>) -> DateWatcher { DateWatcher { last: date } }
+
+    /// Returns whether the date of the most recent visit changed since the last check, i.e.
+    /// whether the lists must be refreshed.
+    pub(crate) fn changed(&mut self, date: Option<i64>) -> bool {
+        if date == self.last {
+            return false;
+        }
+        debug!(
+            "most recent visit changed from {:?} to {:?}",
+            self.last, date
+        );
+        self.last = date;
+        true
+    }
+}
+
+/// Launch the GUI, the lists being filtered with the search text given, if any. The paths
+/// chosen while keeping the GUI open are written into the output, if any. Returns the selected
+/// path or None if the user quit, or the error which prevented the GUI from running, e.g. when
+/// it is not launched from a terminal.
+/// With `watch`, the lists are refreshed when another shell records a path.
+pub(crate) async fn gui(
+    store: store::Store,
+    config: Arc<Config>,
+    output: Option<Box<dyn Write>>,
+    search_string: Option<&str>,
+    watch: bool,
+) -> io::Result<Option<String>> {
     debug!("gui");
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(io::Error::other(NOT_A_TERMINAL_MESSAGE));
+    }
+    if config.stored_frecency {
+        // the scores of the paths not visited since the last launch are outdated
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        if let Err(e) = store.reage_scores(now) {
+            warn!("Failed to age the scores of the paths: {}", e);
+        }
+    }
     let mut view_manager: Rc<ViewManager> = Rc::new(ViewManager::new());
 
     if let Some(vm) = Rc::get_mut(&mut view_manager) {
         let config = config.clone();
-        vm.set_global_help_view(Box::new(move || Help::builder(config.styles.clone())))
+        vm.set_debounce(Duration::from_millis(config.search_debounce_ms));
+        vm.set_global_help_view(Box::new(move || Help::builder(config.styles.clone())));
+        if watch {
+            // the lists are refreshed when another shell records a path
+            let store = store.clone();
+            let tx = vm.tx();
+            let mut watcher = DateWatcher::new(store.max_date().unwrap_or(None));
+            vm.set_tick(
+                WATCH_PERIOD,
+                Box::new(move || match store.max_date() {
+                    Ok(date) if watcher.changed(date) => {
+                        let _ = tx.send(GenericEvent::ApplicationEvent(ApplicationEvent {
+                            id: String::from("data.refresh"),
+                            payload: None,
+                        }));
+                        true
+                    }
+                    Ok(_) => false,
+                    Err(e) => {
+                        warn!("Failed to check the most recent visit: {}", e);
+                        false
+                    }
+                }),
+            );
+        }
     }
 
-    let mut gui = Gui::new(view_manager.clone(), store, config);
-    gui.run(view_manager).await
+    let output = output.map(|output| Rc::new(RefCell::new(output)));
+    let config = Gui::with_remembered_toggles(&store, config);
+    let mut gui = Gui::new(
+        view_manager.clone(),
+        store.clone(),
+        config.clone(),
+        output,
+        search_string,
+    );
+    let result = gui.run(view_manager.clone()).await?;
+
+    // remember the search that led to the chosen path, in the view it was chosen from
+    if result.is_some() {
+        let search_text_state = gui.search_text_states[view_manager.top_level_view_idx()]
+            .lock()
+            .unwrap();
+        if let Err(e) = store.add_recent_search(
+            search_text_state.search_string(),
+            config.recent_searches_max,
+        ) {
+            warn!("Failed to record the recent search: {}", e);
+        }
+    }
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::env;
+    use std::collections::HashMap;
 
     use super::*;
     use crate::{
@@ -445,11 +1367,16 @@ mod tests {
             name: "docs".to_string(),
             path: "/home/user/docs".to_string(),
             description: None,
+            created: 0,
         }];
         let path = Path {
             id: 1,
             path: "/home/user/docs/project".to_string(),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
         let result = Gui::shorten_path(&config, &shortcuts, &path.path, 80, true);
         assert!(result.is_some());
@@ -458,6 +1385,107 @@ mod tests {
         assert_eq!(line_str, "[docs]/project");
     }
 
+    #[test]
+    fn test_shorten_path_trailing_slashes() {
+        let config = Config::default();
+        let shortcut = |name: &str, path: &str| Shortcut {
+            id: 1,
+            name: name.to_string(),
+            path: path.to_string(),
+            description: None,
+            created: 0,
+        };
+        let shorten = |shortcut_path: &str, path: &str| {
+            Gui::shorten_path(&config, &[shortcut("work", shortcut_path)], path, 80, true)
+                .map(|line| line.to_string())
+        };
+        for shortcut_path in ["/home/me/work", "/home/me/work/", "/home/me/work//"] {
+            assert_eq!(
+                shorten(shortcut_path, "/home/me/work/src").as_deref(),
+                Some("[work]/src"),
+                "{}",
+                shortcut_path
+            );
+            assert_eq!(
+                shorten(shortcut_path, "/home/me/work/src/").as_deref(),
+                Some("[work]/src/")
+            );
+            assert_eq!(
+                shorten(shortcut_path, "/home/me/work").as_deref(),
+                Some("[work]")
+            );
+            assert_eq!(
+                shorten(shortcut_path, "/home/me/work/").as_deref(),
+                Some("[work]")
+            );
+            assert_eq!(shorten(shortcut_path, "/home/me/workshop"), None);
+            assert_eq!(shorten(shortcut_path, "/home/me"), None);
+        }
+
+        // a shortcut to the root directory
+        for shortcut_path in ["/", "//"] {
+            assert_eq!(shorten(shortcut_path, "/").as_deref(), Some("[work]"));
+            assert_eq!(
+                shorten(shortcut_path, "/tmp").as_deref(),
+                Some("[work]/tmp")
+            );
+            assert_eq!(shorten(shortcut_path, "tmp"), None);
+        }
+        assert_eq!(shorten("", "/tmp"), None);
+
+        // the exact match is skipped when not allowed, whatever the trailing slashes
+        let shortcuts = [shortcut("work", "/home/me/work/")];
+        assert!(Gui::shorten_path(&config, &shortcuts, "/home/me/work", 80, false).is_none());
+        assert!(Gui::shorten_path(&config, &shortcuts, "/home/me/work/", 80, false).is_none());
+
+        // the longest shortcut path wins, whatever the trailing slashes
+        let shortcuts = [shortcut("root", "/"), shortcut("work", "/home/me/work/")];
+        assert_eq!(
+            Gui::shorten_path(&config, &shortcuts, "/home/me/work/src", 80, true)
+                .map(|line| line.to_string())
+                .as_deref(),
+            Some("[work]/src")
+        );
+    }
+
+    #[test]
+    fn test_shorten_path_shortcut_colors() {
+        let mut config = Config {
+            shortcut_colors: HashMap::from([
+                (String::from("work"), String::from("magenta")),
+                (String::from("oss"), String::from("not a color")),
+            ]),
+            ..Config::default()
+        };
+        config.process();
+        let shortcut = |id, name: &str| Shortcut {
+            id,
+            name: name.to_string(),
+            path: format!("/home/user/{}", name),
+            description: None,
+            created: 0,
+        };
+        let shortcuts = vec![shortcut(1, "work"), shortcut(2, "docs"), shortcut(3, "oss")];
+        let name_style = |path: &str| {
+            let line = Gui::shorten_path(&config, &shortcuts, path, 80, true).unwrap();
+            line.spans[1].style
+        };
+
+        assert_eq!(
+            name_style("/home/user/work/project"),
+            config.styles.shortcut_name_style.fg(Color::Magenta)
+        );
+        // the shortcuts without a valid color keep the default one
+        assert_eq!(
+            name_style("/home/user/docs/project"),
+            config.styles.shortcut_name_style
+        );
+        assert_eq!(
+            name_style("/home/user/oss/project"),
+            config.styles.shortcut_name_style
+        );
+    }
+
     #[test]
     fn test_shorten_path_no_match() {
         let config = Config::default();
@@ -466,11 +1494,16 @@ mod tests {
             name: "docs".to_string(),
             path: "/home/user/docs".to_string(),
             description: None,
+            created: 0,
         }];
         let path = Path {
             id: 1,
             path: "/home/user/other/project".to_string(),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
         let result = Gui::shorten_path(&config, &shortcuts, &path.path, 80, true);
         assert!(result.is_none());
@@ -485,18 +1518,24 @@ mod tests {
                 name: "docs".to_string(),
                 path: "/home/user/docs".to_string(),
                 description: None,
+                created: 0,
             },
             Shortcut {
                 id: 2,
                 name: "work".to_string(),
                 path: "/home/user/docs/work".to_string(),
                 description: None,
+                created: 0,
             },
         ];
         let path = Path {
             id: 1,
             path: "/home/user/docs/work".to_string(),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
         let result = Gui::shorten_path(&config, &shortcuts, &path.path, 80, true);
         assert!(result.is_some());
@@ -514,18 +1553,24 @@ mod tests {
                 name: "docs".to_string(),
                 path: "/home/user/docs".to_string(),
                 description: None,
+                created: 0,
             },
             Shortcut {
                 id: 2,
                 name: "work".to_string(),
                 path: "/home/user/docs/work".to_string(),
                 description: None,
+                created: 0,
             },
         ];
         let path = Path {
             id: 1,
             path: "/home/user/docs/work/project".to_string(),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
         let result = Gui::shorten_path(&config, &shortcuts, &path.path, 80, true);
         assert!(result.is_some());
@@ -542,11 +1587,16 @@ mod tests {
             name: "docs".to_string(),
             path: "/home/user/docs".to_string(),
             description: None,
+            created: 0,
         }];
         let path = Path {
             id: 1,
             path: "/home/user/docs/project".to_string(),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
         let result = Gui::shorten_path(&config, &shortcuts, &path.path, 14, true);
         assert!(result.is_some());
@@ -585,50 +1635,82 @@ mod tests {
         assert_eq!(line_str, "*");
     }
 
+    #[test]
+    fn test_reduce_path_without_home() {
+        let line = Gui::reduce_path_with_home(
+            String::from("/home/testuser/project"),
+            None,
+            80,
+            Style::new(),
+        );
+        assert_eq!(line.to_string(), "/home/testuser/project");
+    }
+
     #[test]
     fn test_reduce_path_home_replacement() {
-        // Set HOME to a known value
         let home = "/home/testuser";
-        unsafe {
-            env::set_var("HOME", home);
-        }
         let path = Path {
             id: 1,
             path: format!("{}/project", home),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
-        let line = Gui::reduce_path(path.path, 80, Style::new());
+        let line = Gui::reduce_path_with_home(path.path, Some(home.to_string()), 80, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "~/project");
     }
 
+    #[test]
+    fn test_display_path_without_home_tilde() {
+        let home = Some("/home/testuser".to_string());
+        let path = "/home/testuser/project".to_string();
+        let line = Gui::display_path(path.clone(), home.clone(), 80, Style::new(), true);
+        assert_eq!(line.to_string(), "~/project");
+        let line = Gui::display_path(path.clone(), home.clone(), 80, Style::new(), false);
+        assert_eq!(line.to_string(), "/home/testuser/project");
+        let line = Gui::display_path(path.clone(), home.clone(), 8, Style::new(), false);
+        assert_eq!(line.to_string(), "*project");
+        let line = Gui::display_path(path, home, 0, Style::new(), false);
+        assert_eq!(line.to_string(), "");
+    }
+
     #[test]
     fn test_reduce_path_exact_home() {
         let home = "/home/testuser";
-        unsafe {
-            env::set_var("HOME", home);
-        }
         let path = Path {
             id: 1,
             path: home.to_string(),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
-        let line = Gui::reduce_path(path.path, 80, Style::new());
+        let line = Gui::reduce_path_with_home(path.path, Some(home.to_string()), 80, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "~");
     }
 
     #[test]
     fn test_reduce_path_no_home_match() {
-        unsafe {
-            env::set_var("HOME", "/home/testuser");
-        }
         let path = Path {
             id: 1,
             path: "/other/path/project".to_string(),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
-        let line = Gui::reduce_path(path.path, 80, Style::new());
+        let line = Gui::reduce_path_with_home(
+            path.path,
+            Some("/home/testuser".to_string()),
+            80,
+            Style::new(),
+        );
         let line_str = line.to_string();
         assert_eq!(line_str, "/other/path/project");
     }
@@ -636,36 +1718,43 @@ mod tests {
     #[test]
     fn test_reduce_path_with_home_limited_size() {
         let home = "/home/testuser";
-        unsafe {
-            env::set_var("HOME", home);
-        }
         let path = Path {
             id: 1,
             path: format!("{}/project", home),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
 
-        let line = Gui::reduce_path(path.path.clone(), 9, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 9, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "~/project");
 
-        let line = Gui::reduce_path(path.path.clone(), 8, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 8, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "~/*oject");
 
-        let line = Gui::reduce_path(path.path.clone(), 4, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 4, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "~/*t");
 
-        let line = Gui::reduce_path(path.path.clone(), 3, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 3, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "~/*");
 
-        let line = Gui::reduce_path(path.path.clone(), 2, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 2, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "~*");
 
-        let line = Gui::reduce_path(path.path.clone(), 1, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 1, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "*");
     }
@@ -673,20 +1762,23 @@ mod tests {
     #[test]
     fn test_reduce_path_with_home_exact_limited_size() {
         let home = "/home/testuser";
-        unsafe {
-            env::set_var("HOME", home);
-        }
         let path = Path {
             id: 1,
             path: home.to_string(),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
 
-        let line = Gui::reduce_path(path.path.clone(), 2, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 2, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "~");
 
-        let line = Gui::reduce_path(path.path.clone(), 1, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 1, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "~");
     }
@@ -694,21 +1786,623 @@ mod tests {
     #[test]
     fn test_reduce_path_without_home_limited_size() {
         let home = "/home/testuser";
-        unsafe {
-            env::set_var("HOME", home);
-        }
         let path = Path {
             id: 1,
             path: "/other/path/project".to_string(),
             date: 0,
+            visits: 1,
+            created: 0,
+            hidden: false,
+            via: None,
         };
 
-        let line = Gui::reduce_path(path.path.clone(), 19, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 19, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "/other/path/project");
 
-        let line = Gui::reduce_path(path.path.clone(), 18, Style::new());
+        let line =
+            Gui::reduce_path_with_home(path.path.clone(), Some(home.to_string()), 18, Style::new());
         let line_str = line.to_string();
         assert_eq!(line_str, "*ther/path/project");
     }
+
+    #[test]
+    fn test_highlight_matches() {
+        let base = Style::new().fg(Color::Blue);
+        let match_style = Style::new().fg(Color::Red);
+        let line = Line::from(vec![Span::styled("~", base), Span::from("/Projects/proj")]);
+
+        let line = Gui::highlight_matches(line, &[String::from("PROJ")], match_style);
+        let spans: Vec<(String, Style)> = line
+            .spans
+            .iter()
+            .map(|span| (span.content.to_string(), span.style))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (String::from("~"), base),
+                (String::from("/"), Style::new()),
+                (String::from("Proj"), match_style),
+                (String::from("ects/"), Style::new()),
+                (String::from("proj"), match_style),
+            ]
+        );
+
+        // no search text: the line is unchanged
+        let line = Gui::highlight_matches(Line::from("/tmp"), &[], match_style);
+        assert_eq!(line.spans.len(), 1);
+
+        let highlighted = |text: &str, search: &str| -> Vec<(String, bool)> {
+            Gui::highlight_matches(
+                Line::from(text.to_string()),
+                &store::highlighted_terms(search),
+                match_style,
+            )
+            .spans
+            .iter()
+            .map(|span| (span.content.to_string(), span.style == match_style))
+            .collect()
+        };
+        let span = |text: &str, matching: bool| (text.to_string(), matching);
+
+        // each term of a multi-term query, the overlapping occurrences merged
+        assert_eq!(
+            highlighted("/home/user/src/cdir", "src user dir ir"),
+            vec![
+                span("/home/", false),
+                span("user", true),
+                span("/", false),
+                span("src", true),
+                span("/c", false),
+                span("dir", true),
+            ]
+        );
+        assert_eq!(
+            highlighted("/tmp/My Drive", r#""my drive""#),
+            vec![span("/tmp/", false), span("My Drive", true)]
+        );
+        // the negated terms and the via filters are not in the paths
+        assert_eq!(
+            highlighted("/home/user/src", "-tmp src via:git"),
+            vec![span("/home/user/", false), span("src", true)]
+        );
+        assert_eq!(highlighted("/tmp", "-tmp"), vec![span("/tmp", false)]);
+    }
+
+    #[test]
+    fn test_rows_with_failing_shortcuts_query() {
+        let store = Store::setup_test_store();
+        store.add_path("/home/user/docs").unwrap();
+        store.add_shortcut("docs", "/home/user/docs", None).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        let shortcuts = store.list_all_shortcuts().unwrap();
+        let config = Arc::new(Config::default());
+        let table_view_state = Arc::new(Mutex::new(TableViewState::new()));
+        let cache = ShortcutsCache::new(&store);
+        let history_rowify = Gui::build_format_history_row_builder(
+            cache.clone(),
+            config.clone(),
+            table_view_state.clone(),
+            None,
+            None,
+            None,
+        );
+        let shortcut_rowify = Gui::build_format_shortcut_row_builder(
+            cache.clone(),
+            config,
+            table_view_state,
+            None,
+            None,
+        );
+        assert_eq!(history_rowify(&paths, &[20, 40]).unwrap().len(), 1);
+        assert_eq!(shortcut_rowify(&shortcuts, &[20, 40, 20]).unwrap().len(), 1);
+
+        // the paths are displayed without shortcut names instead of failing
+        store.drop_table("shortcuts");
+        cache.reload();
+        assert!(cache.shortcuts().is_empty());
+        assert_eq!(history_rowify(&paths, &[20, 40]).unwrap().len(), 1);
+        assert_eq!(shortcut_rowify(&shortcuts, &[20, 40, 20]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_shortcuts_loaded_once() {
+        let store = Store::setup_test_store();
+        store.add_path("/home/user/docs").unwrap();
+        store.add_shortcut("docs", "/home/user/docs", None).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        let loads = Rc::new(std::cell::Cell::new(0));
+        let cache = ShortcutsCache::with_loader({
+            let store = store.clone();
+            let loads = loads.clone();
+            Box::new(move || {
+                loads.set(loads.get() + 1);
+                store.list_all_shortcuts()
+            })
+        });
+        let rowify = Gui::build_format_history_row_builder(
+            cache.clone(),
+            Arc::new(Config::default()),
+            Arc::new(Mutex::new(TableViewState::new())),
+            None,
+            None,
+            None,
+        );
+
+        // a single query, whatever the number of draws
+        for _ in 0..5 {
+            rowify(&paths, &[20, 40]).unwrap();
+        }
+        assert_eq!(loads.get(), 1);
+
+        // a new shortcut is displayed once reloaded
+        store.add_shortcut("home", "/home/user", None).unwrap();
+        cache.reload();
+        rowify(&paths, &[20, 40]).unwrap();
+        assert_eq!(loads.get(), 2);
+        assert_eq!(cache.shortcuts().len(), 2);
+    }
+
+    /// Renders rows into a table of the given width, returning the text of its lines
+    fn render_rows(rows: Vec<Row>, constraints: Vec<Constraint>, width: u16) -> Vec<String> {
+        let height = rows.len() as u16;
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| {
+                frame.render_widget(
+                    ratatui::widgets::Table::new(rows, constraints),
+                    frame.area(),
+                )
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rows_without_home_substitution() {
+        let store = Store::setup_test_store();
+        store
+            .add_path_with_time("/home/testuser/work/src", 1000)
+            .unwrap();
+        store.add_path_with_time("/home/testuser/tmp", 900).unwrap();
+        store
+            .add_shortcut("work", "/home/testuser/work", None)
+            .unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        let rows = |substitute_home: bool| {
+            let table_view_state = TableViewState {
+                display_with_home_tilde: substitute_home,
+                ..TableViewState::new()
+            };
+            let rows = Gui::build_format_history_row_builder(
+                ShortcutsCache::new(&store),
+                Arc::new(Config::default()),
+                Arc::new(Mutex::new(table_view_state)),
+                None,
+                None,
+                Some("/home/testuser".to_string()),
+            )(&paths, &[20, 30])
+            .unwrap();
+            render_rows(rows, vec![Constraint::Length(20), Constraint::Fill(1)], 51)
+        };
+
+        // the shortcuts take precedence over the home directory, substituted or not
+        assert_eq!(
+            rows(true),
+            vec![
+                "1000                 [work]/src",
+                "900                  ~/tmp"
+            ]
+        );
+        assert_eq!(
+            rows(false),
+            vec![
+                "1000                 [work]/src",
+                "900                  /home/testuser/tmp"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compact_rows() {
+        let store = Store::setup_test_store();
+        store
+            .add_path_with_time("/var/lib/projects/cdir/src", 1000)
+            .unwrap();
+        store.add_path_with_time("/tmp", 900).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        let rows = |compact: bool, size: &[u16]| {
+            let config = Config {
+                compact,
+                ..Config::default()
+            };
+            Gui::build_format_history_row_builder(
+                ShortcutsCache::new(&store),
+                Arc::new(config),
+                Arc::new(Mutex::new(TableViewState::new())),
+                None,
+                None,
+                None,
+            )(&paths, size)
+            .unwrap()
+        };
+
+        // the date takes a column of its own, the path is truncated
+        let normal = render_rows(
+            rows(false, &[20, 19]),
+            vec![Constraint::Length(20), Constraint::Fill(1)],
+            40,
+        );
+        assert_eq!(normal.len(), 2);
+        assert_eq!(normal[0], "1000                 */projects/cdir/src");
+        assert_eq!(normal[1], "900                  /tmp");
+
+        // a row per entry as well, with the short date just before the path, in full
+        let compact = render_rows(rows(true, &[40]), vec![Constraint::Fill(1)], 40);
+        assert_eq!(compact.len(), 2);
+        assert_eq!(
+            compact[0],
+            format!(
+                "{} /var/lib/projects/cdir/src",
+                Gui::format_compact_date(1000)
+            )
+        );
+        assert_eq!(
+            compact[1],
+            format!("{} /tmp", Gui::format_compact_date(900))
+        );
+    }
+
+    #[test]
+    fn test_current_directory_marker() {
+        let store = Store::setup_test_store();
+        store
+            .add_path_with_time("/var/lib/projects/cdir/src/", 1000)
+            .unwrap();
+        store.add_path_with_time("/tmp", 900).unwrap();
+        store
+            .add_shortcut("projects", "/var/lib/projects", None)
+            .unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        let config = Config::default();
+        let table_view_state = Arc::new(Mutex::new(TableViewState {
+            match_text: String::from("cdir"),
+            ..TableViewState::new()
+        }));
+        let rows = |current_directory: &str| {
+            let rows = Gui::build_format_history_row_builder(
+                ShortcutsCache::new(&store),
+                Arc::new(config.clone()),
+                table_view_state.clone(),
+                None,
+                Some(current_directory.to_string()),
+                None,
+            )(&paths, &[20, 30])
+            .unwrap();
+            render_rows(rows, vec![Constraint::Length(20), Constraint::Fill(1)], 51)
+        };
+
+        // the marker comes before the shortcut, whatever the trailing slash
+        let lines = rows("/var/lib/projects/cdir/src");
+        assert_eq!(lines[0], "1000                 ● [projects]/cdir/src/");
+        assert_eq!(lines[1], "900                  /tmp");
+
+        let lines = rows("/tmp");
+        assert_eq!(lines[0], "1000                 [projects]/cdir/src/");
+        assert_eq!(lines[1], "900                  ● /tmp");
+
+        assert_eq!(rows("/home")[1], "900                  /tmp");
+    }
+
+    #[test]
+    fn test_shortcut_name_width() {
+        let config = Config {
+            shortcut_name_min_width: 4,
+            shortcut_name_max_width: 12,
+            ..Config::default()
+        };
+        let shortcuts = |names: &[&str]| -> Vec<Shortcut> {
+            names
+                .iter()
+                .map(|name| Shortcut {
+                    id: 1,
+                    name: name.to_string(),
+                    path: String::from("/tmp"),
+                    description: None,
+                    created: 0,
+                })
+                .collect()
+        };
+        assert_eq!(
+            Gui::shortcut_name_width(&config, &shortcuts(&["a", "docs", "work"])),
+            4
+        );
+        assert_eq!(
+            Gui::shortcut_name_width(&config, &shortcuts(&["a", "project"])),
+            7
+        );
+        assert_eq!(
+            Gui::shortcut_name_width(&config, &shortcuts(&["a-very-long-shortcut"])),
+            12
+        );
+        assert_eq!(Gui::shortcut_name_width(&config, &[]), 4);
+    }
+
+    #[test]
+    fn test_format_shortcut_created() {
+        let config = Config::default();
+        assert_eq!(Gui::format_shortcut_created(&config, 0), "");
+        assert_eq!(
+            Gui::format_shortcut_created(&config, 1700000000),
+            (config.date_formater)(1700000000)
+        );
+    }
+
+    /// A writer whose content can be read while it is owned by the GUI
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> { self.0.borrow_mut().write(buf) }
+
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn test_write_chosen_path() {
+        let outcome = Gui::write_chosen_path(&None, "/tmp");
+        assert_eq!(
+            outcome,
+            ActionOutcome::Message(String::from(NO_OUTPUT_MESSAGE))
+        );
+
+        let buffer = Rc::new(RefCell::new(vec![]));
+        let output: Option<PathOutput> = Some(Rc::new(RefCell::new(Box::new(SharedBuffer(
+            buffer.clone(),
+        )))));
+        // the GUI stays open after each choice
+        for path in ["/tmp", "/home/user/My Drive"] {
+            let outcome = Gui::write_chosen_path(&output, path);
+            assert_eq!(
+                outcome,
+                ActionOutcome::Message(String::from(PATH_SENT_MESSAGE))
+            );
+        }
+        assert_eq!(
+            String::from_utf8(buffer.borrow().clone()).unwrap(),
+            "/tmp\n/home/user/My Drive\n"
+        );
+    }
+
+    #[test]
+    fn test_single_match() {
+        let store = Store::setup_test_store();
+        store.add_path("/home/user/src/cdir").unwrap();
+        store.add_path("/home/user/src/other").unwrap();
+        store.add_path("/tmp").unwrap();
+        let config = Config::default();
+
+        assert_eq!(
+            single_match(&store, &config, "cdir").unwrap().as_deref(),
+            Some("/home/user/src/cdir")
+        );
+        // several matches or none: the GUI is needed
+        assert_eq!(single_match(&store, &config, "src").unwrap(), None);
+        assert_eq!(single_match(&store, &config, "none").unwrap(), None);
+
+        // the configured search mode
+        assert_eq!(single_match(&store, &config, "cdr").unwrap(), None);
+        let config = Config {
+            fuzzy_search: true,
+            ..Config::default()
+        };
+        assert_eq!(
+            single_match(&store, &config, "cdr").unwrap().as_deref(),
+            Some("/home/user/src/cdir")
+        );
+    }
+
+    #[test]
+    fn test_search_string_at_launch() {
+        for shared_filter in [true, false] {
+            let config = Config {
+                shared_filter,
+                ..Config::default()
+            };
+            let gui = Gui::new(
+                Rc::new(ViewManager::new()),
+                Store::setup_test_store(),
+                Arc::new(config),
+                None,
+                Some("src"),
+            );
+            for state in &gui.search_text_states {
+                assert_eq!(state.lock().unwrap().search_string(), "src");
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_shortcut_choice() {
+        let store = Store::setup_test_store();
+        store.add_shortcut("docs", "/home/user/docs", None).unwrap();
+        let table_view_state = Arc::new(Mutex::new(TableViewState::new()));
+        assert!(
+            Gui::exact_shortcut_choice_fn(&store, &Config::default(), &table_view_state).is_none()
+        );
+
+        let config = Config {
+            exact_shortcut_match: true,
+            ..Config::default()
+        };
+        let choice = Gui::exact_shortcut_choice_fn(&store, &config, &table_view_state).unwrap();
+        assert_eq!(choice("docs").as_deref(), Some("/home/user/docs"));
+        assert_eq!(choice("docs ").as_deref(), Some("/home/user/docs"));
+        assert_eq!(choice("doc"), None);
+        assert_eq!(choice(""), None);
+    }
+
+    #[test]
+    fn test_start_view() {
+        let store = Store::setup_test_store();
+        let config = Config::default();
+        assert_eq!(Gui::start_view(&store, &config), StartView::History);
+
+        // the view last quit from, unless configured
+        store.set_last_view("shortcuts").unwrap();
+        assert_eq!(Gui::start_view(&store, &config), StartView::Shortcuts);
+        let configured = Config {
+            start_view: Some(StartView::History),
+            ..Config::default()
+        };
+        assert_eq!(Gui::start_view(&store, &configured), StartView::History);
+
+        // an unknown name is ignored
+        store.set_last_view("pinned").unwrap();
+        assert_eq!(Gui::start_view(&store, &config), StartView::History);
+    }
+
+    #[test]
+    fn test_date_watcher() {
+        let store = Store::setup_test_store();
+        let mut watcher = DateWatcher::new(store.max_date().unwrap());
+        // nothing recorded yet
+        assert!(!watcher.changed(store.max_date().unwrap()));
+
+        store.add_path_with_time("/tmp", 1000).unwrap();
+        assert!(watcher.changed(store.max_date().unwrap()));
+        // the change triggers a single refresh
+        assert!(!watcher.changed(store.max_date().unwrap()));
+
+        // an older visit doesn't change the most recent one
+        store.add_path_with_time("/src", 500).unwrap();
+        assert!(!watcher.changed(store.max_date().unwrap()));
+
+        // a path visited again by another shell
+        store.add_path_with_time("/src", 2000).unwrap();
+        assert!(watcher.changed(store.max_date().unwrap()));
+
+        // the history was emptied
+        for path in store.list_paths(0, 10, "", false, true).unwrap() {
+            store.delete_path_by_id(path.id).unwrap();
+        }
+        assert!(watcher.changed(store.max_date().unwrap()));
+        assert!(!watcher.changed(None));
+    }
+
+    #[test]
+    fn test_remembered_toggles() {
+        let store = Store::setup_test_store();
+        let toggles = |config: &Arc<Config>| (config.shorten_paths, config.substitute_home);
+        let config = Arc::new(Config::default());
+        assert_eq!(toggles(&config), (true, true));
+
+        // the configured values, until the GUI is quit
+        let config = Arc::new(Config {
+            shorten_paths: false,
+            remember_toggles: true,
+            ..Config::default()
+        });
+        let remembered = Gui::with_remembered_toggles(&store, config.clone());
+        assert_eq!(toggles(&remembered), (false, true));
+        store.set_toggle(SHORTEN_PATHS_TOGGLE, true).unwrap();
+        store.set_toggle(SUBSTITUTE_HOME_TOGGLE, false).unwrap();
+        let remembered = Gui::with_remembered_toggles(&store, config.clone());
+        assert_eq!(toggles(&remembered), (true, false));
+
+        // the GUI starts with them and records them as toggled
+        let gui = Gui::new(
+            Rc::new(ViewManager::new()),
+            store.clone(),
+            remembered,
+            None,
+            None,
+        );
+        {
+            let mut table_view_state = gui.table_view_state.lock().unwrap();
+            assert!(table_view_state.display_with_shortcuts);
+            assert!(!table_view_state.display_with_home_tilde);
+            table_view_state.display_with_shortcuts = false;
+        }
+        gui.record_toggles();
+        assert_eq!(store.toggle(SHORTEN_PATHS_TOGGLE).unwrap(), Some(false));
+        assert_eq!(store.toggle(SUBSTITUTE_HOME_TOGGLE).unwrap(), Some(false));
+
+        // the recorded values are ignored unless remembered
+        let config = Arc::new(Config {
+            shorten_paths: false,
+            ..Config::default()
+        });
+        let remembered = Gui::with_remembered_toggles(&store, config);
+        assert_eq!(toggles(&remembered), (false, true));
+    }
+
+    #[test]
+    fn test_validate_shortcut_name() {
+        let store = Store::setup_test_store();
+        store.add_shortcut("docs", "/home/user/docs", None).unwrap();
+        let reserved_names = Config::default().reserved_shortcut_names;
+        let validate = |name| Gui::validate_shortcut_name(&store, &reserved_names, name);
+        assert_eq!(validate(" src "), Ok(String::from("src")));
+        assert_eq!(validate("  "), Err(String::from("the name is empty")));
+        assert_eq!(
+            validate("my src"),
+            Err(String::from("the name contains spaces"))
+        );
+        assert_eq!(
+            validate(reserved_names[0].as_str()),
+            Err(format!(
+                "the name '{}' shadows a command",
+                reserved_names[0]
+            ))
+        );
+        assert_eq!(
+            validate("docs"),
+            Err(String::from(
+                "'docs' is already a shortcut to /home/user/docs"
+            ))
+        );
+        store.drop_table("shortcuts");
+        assert!(validate("src").unwrap_err().starts_with("database error: "));
+    }
+
+    #[test]
+    fn test_shared_filter() {
+        let search_text_states = |shared_filter| {
+            let config = Config {
+                shared_filter,
+                ..Config::default()
+            };
+            let gui = Gui::new(
+                Rc::new(ViewManager::new()),
+                Store::setup_test_store(),
+                Arc::new(config),
+                None,
+                None,
+            );
+            gui.search_text_states
+        };
+
+        let states = search_text_states(true);
+        assert!(Arc::ptr_eq(
+            &states[HISTORY_VIEW_CONTAINER as usize],
+            &states[SHORTCUT_VIEW_ID as usize]
+        ));
+        let states = search_text_states(false);
+        assert!(!Arc::ptr_eq(
+            &states[HISTORY_VIEW_CONTAINER as usize],
+            &states[SHORTCUT_VIEW_ID as usize]
+        ));
+    }
 }
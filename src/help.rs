@@ -2,43 +2,264 @@ use crossterm::event::{KeyCode, KeyEvent};
 use log::debug;
 use ratatui::{
     layout::{Constraint, Layout, Rect},
-    style::Style,
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Padding, Paragraph},
+    widgets::{Block, Borders, Padding, Paragraph},
 };
 
 use crate::{
     theme::ThemeStyles,
-    tui::{EventCaptured, ManagerAction, View, ViewBuilder},
+    tui::{EventCaptured, ManagerAction, View, ViewBuilder, overlay::clear_overlay},
 };
 
+// the rows taken by the borders and the padding of the popup
+const HELP_FRAME_HEIGHT: u16 = 4;
+
+/// A popup listing the key bindings. When the terminal is too small to show them all, they are
+/// scrolled with the arrow and page keys.
 pub struct Help {
     styles: ThemeStyles,
+    // the number of lines scrolled, at most the lines which are not shown
+    scroll: u16,
+    // the number of lines shown by the last draw
+    visible_lines: u16,
 }
 
 impl Help {
     pub fn builder(styles: ThemeStyles) -> ViewBuilder {
-        ViewBuilder::from(Box::new(Self { styles }))
+        ViewBuilder::from(Box::new(Self {
+            styles,
+            scroll: 0,
+            visible_lines: 0,
+        }))
     }
 }
 
 impl View for Help {
     fn handle_key_event(&mut self, key_event: KeyEvent) -> (EventCaptured, ManagerAction) {
+        let page = self.visible_lines.max(1);
         match key_event.code {
-            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => (
-                EventCaptured::Yes,
-                ManagerAction::new(false).with_close(true),
-            ),
-            _ => (EventCaptured::Yes, ManagerAction::new(false)),
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                return (
+                    EventCaptured::Yes,
+                    ManagerAction::new(false).with_close(true),
+                );
+            }
+            // the scroll is clamped when drawn
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(page),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(page),
+            _ => return (EventCaptured::Yes, ManagerAction::new(false)),
         }
+        (EventCaptured::Yes, ManagerAction::new(true))
     }
 
     fn draw(&mut self, frame: &mut ratatui::Frame, modal_area: Rect, _active: bool) {
         debug!("Drawing help active");
 
+        let ts = self.styles.text_style;
+        let es = self.styles.text_em_style;
+
+        let lines = vec![
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("tab", es),
+                Span::styled(" to switch between the views.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("enter", es),
+                Span::styled(" to exit the GUI and go into the selected directory;", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("esc or ctrl+q", es),
+                Span::styled(" to simply exit and stay in the current directory.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use the ", ts),
+                Span::styled("up", es),
+                Span::styled(" and ", ts),
+                Span::styled("down", es),
+                Span::styled(" arrow keys, or ", ts),
+                Span::styled("ctrl+p", es),
+                Span::styled(" and ", ts),
+                Span::styled("ctrl+n", es),
+                Span::styled(", to select a directory (", ts),
+                Span::styled("shift", es),
+                Span::styled("+arrow for bigger jumps);", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("page up", es),
+                Span::styled(" and ", ts),
+                Span::styled("page down", es),
+                Span::styled(" to scroll through the list by page;", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("home", es),
+                Span::styled(
+                    " to go to the most recent directory in the history (the top);",
+                    ts,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("end", es),
+                Span::styled(" to go to the last entry of the list;", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("f5", es),
+                Span::styled(
+                    " to reload the list, e.g. with the entries added by another shell;",
+                    ts,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+space", es),
+                Span::styled(
+                    " to mark the selected entry, or unmark it, and go to the next one;",
+                    ts,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+a", es),
+                Span::styled(
+                    " to see the full directory path without shortcuts, or switch back to shortcut usage.",
+                    ts,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+b", es),
+                Span::styled(" to create a shortcut to the selected directory.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+c", es),
+                Span::styled(
+                    " to copy the marked entries, one per line, or the selected one to the clipboard.",
+                    ts,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+d", es),
+                Span::styled(" to delete the selected entry.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+e", es),
+                Span::styled(" to edit a shortcut description.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+f", es),
+                Span::styled(" to switch between exact and fuzzy search.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+g", es),
+                Span::styled(" to select the directory chosen the last time.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+k", es),
+                Span::styled(
+                    " to send the selected directory (see --out-fd) and keep the GUI open.",
+                    ts,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+l", es),
+                Span::styled(" to change the path of a shortcut.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+o", es),
+                Span::styled(
+                    " to open the selected directory with an external program.",
+                    ts,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+h", es),
+                Span::styled(" for the help screen.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+r", es),
+                Span::styled(" to recall the recent searches.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+s", es),
+                Span::styled(" to change the order of the paths or of the shortcuts.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+t", es),
+                Span::styled(" to show or hide the hidden paths.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+u", es),
+                Span::styled(" to show the home directory as ~ or in full.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+v", es),
+                Span::styled(" to view the selected entry in full.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+x", es),
+                Span::styled(" to hide the selected path, or show it again.", ts),
+            ]),
+            Line::from(vec![
+                Span::styled("Use ", ts),
+                Span::styled("ctrl+y", es),
+                Span::styled(
+                    " to exit and go into the selected directory, with its canonical path.",
+                    ts,
+                ),
+            ]),
+            Line::from(""),
+            Line::from(vec![Span::styled("Enter a text to filter.", ts)]),
+        ];
+
+        // with the borders and the padding, within the frame
+        let height = (lines.len() as u16 + HELP_FRAME_HEIGHT).min(modal_area.height);
+        self.visible_lines = height.saturating_sub(HELP_FRAME_HEIGHT);
+        self.scroll = self
+            .scroll
+            .min((lines.len() as u16).saturating_sub(self.visible_lines));
+        // the way to close the popup stays visible, below the scrolled lines
+        let mut hint = vec![
+            Span::styled(" Use ", ts),
+            Span::styled("esc", es),
+            Span::styled(" to close this window", ts),
+        ];
+        if self.visible_lines < lines.len() as u16 {
+            hint.extend([
+                Span::styled(", ", ts),
+                Span::styled("up", es),
+                Span::styled(" and ", ts),
+                Span::styled("down", es),
+                Span::styled(" to scroll", ts),
+            ]);
+        }
+        hint.push(Span::styled(". ", ts));
+
         let layout = Layout::vertical([
             Constraint::Fill(1),
-            Constraint::Length(19),
+            Constraint::Length(height),
             Constraint::Fill(1),
         ]);
         let chunks = layout.split(modal_area);
@@ -50,97 +271,85 @@ impl View for Help {
         let chunks = center_layout.split(chunks[1]);
         let modal_area = chunks[1];
 
-        frame.render_widget(Clear, modal_area);
+        clear_overlay(frame, modal_area, self.styles.background_color);
 
-        let ts = self.styles.text_style;
-        let es = self.styles.text_em_style;
+        let message = Paragraph::new(lines).scroll((self.scroll, 0)).block(
+            Block::default()
+                .padding(Padding::new(1, 1, 1, 1))
+                .title(Span::styled(" cdir help ", self.styles.title_style))
+                .title_bottom(Line::from(hint))
+                .borders(Borders::ALL),
+        );
+        frame.render_widget(message, modal_area);
+    }
+}
 
-        let message = Paragraph::new(vec![
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("tab", es),
-            Span::styled(" to switch between the views.", ts),
-        ]),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("enter", es),
-            Span::styled(" to exit the GUI and go into the selected directory;", ts),
-        ]),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("esc or ctrl+q", es),
-            Span::styled(" to simply exit and stay in the current directory.", ts),
-        ]),
-        Line::from(vec![
-            Span::styled("Use the ", ts),
-            Span::styled("up", es),
-            Span::styled(" and ", ts),
-            Span::styled("down", es),
-            Span::styled(" arrow keys to select a directory (", ts),
-            Span::styled("shift", es),
-            Span::styled(" for bigger jumps);", ts),
-        ]),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("page up", es),
-            Span::styled(" and ", ts),
-            Span::styled("page down", es),
-            Span::styled(" to scroll through the list by page;", ts),
-        ]),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("home", es),
-            Span::styled(
-                " to go to the most recent directory in the history (the top);",
-                ts,
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("ctrl+a", es),
-            Span::styled(" to see the full directory path without shortcuts, or switch back to shortcut usage.", ts),
-        ]),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("ctrl+d", es),
-            Span::styled(" to delete the selected entry.", ts),
-        ]),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("ctrl+e", es),
-            Span::styled(" to edit a shortcut description.", ts),
-        ]),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("ctrl+f", es),
-            Span::styled(" to switch between exact and fuzzy search.", ts),
-        ]),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("ctrl+h", es),
-            Span::styled(" for the help screen.", ts),
-        ]),
-        Line::from(""),
-        Line::from(vec![Span::styled("Enter a text to filter.", ts)]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("Use ", ts),
-            Span::styled("esc", es),
-            Span::styled(" to close this window.", ts),
-        ]),
-    ])
-    .block(
-        Block::default()
-            .padding(Padding::new(1, 1, 1, 1))
-            .title(Span::styled(" cdir help ", self.styles.title_style))
-            .borders(Borders::ALL)
-    );
-
-        // Fill the frame with the background color if defined
-        if let Some(bg_color) = &self.styles.background_color {
-            let background = Paragraph::new("").style(Style::default().bg(*bg_color));
-            frame.render_widget(background, modal_area);
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::config::Config;
+
+    fn render(help: &mut Help, height: u16) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(110, height)).unwrap();
+        terminal
+            .draw(|frame| help.draw(frame, frame.area(), true))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| (0..110).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_scroll_in_small_terminal() {
+        let mut help = Help {
+            styles: Config::default().styles,
+            scroll: 0,
+            visible_lines: 0,
+        };
+        let press = |help: &mut Help, code: KeyCode| {
+            help.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+        };
+
+        // clamped to the terminal, the way to close it shown
+        let rows = render(&mut help, 24);
+        assert_eq!(help.visible_lines, 20);
+        assert!(rows[23].contains("esc to close this window, up and down to scroll"));
+        assert!(rows[2].contains("Use tab to switch between the views."));
+
+        press(&mut help, KeyCode::Down);
+        let rows = render(&mut help, 24);
+        assert!(!rows.iter().any(|row| row.contains("Use tab to switch")));
+        assert!(rows[23].contains("esc to close"));
+
+        // down to the last line, not further
+        for _ in 0..100 {
+            press(&mut help, KeyCode::PageDown);
         }
-        frame.render_widget(message, modal_area);
+        let rows = render(&mut help, 24);
+        assert!(rows[21].contains("Enter a text to filter."));
+        let last = help.scroll;
+        press(&mut help, KeyCode::Up);
+        assert_eq!(help.scroll, last - 1);
+        press(&mut help, KeyCode::PageUp);
+        assert_eq!(help.scroll, 0);
+        assert!(
+            press(&mut help, KeyCode::Esc).1.close,
+            "esc closes the help"
+        );
+
+        // everything fits in a big terminal
+        let mut help = Help {
+            styles: Config::default().styles,
+            scroll: 3,
+            visible_lines: 0,
+        };
+        let rows = render(&mut help, 60);
+        assert_eq!(help.scroll, 0);
+        assert!(rows.iter().any(|row| row.contains("Use tab to switch")));
+        assert!(!rows.iter().any(|row| row.contains("up and down to scroll")));
     }
 }
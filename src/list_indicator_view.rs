@@ -1,22 +1,42 @@
-use std::sync::Arc;
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
 
-use log::debug;
+use log::{debug, warn};
 use ratatui::{
     layout::{Alignment, Rect},
     prelude::Style,
-    style::{Color, Stylize},
+    style::Stylize,
     widgets::Paragraph,
 };
 
 use crate::{
     config::Config,
     model::DataStatePayload,
+    tableview::FooterMessagePayload,
     tui::{View, ViewBuilder, event::ApplicationEvent},
 };
 
+const INVALID_PATTERN_MESSAGE: &str = "invalid pattern";
+const SEARCHING_MESSAGE: &str = "searching…";
+
 pub struct ListIndicatorState {
     objects_type: String,
     is_empty: bool,
+    is_error: bool,
+    // the number of matches of the current filter, if any
+    count: Option<usize>,
+    // whether the hidden entries are listed
+    include_hidden: bool,
+    // the number of entries after the visible ones, if hinted
+    more: Option<usize>,
+    // a message (e.g. a failure) shown until the list changes
+    message: Option<String>,
+    // the list just became empty, and the user was not notified yet
+    no_match_pending: bool,
+    // a slow query is about to run: the list is outdated until it returns
+    is_searching: bool,
 }
 
 impl ListIndicatorState {
@@ -24,6 +44,13 @@ impl ListIndicatorState {
         Self {
             objects_type,
             is_empty: false,
+            is_error: false,
+            count: None,
+            include_hidden: false,
+            more: None,
+            message: None,
+            no_match_pending: false,
+            is_searching: false,
         }
     }
 }
@@ -52,19 +79,61 @@ impl View for ListIndicatorView {
             frame.render_widget(background, area);
         }
 
-        let pa = if self.state.is_empty {
-            Paragraph::new("no entry")
-                .style(
-                    Style::default().fg(Color::Black).bg(self
-                        .config
-                        .styles
-                        .free_text_area_bg_color
-                        .unwrap()),
-                )
-                .bg(Color::Red)
+        let mut empty_message_style = Style::default();
+        if let Some(fg_color) = self.config.styles.empty_message_fg_color {
+            empty_message_style = empty_message_style.fg(fg_color);
+        }
+        if let Some(bg_color) = self.config.styles.empty_message_bg_color {
+            empty_message_style = empty_message_style.bg(bg_color);
+        }
+
+        // notify once that the list just became empty
+        let flash = self.state.no_match_pending && self.config.no_match_flash;
+        if self.state.no_match_pending && self.config.no_match_bell {
+            let mut stdout = io::stdout();
+            if let Err(e) = stdout.write_all(b"\x07").and_then(|_| stdout.flush()) {
+                warn!("Failed to ring the bell: {}", e);
+            }
+        }
+        self.state.no_match_pending = false;
+
+        let pa = if self.state.is_searching {
+            Paragraph::new(SEARCHING_MESSAGE)
+                .style(empty_message_style)
+                .alignment(Alignment::Center)
+        } else if let Some(message) = &self.state.message {
+            Paragraph::new(message.as_str())
+                .style(empty_message_style)
+                .alignment(Alignment::Center)
+        } else if self.state.is_error {
+            Paragraph::new(INVALID_PATTERN_MESSAGE)
+                .style(empty_message_style)
+                .alignment(Alignment::Center)
+        } else if self.state.is_empty {
+            // an empty message means that nothing is displayed
+            if self.config.empty_message.is_empty() {
+                return;
+            }
+            if flash {
+                // inverted colors, until the next redraw
+                let background = Paragraph::new("").style(Style::default().reversed());
+                frame.render_widget(background, area);
+                empty_message_style = empty_message_style.reversed();
+            }
+            Paragraph::new(self.config.empty_message.as_str())
+                .style(empty_message_style)
                 .alignment(Alignment::Center)
         } else {
-            Paragraph::new("ctrl+h: help")
+            let text = match (self.state.count, self.state.include_hidden, self.state.more) {
+                (Some(1), false, _) => String::from("1 match"),
+                (Some(1), true, _) => String::from("1 match, hidden shown"),
+                (Some(count), false, _) => format!("{} matches", count),
+                (Some(count), true, _) => format!("{} matches, hidden shown", count),
+                (None, false, Some(more)) => format!("+{} more", more),
+                (None, false, None) => String::from("ctrl+h: help"),
+                (None, true, _) => String::from("hidden shown"),
+            };
+            Paragraph::new(text)
                 .style(
                     Style::default()
                         .bg(self.config.styles.header_bg_color.unwrap())
@@ -81,12 +150,268 @@ impl View for ListIndicatorView {
             && let Some(payload) = payload.downcast_ref::<DataStatePayload>()
             && payload.objects_type == self.state.objects_type
         {
-            debug!("data.payload is_empty={}", payload.is_empty);
+            debug!(
+                "data.payload is_empty={} is_error={} count={:?}",
+                payload.is_empty, payload.is_error, payload.count
+            );
+            self.state.is_searching = payload.is_searching;
+            if payload.is_searching {
+                // the other states are kept until the search returns
+                return;
+            }
+            if payload.is_empty && !self.state.is_empty {
+                self.state.no_match_pending = true;
+            }
             self.state.is_empty = payload.is_empty;
+            self.state.is_error = payload.is_error;
+            self.state.count = payload.count;
+            self.state.include_hidden = payload.include_hidden;
+            self.state.more = payload.more;
+            self.state.message = None;
 
             // let _ = self
             //     .tx
             //     .send(GenericEvent::ViewManagerEvent(ViewManagerEvent::Redraw));
         }
+        if ae.id == "footer.message"
+            && let Some(payload) = &ae.payload
+            && let Some(payload) = payload.downcast_ref::<FooterMessagePayload>()
+            && payload.objects_type == self.state.objects_type
+        {
+            debug!("footer.message message={}", payload.message);
+            self.state.message = Some(payload.message.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend, style::Modifier};
+
+    use super::*;
+
+    fn render(config: Config, is_empty: bool) -> String { render_state(config, is_empty, false) }
+
+    fn render_state(config: Config, is_empty: bool, is_error: bool) -> String {
+        let mut view = ListIndicatorView {
+            state: ListIndicatorState::new("path".to_string()),
+            config: Arc::new(config),
+        };
+        view.state.is_empty = is_empty;
+        view.state.is_error = is_error;
+        let mut terminal = Terminal::new(TestBackend::new(16, 1)).unwrap();
+        terminal
+            .draw(|frame| view.draw(frame, frame.area(), false))
+            .unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_message() {
+        let config = Config::default();
+        assert_eq!(render(config.clone(), true).trim(), "no entry");
+        assert_eq!(render(config, false).trim(), "ctrl+h: help");
+
+        let config = Config {
+            empty_message: String::from("nothing!"),
+            ..Config::default()
+        };
+        assert_eq!(render(config, true).trim(), "nothing!");
+
+        let config = Config {
+            empty_message: String::new(),
+            ..Config::default()
+        };
+        assert_eq!(render(config, true).trim(), "");
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let config = Config::default();
+        assert_eq!(
+            render_state(config.clone(), false, true).trim(),
+            "invalid pattern"
+        );
+        assert_eq!(render_state(config, true, true).trim(), "invalid pattern");
+    }
+
+    #[test]
+    fn test_searching() {
+        let mut view = ListIndicatorView {
+            state: ListIndicatorState::new("path".to_string()),
+            config: Arc::new(Config::default()),
+        };
+        let mut terminal = Terminal::new(TestBackend::new(16, 1)).unwrap();
+        let mut draw = |view: &mut ListIndicatorView| -> String {
+            terminal
+                .draw(|frame| view.draw(frame, frame.area(), false))
+                .unwrap();
+            let content: String = terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            content.trim().to_string()
+        };
+
+        let searching = |is_searching: bool| ApplicationEvent {
+            id: String::from("data.payload"),
+            payload: Some(Arc::new(DataStatePayload {
+                objects_type: "path".to_string(),
+                is_empty: false,
+                is_error: false,
+                count: Some(3),
+                sort_name: None,
+                include_hidden: false,
+                more: None,
+                is_searching,
+            })),
+        };
+        view.handle_application_event(&searching(false));
+        assert_eq!(draw(&mut view), "3 matches");
+        view.handle_application_event(&searching(true));
+        assert_eq!(draw(&mut view), "searching…");
+        view.handle_application_event(&payload(true));
+        assert_eq!(draw(&mut view), "no entry");
+    }
+
+    fn payload(is_empty: bool) -> ApplicationEvent {
+        ApplicationEvent {
+            id: String::from("data.payload"),
+            payload: Some(Arc::new(DataStatePayload {
+                objects_type: "path".to_string(),
+                is_empty,
+                is_error: false,
+                count: None,
+                sort_name: None,
+                include_hidden: false,
+                more: None,
+                is_searching: false,
+            })),
+        }
+    }
+
+    #[test]
+    fn test_match_count() {
+        let mut view = ListIndicatorView {
+            state: ListIndicatorState::new("path".to_string()),
+            config: Arc::new(Config::default()),
+        };
+        let mut terminal = Terminal::new(TestBackend::new(16, 1)).unwrap();
+        let mut draw = |view: &mut ListIndicatorView| -> String {
+            terminal
+                .draw(|frame| view.draw(frame, frame.area(), false))
+                .unwrap();
+            let content: String = terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            content.trim().to_string()
+        };
+
+        view.state.count = Some(128);
+        assert_eq!(draw(&mut view), "128 matches");
+        view.state.count = Some(1);
+        assert_eq!(draw(&mut view), "1 match");
+
+        // The matches take precedence over the hint
+        view.state.more = Some(12);
+        assert_eq!(draw(&mut view), "1 match");
+        view.state.count = None;
+        assert_eq!(draw(&mut view), "+12 more");
+        view.state.more = None;
+        assert_eq!(draw(&mut view), "ctrl+h: help");
+
+        // The empty and error states take precedence
+        view.state.count = Some(0);
+        view.state.is_empty = true;
+        assert_eq!(draw(&mut view), "no entry");
+        view.state.is_error = true;
+        assert_eq!(draw(&mut view), "invalid pattern");
+    }
+
+    #[test]
+    fn test_message() {
+        let mut view = ListIndicatorView {
+            state: ListIndicatorState::new("path".to_string()),
+            config: Arc::new(Config::default()),
+        };
+        let mut terminal = Terminal::new(TestBackend::new(16, 1)).unwrap();
+        let mut draw = |view: &mut ListIndicatorView| -> String {
+            terminal
+                .draw(|frame| view.draw(frame, frame.area(), false))
+                .unwrap();
+            let content: String = terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            content.trim().to_string()
+        };
+
+        view.handle_application_event(&ApplicationEvent {
+            id: String::from("footer.message"),
+            payload: Some(Arc::new(FooterMessagePayload {
+                objects_type: "path".to_string(),
+                message: "open failed".to_string(),
+            })),
+        });
+        assert_eq!(draw(&mut view), "open failed");
+        assert_eq!(draw(&mut view), "open failed");
+
+        // until the list changes
+        view.handle_application_event(&payload(false));
+        assert_eq!(draw(&mut view), "ctrl+h: help");
+    }
+
+    #[test]
+    fn test_no_match_flash() {
+        let config = Config {
+            no_match_flash: true,
+            ..Config::default()
+        };
+        let mut view = ListIndicatorView {
+            state: ListIndicatorState::new("path".to_string()),
+            config: Arc::new(config),
+        };
+        let mut terminal = Terminal::new(TestBackend::new(16, 1)).unwrap();
+        let mut draw = |view: &mut ListIndicatorView| -> bool {
+            terminal
+                .draw(|frame| view.draw(frame, frame.area(), false))
+                .unwrap();
+            terminal.backend().buffer().content[0]
+                .modifier
+                .contains(Modifier::REVERSED)
+        };
+
+        view.handle_application_event(&payload(false));
+        assert!(!draw(&mut view));
+
+        // The list becomes empty: a single flash
+        view.handle_application_event(&payload(true));
+        assert!(draw(&mut view));
+        assert!(!draw(&mut view));
+
+        // Still empty: no flash
+        view.handle_application_event(&payload(true));
+        assert!(!draw(&mut view));
+
+        // Empty again after some matches
+        view.handle_application_event(&payload(false));
+        view.handle_application_event(&payload(true));
+        assert!(draw(&mut view));
     }
 }
@@ -1,11 +1,17 @@
+mod clipboard;
 mod config;
 mod confirmation;
+mod details;
+mod dialog;
+mod existence_checker;
 mod expimp;
 mod gui;
 mod help;
 mod history_view_container;
 mod list_indicator_view;
 mod model;
+mod mounts;
+mod path_syntax;
 mod search_text_view;
 mod shortcut_editor;
 mod shortcut_view_container;
@@ -14,6 +20,7 @@ mod tableview;
 mod text_to_ansi;
 mod theme;
 mod tui;
+mod unicode;
 
 use std::{
     error::Error,
@@ -21,17 +28,37 @@ use std::{
     io::Write,
     path::{Path, PathBuf},
     sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand, error::ErrorKind};
 use config::Config;
 use expimp::load_paths_from_yaml;
-use log::{debug, error, info};
-use ratatui::text::Text;
+use log::{LevelFilter, debug, error, info, warn};
+use log4rs::{
+    append::file::FileAppender,
+    config::{Appender, Root},
+    encode::pattern::PatternEncoder,
+};
+use ratatui::{
+    style::Style,
+    text::{Line, Span, Text},
+};
+use serde::Serialize;
 use store::Store;
 
 use crate::{expimp::load_shortcuts_from_yaml, store::Shortcut, text_to_ansi::text_to_ansi};
 
+// The environment variable giving the file where the logs are written (see --log-file)
+const CDIR_LOG_VAR: &str = "CDIR_LOG";
+
+// The environment variable disabling the colors of the output when set and not empty
+// (see https://no-color.org)
+const NO_COLOR_VAR: &str = "NO_COLOR";
+
+// The format of the lines of the log file
+const LOG_PATTERN: &str = "{d(%Y-%m-%d %H:%M:%S%.3f)} {l} {t} - {m}{n}";
+
 /// cdir helps you to switch quickly and easily between directories
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -40,6 +67,48 @@ struct Args {
     #[arg(short, long)]
     config_file: Option<std::path::PathBuf>,
 
+    /// Profile, i.e. the database to use (default is "default")
+    #[arg(short, long)]
+    profile: Option<String>,
+
+    /// Use an empty in-memory database, discarded at exit (e.g. to try cdir)
+    #[arg(long)]
+    no_persist: bool,
+
+    /// Path to the database file to use instead of the configured one, created if it doesn't
+    /// exist (e.g. to inspect another database)
+    #[arg(long, conflicts_with_all = ["profile", "no_persist"])]
+    db: Option<PathBuf>,
+
+    /// Path to a file where the logs are written, instead of following the log configuration
+    /// file (also set by the CDIR_LOG environment variable)
+    #[arg(long)]
+    log_file: Option<PathBuf>,
+
+    /// Log the debug messages into the log file, and the trace messages too if repeated (-vv)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Print the path chosen the last time the GUI exited with one, if any (e.g. to go back
+    /// there from another shell), when no command is given
+    #[arg(long)]
+    last: bool,
+
+    /// Print the whole history as a table aligned on columns (date, visits, path), e.g. to read
+    /// it with less -R, when no command is given (without colors if NO_COLOR is set)
+    #[arg(long, conflicts_with = "last")]
+    dump: bool,
+
+    /// Print the version, the database path, schema version, size and numbers of entries, as
+    /// JSON, when no command is given
+    #[arg(long, conflicts_with_all = ["last", "dump"])]
+    info: bool,
+
+    /// Launch the GUI filtered with a search text, and print the chosen directory, when no
+    /// command is given (e.g. cdir src)
+    #[arg(trailing_var_arg = true, conflicts_with_all = ["last", "dump", "info"])]
+    query: Vec<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -47,11 +116,37 @@ struct Args {
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Launch the GUI
-    Gui { filename: Option<String> },
+    Gui {
+        filename: Option<String>,
+        /// a search text the lists are filtered with at launch
+        #[arg(short, long)]
+        query: Option<String>,
+        /// a file descriptor where the paths chosen with ctrl+k are written, one per line,
+        /// while the GUI stays open
+        #[arg(long)]
+        out_fd: Option<u32>,
+        /// choose the directory without launching the GUI if it is the only one of the
+        /// history matching the query
+        #[arg(long, requires = "query")]
+        select_one: bool,
+        /// keep the lists up to date with the paths recorded by the other shells, e.g. to
+        /// leave the GUI open as a dashboard
+        #[arg(long)]
+        watch: bool,
+    },
     /// Print the path to the configuration file
     ConfigFile,
     /// Add a directory path
-    AddPath { path: String },
+    AddPath {
+        path: String,
+        /// the command which was running when the directory was visited (e.g. git)
+        #[arg(long)]
+        via: Option<String>,
+    },
+    /// Record that a directory is entered, to measure the time spent in it
+    Enter { path: String },
+    /// Record that a directory is left, to measure the time spent in it
+    Leave { path: String },
     /// Import a path file
     ImportPaths { filename: String },
     /// Add a shortcut
@@ -59,15 +154,60 @@ enum Commands {
         name: String,
         path: String,
         description: Option<String>,
+        /// reject a name shadowing a command (see the reserved_shortcut_names option) or an
+        /// existing shortcut, instead of warning
+        #[arg(long)]
+        strict: bool,
     },
     /// Delete a shortcut
     DeleteShortcut { name: String },
     /// Print a shortcut
     PrintShortcut { name: String },
+    /// Query the shortcuts, e.g. for the shell completion
+    Shortcut {
+        #[command(subcommand)]
+        command: ShortcutCommands,
+    },
     /// Import a shortcuts file
     ImportShortcuts { filename: String },
     /// Print last paths
-    Lasts,
+    Lasts {
+        /// print all the paths instead of the last 10 ones
+        #[arg(long)]
+        all: bool,
+        /// print only the paths starting with a prefix (ignoring the ASCII case), e.g. the
+        /// directories under /home/user/src
+        #[arg(long, conflicts_with = "all")]
+        prefix: Option<String>,
+    },
+    /// Remove the paths which no longer exist
+    Prune {
+        /// print the paths which would be removed, without removing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Merge the entries of a same path, keeping the most recent one with all the visits
+    Dedup {
+        /// print the entries which would be removed, without removing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Remove the paths not visited for a number of days
+    Expire {
+        /// the number of days
+        days: u64,
+        /// print the paths which would be removed, without removing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Remove the oldest paths, to keep a maximum number of paths
+    Trim {
+        /// the number of paths to keep
+        max: usize,
+        /// print the paths which would be removed, without removing them
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Pretty print a path using shortcuts
     PrettyPrintPath {
         /// the path to pretty print
@@ -79,18 +219,318 @@ enum Commands {
     },
 }
 
-fn initialize_logs(config_path: &Option<PathBuf>) {
-    if let Some(config_path) = config_path.as_ref()
+#[derive(Subcommand, Debug)]
+enum ShortcutCommands {
+    /// Print the shortcut names, one per line (e.g. for the shell completion)
+    Names,
+}
+
+impl Args {
+    /// Rejects the options which only apply when no command is given, clap not declaring a
+    /// conflict between an option and the subcommands only for some of the options.
+    ///
+    /// ### Returns
+    /// The arguments, or the error to report as a usage error.
+    fn validated(self) -> Result<Args, clap::Error> {
+        if self.command.is_some() {
+            for (set, name) in [
+                (self.last, "--last"),
+                (self.dump, "--dump"),
+                (self.info, "--info"),
+            ] {
+                if set {
+                    return Err(Args::command().error(
+                        ErrorKind::ArgumentConflict,
+                        format!("the argument '{}' cannot be used with a command", name),
+                    ));
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Turns a search text given without command into the command launching the GUI with it,
+    /// the chosen directory being printed.
+    fn with_default_gui(mut self) -> Args {
+        if self.command.is_none() && !self.query.is_empty() {
+            self.command = Some(Commands::Gui {
+                filename: None,
+                query: Some(self.query.join(" ")),
+                out_fd: None,
+                select_one: false,
+                watch: false,
+            });
+        }
+        self
+    }
+}
+
+/// Initializes the logs: into the log file if given (option or environment variable), else as
+/// configured by the log configuration file if it exists. Otherwise nothing is logged, so that
+/// nothing is written over the GUI.
+fn initialize_logs(args: &Args, config_path: &Option<PathBuf>) {
+    let log_file = args
+        .log_file
+        .clone()
+        .or_else(|| std::env::var_os(CDIR_LOG_VAR).map(PathBuf::from));
+    if let Some(log_file) = log_file {
+        match file_log_config(&log_file, args.verbose) {
+            Ok(log_config) => {
+                if let Err(e) = log4rs::init_config(log_config) {
+                    eprintln!("Failed to initialize the logs: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to log into {}: {}", log_file.display(), e),
+        }
+    } else if let Some(config_path) = config_path.as_ref()
         && config_path.exists()
     {
         let _ = log4rs::init_file(config_path, Default::default());
     };
 }
 
+/// Builds the configuration of the logs written into a file, at the info level, or debug or
+/// trace with the number of `-v`.
+fn file_log_config(log_file: &Path, verbose: u8) -> Result<log4rs::Config, Box<dyn Error>> {
+    let level = match verbose {
+        0 => LevelFilter::Info,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    let appender = FileAppender::builder()
+        .encoder(Box::new(PatternEncoder::new(LOG_PATTERN)))
+        .build(log_file)?;
+    Ok(log4rs::Config::builder()
+        .appender(Appender::builder().build("file", Box::new(appender)))
+        .build(Root::builder().appender("file").build(level))?)
+}
+
+fn print_removed_paths(config: &Config, paths: Vec<store::Path>, dry_run: bool) {
+    for path in paths.iter() {
+        println!("{} {}", (config.date_formater)(path.date), path.path);
+    }
+    if dry_run {
+        println!("{} paths would be removed", paths.len());
+    } else {
+        println!("{} paths removed", paths.len());
+    }
+}
+
+/// Writes the shortcut names sorted by name, one per line, and nothing else.
+fn write_shortcut_names(store: &store::Store, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    for shortcut in store.list_all_shortcuts()? {
+        writeln!(out, "{}", shortcut.name)?;
+    }
+    Ok(())
+}
+
+/// Writes the path chosen the last time the GUI exited with one, if any, and nothing else.
+fn write_last_path(store: &Store, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = store.last_path()? {
+        writeln!(out, "{}", path)?;
+    }
+    Ok(())
+}
+
+/// Writes the whole history as a table aligned on columns, the most recent paths first: the
+/// date of the last visit, the number of visits and the path, under their names.
+///
+/// ### Parameters
+/// - `store`: the store of the paths
+/// - `config`: the configuration, for the date format and the styles
+/// - `color`: whether the dates and the paths are styled with ANSI sequences
+/// - `out`: where the table is written
+fn write_dump(
+    store: &Store,
+    config: &Config,
+    color: bool,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let mut rows: Vec<(String, String, String)> = vec![];
+    store.for_each_path("", true, |path| {
+        rows.push((
+            (config.date_formater)(path.date),
+            path.visits.to_string(),
+            path.path,
+        ))
+    })?;
+
+    // the columns are as wide as their widest cell, like in the GUI
+    let header = (
+        String::from("date"),
+        String::from("visits"),
+        String::from("path"),
+    );
+    let width = |cell: &String| Span::from(cell.as_str()).width();
+    let date_width = rows.iter().chain([&header]).map(|row| width(&row.0)).max();
+    let visits_width = rows.iter().chain([&header]).map(|row| width(&row.1)).max();
+    let (date_width, visits_width) = (date_width.unwrap_or(0), visits_width.unwrap_or(0));
+
+    for (i, (date, visits, path)) in [header].iter().chain(rows.iter()).enumerate() {
+        let (date_style, path_style) = match (color, i) {
+            (false, _) => (Style::default(), Style::default()),
+            (true, 0) => (config.styles.header_style, config.styles.header_style),
+            (true, _) => (config.styles.date_style, config.styles.path_style),
+        };
+        let line = Line::from(vec![
+            Span::styled(format!("{:<date_width$}", date), date_style),
+            Span::from(format!(" {:>visits_width$} ", visits)),
+            Span::styled(path.as_str(), path_style),
+        ]);
+        if color {
+            writeln!(out, "{}", text_to_ansi(&Text::from(line)))?;
+        } else {
+            writeln!(out, "{}", line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Opens the file descriptor given with `--out-fd`, inherited from the shell, e.g. with
+/// `3>> chosen.txt`.
+///
+/// ### Returns
+/// The output of the paths chosen with ctrl+k, or an error if the descriptor is not open.
+#[cfg(unix)]
+fn open_out_fd(fd: u32) -> Result<Box<dyn Write>, Box<dyn Error>> {
+    use std::{
+        mem::ManuallyDrop,
+        os::fd::{FromRawFd, RawFd},
+    };
+
+    let fd = RawFd::try_from(fd)?;
+    // SAFETY: the descriptor is inherited from the shell and only used by cdir. It is not
+    // closed if it is not open.
+    let file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+    if let Err(e) = file.metadata() {
+        return Err(Box::<dyn Error>::from(format!(
+            "invalid file descriptor {}: {}",
+            fd, e
+        )));
+    }
+    Ok(Box::new(ManuallyDrop::into_inner(file)))
+}
+
+/// The file descriptors are only inherited on Unix.
+#[cfg(not(unix))]
+fn open_out_fd(_fd: u32) -> Result<Box<dyn Write>, Box<dyn Error>> {
+    Err(Box::<dyn Error>::from(
+        "--out-fd is only supported on Unix, the chosen paths can't be sent",
+    ))
+}
+
+/// Returns whether the output can be colored, i.e. unless the `NO_COLOR` variable is set.
+fn color_enabled() -> bool { std::env::var_os(NO_COLOR_VAR).is_none_or(|value| value.is_empty()) }
+
+/// The information printed by the `info` command.
+/// db_path: the database file, or none if the database is in memory
+/// db_size: the size of the database (in bytes)
+#[derive(Serialize, Debug)]
+struct Info {
+    version: &'static str,
+    db_path: Option<PathBuf>,
+    schema_version: i64,
+    db_size: u64,
+    paths: usize,
+    shortcuts: usize,
+}
+
+/// Writes the information about cdir and its database as JSON, and nothing else.
+fn write_info(
+    args: &Args,
+    config: &Config,
+    store: &Store,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let info = Info {
+        version: env!("CARGO_PKG_VERSION"),
+        db_path: if args.no_persist {
+            None
+        } else {
+            args.db.clone().or(config.db_path.clone())
+        },
+        schema_version: store.schema_version(),
+        db_size: store.database_size()?,
+        paths: store.count_paths("", false, true)?,
+        shortcuts: store.count_shortcuts("", false)?,
+    };
+    serde_json::to_writer_pretty(&mut *out, &info)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Returns why a shortcut name may be a mistake: it is the name of a command (one of the
+/// `reserved_shortcut_names`), or of an existing shortcut, which would be replaced.
+fn shortcut_name_warning(
+    store: &Store,
+    config: &Config,
+    name: &str,
+) -> Result<Option<String>, rusqlite::Error> {
+    let name = name.trim();
+    if config
+        .reserved_shortcut_names
+        .iter()
+        .any(|reserved| reserved == name)
+    {
+        Ok(Some(format!(
+            "the shortcut name '{}' shadows a command",
+            name
+        )))
+    } else {
+        Ok(store
+            .find_shortcut(name)?
+            .map(|shortcut| format!("the shortcut '{}' to {} is replaced", name, shortcut.path)))
+    }
+}
+
+/// Adds a shortcut, warning into `err` if its name may be a mistake, or rejecting it if strict.
+fn add_shortcut(
+    store: &Store,
+    config: &Config,
+    name: &str,
+    path: &str,
+    description: Option<&str>,
+    strict: bool,
+    err: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(warning) = shortcut_name_warning(store, config, name)? {
+        if strict {
+            error!("Rejected shortcut: {}", warning);
+            return Err(Box::<dyn Error>::from(warning));
+        }
+        warn!("{}", warning);
+        writeln!(err, "warning: {}", warning)?;
+    }
+    store.add_shortcut(name, path, description)?;
+    Ok(())
+}
+
+/// Opens the database given on the command line, otherwise the configured one.
+fn open_store(args: &Args, config: &Config) -> Result<Store, Box<dyn Error>> {
+    let store = if args.no_persist {
+        Store::in_memory()?
+    } else {
+        let db_path = args
+            .db
+            .as_ref()
+            .or(config.db_path.as_ref())
+            .ok_or(config::NO_HOME_ERROR)?;
+        Store::new(db_path)
+    };
+    Ok(store
+        .with_revisit_window(config.revisit_window_seconds)
+        .with_stored_frecency(config.stored_frecency)
+        .with_normalize_unicode(config.normalize_unicode)
+        .with_lenient_shortcut_lookup(config.lenient_shortcut_lookup))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     color_eyre::install()?;
-    let args = Args::parse();
+    let args = Args::parse()
+        .validated()
+        .unwrap_or_else(|e| e.exit())
+        .with_default_gui();
     let mut config = match Config::load(args.config_file.clone()) {
         Ok(config) => config,
         Err(e) => {
@@ -98,22 +538,49 @@ async fn main() -> Result<(), Box<dyn Error>> {
             return Err(Box::<dyn Error>::from(e));
         }
     };
-    initialize_logs(&config.log_config_path);
+    initialize_logs(&args, &config.log_config_path);
+    if let Err(e) = config.select_profile(args.profile.clone()) {
+        error!("{}", e);
+        return Err(Box::<dyn Error>::from(e));
+    }
     config.process();
 
     info!("Starting with args={args:?}");
 
     let config = Arc::new(config);
 
-    let store = Store::new(
-        config
-            .db_path
-            .as_ref()
-            .expect("missing db_path into the configuration"),
-    );
+    let store = open_store(&args, &config)?;
     match &args.command {
-        Some(Commands::Gui { filename }) => {
-            if let Some(s) = gui::gui(store, config.clone()).await {
+        Some(Commands::Gui {
+            filename,
+            query,
+            out_fd,
+            select_one,
+            watch,
+        }) => {
+            let output = match out_fd {
+                Some(fd) => Some(open_out_fd(*fd)?),
+                None => None,
+            };
+            let single_match = match query {
+                Some(query) if *select_one => gui::single_match(&store, &config, query)?,
+                _ => None,
+            };
+            let chosen = match single_match {
+                Some(path) => Some(path),
+                None => {
+                    gui::gui(
+                        store.clone(),
+                        config.clone(),
+                        output,
+                        query.as_deref(),
+                        *watch,
+                    )
+                    .await?
+                }
+            };
+            if let Some(s) = chosen {
+                gui::record_last_path(&store, &s);
                 match filename {
                     None => {
                         println!("{}", s);
@@ -132,15 +599,23 @@ async fn main() -> Result<(), Box<dyn Error>> {
             } else if let Ok(config_file) = std::env::var(config::CDIR_CONFIG_VAR) {
                 println!("{}", config_file);
             } else {
-                let mut cpath = dirs::home_dir().unwrap();
-                cpath.push(".config");
-                cpath.push("cdir");
-                cpath.push("config.yaml");
-                println!("{}", cpath.display());
+                println!("{}", Config::build_default_config_path().display());
             }
         }
-        Some(Commands::AddPath { path }) => {
-            store.add_path(path).unwrap();
+        Some(Commands::AddPath { path, via }) => {
+            store.add_path_with_context(path, via.as_deref()).unwrap();
+        }
+        Some(Commands::Enter { path }) => {
+            store.record_enter(
+                path,
+                SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            )?;
+        }
+        Some(Commands::Leave { path }) => {
+            store.record_leave(
+                path,
+                SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            )?;
         }
         Some(Commands::ImportPaths { filename }) => {
             load_paths_from_yaml(store, PathBuf::from(filename));
@@ -149,11 +624,18 @@ async fn main() -> Result<(), Box<dyn Error>> {
             name,
             path,
             description,
+            strict,
         }) => {
             debug!("AddShortcut {} {} {:?}", name, path, description);
-            store
-                .add_shortcut(name, path, description.as_ref().map(|s| s.as_str()))
-                .unwrap()
+            add_shortcut(
+                &store,
+                &config,
+                name,
+                path,
+                description.as_deref(),
+                *strict,
+                &mut std::io::stderr(),
+            )?;
         }
         Some(Commands::DeleteShortcut { name }) => {
             debug!("DeleteShortcut {}", name);
@@ -161,20 +643,48 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
         Some(Commands::PrintShortcut { name }) => {
             debug!("PrintShortcut {}", name);
-            match store.find_shortcut(name) {
-                None => {}
-                Some(s) => {
-                    print!("{}", s.path)
-                }
-            };
+            if let Some(s) = store.resolve_shortcut(name)? {
+                print!("{}", s.path)
+            }
+        }
+        Some(Commands::Shortcut {
+            command: ShortcutCommands::Names,
+        }) => {
+            write_shortcut_names(&store, &mut std::io::stdout().lock())?;
         }
         Some(Commands::ImportShortcuts { filename }) => {
             load_shortcuts_from_yaml(store, PathBuf::from(filename));
         }
-        Some(Commands::Lasts) => {
-            let list = store.list_paths(0, 10, "", false).unwrap();
-            list.iter()
-                .for_each(|s| println!("{} {}", (config.date_formater)(s.date), s.path));
+        Some(Commands::Lasts { all, prefix }) => {
+            if *all {
+                // streamed, as the history can be huge
+                let mut stdout = std::io::stdout().lock();
+                store.for_each_path("", false, |s| {
+                    let _ = writeln!(stdout, "{} {}", (config.date_formater)(s.date), s.path);
+                })?;
+            } else {
+                let list = match prefix {
+                    // anchored, so that the query uses the index on the paths
+                    Some(prefix) => store.list_paths_prefix(0, 10, prefix)?,
+                    None => store.list_paths(0, 10, "", false, false).unwrap(),
+                };
+                list.iter()
+                    .for_each(|s| println!("{} {}", (config.date_formater)(s.date), s.path));
+            }
+        }
+        Some(Commands::Prune { dry_run }) => {
+            print_removed_paths(&config, store.prune_paths(*dry_run)?, *dry_run);
+        }
+        Some(Commands::Dedup { dry_run }) => {
+            print_removed_paths(&config, store.dedup_paths(*dry_run)?, *dry_run);
+        }
+        Some(Commands::Expire { days, dry_run }) => {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let before = now.saturating_sub(days.saturating_mul(86_400)) as i64;
+            print_removed_paths(&config, store.expire_paths(before, *dry_run)?, *dry_run);
+        }
+        Some(Commands::Trim { max, dry_run }) => {
+            print_removed_paths(&config, store.trim_paths(*max, *dry_run)?, *dry_run);
         }
         Some(Commands::PrettyPrintPath {
             path,
@@ -189,7 +699,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 gui::Gui::shorten_path(config.as_ref(), &shortcuts, path, max_width, true);
             let shortened_line = shortened_line
                 .unwrap_or_else(|| {
-                    gui::Gui::reduce_path(path.clone(), max_width, config1.styles.home_tilde_style)
+                    gui::Gui::display_path(
+                        path.clone(),
+                        tableview::home_directory(),
+                        max_width,
+                        config1.styles.home_tilde_style,
+                        config1.substitute_home,
+                    )
                 })
                 .style(config2.styles.path_style);
             if style.is_none_or(|s| s) {
@@ -198,6 +714,20 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 print!("{}", shortened_line);
             }
         }
+        None if args.last => {
+            write_last_path(&store, &mut std::io::stdout().lock())?;
+        }
+        None if args.dump => {
+            write_dump(
+                &store,
+                &config,
+                color_enabled(),
+                &mut std::io::stdout().lock(),
+            )?;
+        }
+        None if args.info => {
+            write_info(&args, &config, &store, &mut std::io::stdout().lock())?;
+        }
         None => {
             println!("Use the 'c' shell alias to launch the GUI.");
             println!("Use --help to see available commands.");
@@ -207,3 +737,327 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcut_names() {
+        let store = store::Store::setup_test_store();
+        store.add_shortcut("work", "/home/user/work", None).unwrap();
+        store
+            .add_shortcut("docs", "/home/user/docs", Some("documents"))
+            .unwrap();
+        store.add_shortcut("tmp", "/tmp", None).unwrap();
+
+        let mut out = vec![];
+        write_shortcut_names(&store, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "docs\ntmp\nwork\n");
+    }
+
+    #[test]
+    fn test_last_path() {
+        let args = Args::try_parse_from(["cdir", "--last"]).unwrap();
+        assert!(args.last && args.command.is_none());
+        let args = Args::try_parse_from(["cdir", "--last", "gui"]).unwrap();
+        assert_eq!(
+            args.validated().unwrap_err().kind(),
+            ErrorKind::ArgumentConflict
+        );
+
+        // nothing printed until a path is chosen
+        let store = store::Store::setup_test_store();
+        let mut out = vec![];
+        write_last_path(&store, &mut out).unwrap();
+        assert!(out.is_empty());
+
+        gui::record_last_path(&store, "/tmp");
+        gui::record_last_path(&store, "/home/user/src");
+        let mut out = vec![];
+        write_last_path(&store, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "/home/user/src\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_open_out_fd() {
+        use std::os::fd::IntoRawFd;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chosen.txt");
+        let fd = File::create(&path).unwrap().into_raw_fd();
+        let mut output = open_out_fd(fd as u32).unwrap();
+        writeln!(output, "/tmp").unwrap();
+        drop(output);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "/tmp\n");
+
+        // a descriptor not open, or not one
+        assert!(open_out_fd(1_000_000).is_err());
+        assert!(open_out_fd(u32::MAX).is_err());
+    }
+
+    #[test]
+    fn test_dump() {
+        let args = Args::try_parse_from(["cdir", "--dump"]).unwrap();
+        assert!(args.dump && args.command.is_none());
+        assert!(Args::try_parse_from(["cdir", "--dump", "--last"]).is_err());
+        let args = Args::try_parse_from(["cdir", "--dump", "lasts"]).unwrap();
+        assert_eq!(
+            args.validated().unwrap_err().kind(),
+            ErrorKind::ArgumentConflict
+        );
+
+        let store = store::Store::setup_test_store();
+        for i in 0..12 {
+            store
+                .add_path_with_time("/home/user/src", 1_000_000 + i * 100_000)
+                .unwrap();
+        }
+        store.add_path_with_time("/tmp", 900_000).unwrap();
+        store.add_path_with_time("/hidden", 800_000).unwrap();
+        let hidden = store.list_paths(0, 10, "hidden", false, false).unwrap();
+        store.set_path_hidden(hidden[0].id, true).unwrap();
+
+        let config = Config::default();
+        let mut out = vec![];
+        write_dump(&store, &config, false, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 4, "{}", out);
+
+        // all the rows, the most recent first, the hidden ones included
+        let date = (config.date_formater)(2_100_000);
+        assert_eq!(lines[1], format!("{}     12 /home/user/src", date));
+        assert!(lines[2].ends_with(" 1 /tmp"), "{}", out);
+        assert!(lines[3].ends_with(" 1 /hidden"), "{}", out);
+
+        // the paths start at the same column, and the visits are aligned on the right
+        let column = lines[0].find("path").unwrap();
+        assert!(lines[0].starts_with("date"), "{}", out);
+        for line in &lines[1..] {
+            assert_eq!(line.find('/'), Some(column), "{}", out);
+            assert_eq!(&line[column - 1..column], " ");
+        }
+        assert_eq!(lines[0].find("visits").map(|i| i + 6), Some(column - 1));
+
+        // no ANSI sequence without colors, some with
+        assert!(!out.contains('\x1b'));
+        let mut out = vec![];
+        write_dump(&store, &config, true, &mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains('\x1b'));
+    }
+
+    #[test]
+    fn test_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("cdir.db");
+        let config = Config {
+            db_path: Some(db_path.clone()),
+            ..Config::default()
+        };
+        let args = Args::try_parse_from(["cdir", "--info"]).unwrap();
+        assert!(args.info && args.command.is_none());
+        let store = open_store(&args, &config).unwrap();
+        store.add_path("/tmp").unwrap();
+        store.add_path("/home").unwrap();
+        store.add_shortcut("tmp", "/tmp", None).unwrap();
+
+        let mut out = vec![];
+        write_info(&args, &config, &store, &mut out).unwrap();
+        let info: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(info["db_path"], db_path.to_str().unwrap());
+        assert_eq!(info["schema_version"], 11);
+        assert_eq!(info["paths"], 2);
+        assert_eq!(info["shortcuts"], 1);
+        assert!(info["db_size"].as_u64().unwrap() > 0);
+
+        // in memory: no database path
+        let args = Args::try_parse_from(["cdir", "--no-persist", "--info"]).unwrap();
+        let store = open_store(&args, &config).unwrap();
+        let mut out = vec![];
+        write_info(&args, &config, &store, &mut out).unwrap();
+        let info: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert!(info["db_path"].is_null());
+        assert_eq!(info["paths"], 0);
+
+        // only without command
+        let args = Args::try_parse_from(["cdir", "--info", "lasts"]).unwrap();
+        assert_eq!(
+            args.validated().unwrap_err().kind(),
+            ErrorKind::ArgumentConflict
+        );
+        assert!(Args::try_parse_from(["cdir", "--info", "--last"]).is_err());
+    }
+
+    #[test]
+    fn test_add_shortcut_warnings() {
+        let store = store::Store::setup_test_store();
+        let config = Config::default();
+        let add = |name: &str, strict: bool| -> (bool, String) {
+            let mut err = vec![];
+            let added = add_shortcut(&store, &config, name, "/tmp", None, strict, &mut err).is_ok();
+            (added, String::from_utf8(err).unwrap())
+        };
+
+        assert_eq!(add("tmp", false), (true, String::new()));
+
+        // A reserved name: added with a warning, or rejected if strict
+        assert_eq!(
+            add("ls", false),
+            (
+                true,
+                "warning: the shortcut name 'ls' shadows a command\n".to_string()
+            )
+        );
+        assert_eq!(add("cd", true), (false, String::new()));
+        assert!(store.find_shortcut("cd").unwrap().is_none());
+
+        // An existing shortcut
+        assert_eq!(
+            add("tmp", false),
+            (
+                true,
+                "warning: the shortcut 'tmp' to /tmp is replaced\n".to_string()
+            )
+        );
+        assert!(!add("tmp", true).0);
+
+        // No reserved name
+        let config = Config {
+            reserved_shortcut_names: vec![],
+            ..Config::default()
+        };
+        let mut err = vec![];
+        add_shortcut(&store, &config, "git", "/tmp", None, true, &mut err).unwrap();
+        assert!(err.is_empty());
+    }
+
+    #[test]
+    fn test_file_log_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = |verbose: u8| -> String {
+            let log_file = dir.path().join(format!("cdir-{}.log", verbose));
+            let logger = log4rs::Logger::new(file_log_config(&log_file, verbose).unwrap());
+            for (level, message) in [
+                (log::Level::Info, "started"),
+                (log::Level::Debug, "details"),
+                (log::Level::Trace, "more details"),
+            ] {
+                log::Log::log(
+                    &logger,
+                    &log::Record::builder()
+                        .level(level)
+                        .target("cdir::gui")
+                        .args(format_args!("{}", message))
+                        .build(),
+                );
+            }
+            log::Log::flush(&logger);
+            std::fs::read_to_string(&log_file).unwrap()
+        };
+
+        let content = log(0);
+        assert_eq!(content.lines().count(), 1);
+        assert!(
+            content.ends_with(" INFO cdir::gui - started\n"),
+            "{}",
+            content
+        );
+        assert_eq!(log(1).lines().count(), 2);
+        assert!(log(1).contains(" DEBUG cdir::gui - details"));
+        assert_eq!(log(2).lines().count(), 3);
+
+        let args =
+            Args::try_parse_from(["cdir", "--log-file", "cdir.log", "-vv", "--info"]).unwrap();
+        assert_eq!(args.log_file, Some(PathBuf::from("cdir.log")));
+        assert_eq!(args.verbose, 2);
+    }
+
+    #[test]
+    fn test_db_option() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("other").join("other.db");
+        let configured_db_path = dir.path().join("cdir.db");
+        let config = Config {
+            db_path: Some(configured_db_path.clone()),
+            ..Config::default()
+        };
+
+        // the database is created, and used for the reads and writes
+        let args = Args::try_parse_from([
+            "cdir",
+            "--db",
+            db_path.to_str().unwrap(),
+            "add-path",
+            "/tmp",
+        ])
+        .unwrap();
+        let store = open_store(&args, &config).unwrap();
+        store.add_path("/tmp").unwrap();
+        drop(store);
+        assert!(db_path.exists());
+        assert!(!configured_db_path.exists());
+        let store = open_store(&args, &config).unwrap();
+        assert_eq!(
+            store.list_paths(0, 10, "", false, false).unwrap()[0].path,
+            "/tmp"
+        );
+
+        // without the option, the configured database is used
+        let args = Args::try_parse_from(["cdir", "lasts"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Commands::Lasts {
+                all: false,
+                prefix: None
+            })
+        ));
+        let prefix_args = Args::try_parse_from(["cdir", "lasts", "--prefix", "/home"]).unwrap();
+        assert!(matches!(
+            prefix_args.command,
+            Some(Commands::Lasts { prefix: Some(ref prefix), .. }) if prefix == "/home"
+        ));
+        assert!(Args::try_parse_from(["cdir", "lasts", "--all", "--prefix", "/home"]).is_err());
+        let store = open_store(&args, &config).unwrap();
+        assert!(configured_db_path.exists());
+        assert!(
+            store
+                .list_paths(0, 10, "", false, false)
+                .unwrap()
+                .is_empty()
+        );
+
+        let args =
+            Args::try_parse_from(["cdir", "gui", "out.txt", "-q", "src", "--select-one"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Commands::Gui { query: Some(ref query), select_one: true, .. }) if query == "src"
+        ));
+        // a single argument is the file, not the query
+        let args = Args::try_parse_from(["cdir", "gui", "src"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Commands::Gui { filename: Some(ref filename), query: None, .. })
+                if filename == "src"
+        ));
+        assert!(Args::try_parse_from(["cdir", "gui", "out.txt", "src"]).is_err());
+        // without command, the words are the query of the GUI
+        let args = Args::try_parse_from(["cdir", "src", "cdir"])
+            .unwrap()
+            .with_default_gui();
+        assert!(matches!(
+            args.command,
+            Some(Commands::Gui { filename: None, query: Some(ref query), .. }) if query == "src cdir"
+        ));
+        let args = Args::try_parse_from(["cdir"]).unwrap().with_default_gui();
+        assert!(args.command.is_none());
+        assert!(Args::try_parse_from(["cdir", "--last", "src"]).is_err());
+        // nothing to select without a query
+        assert!(Args::try_parse_from(["cdir", "gui", "--select-one"]).is_err());
+
+        assert!(Args::try_parse_from(["cdir", "--db", "a.db", "--profile", "work"]).is_err());
+        assert!(Args::try_parse_from(["cdir", "--db", "a.db", "--no-persist"]).is_err());
+    }
+}
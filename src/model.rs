@@ -1,16 +1,41 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use log::{debug, error, trace};
 use tokio::sync::broadcast;
 
-use crate::tui::{GenericEvent, event::ApplicationEvent};
+use crate::{
+    store::{ListOrder, Ordered},
+    tui::{GenericEvent, event::ApplicationEvent},
+};
 
 // "data.payload"
 pub struct DataStatePayload {
     pub objects_type: String,
     pub is_empty: bool,
+    pub is_error: bool,
+    pub count: Option<usize>,
+    /// the name of the order of the entries, if they can be sorted
+    pub sort_name: Option<&'static str>,
+    pub include_hidden: bool,
+    /// the number of entries after the loaded ones, if hinted and any
+    pub more: Option<usize>,
+    /// whether a query is about to run, the entries being outdated until it returns
+    pub is_searching: bool,
 }
 
+/// The duration from which a query is slow: the next search is announced before it runs.
+const SLOW_QUERY: Duration = Duration::from_millis(100);
+
+/// The number of entries among which an entry is looked for, fetched with a single query (see
+/// `select_matching`): a fuzzy query ranks all the matches whatever the range requested.
+const SEARCH_LIMIT: usize = 10_000;
+
+/// The error of a data source, whatever its backend: the model only reports it.
+pub(crate) type DataError = Box<dyn std::error::Error>;
+
 /// A type alias for a function that retrieves a list of data entries based on the given parameters.
 ///
 /// # Type Parameters
@@ -22,12 +47,54 @@ pub struct DataStatePayload {
 /// - `count`: The maximum number of data entries to retrieve.
 /// - `filter`: A string used as a filter or search term for the data entries.
 /// - `fuzzy`: If true, perform a fuzzy search ; else perform an exact search
+/// - `sort_mode`: The order of the data entries, among the orders of their type (ignored by the
+///   lists which can't be sorted)
+/// - `include_hidden`: Whether the hidden entries are listed (ignored by the lists without
+///   hidden entries)
 ///
 /// # Returns
-/// - `Result<Vec<T>, rusqlite::Error>`: A `Result` containing either a vector of data entries
-///   (`Vec<T>`) on success or a `rusqlite::Error` on failure.
+/// - `Result<Vec<T>, DataError>`: A `Result` containing either a vector of data entries
+///   (`Vec<T>`) on success or the error of the data source on failure.
 pub(crate) type ListFunction<T> =
-    dyn Fn(usize, usize, &str, bool) -> Result<Vec<T>, rusqlite::Error>;
+    dyn Fn(usize, usize, &str, bool, <T as Ordered>::Order, bool) -> Result<Vec<T>, DataError>;
+
+/// A type alias for a function that counts the data entries matching a filter, with the same
+/// semantics as the `ListFunction`.
+///
+/// # Parameters
+/// - `filter`: A string used as a filter or search term for the data entries.
+/// - `fuzzy`: If true, perform a fuzzy search ; else perform an exact search
+/// - `include_hidden`: Whether the hidden entries are counted
+pub(crate) type CountFunction = dyn Fn(&str, bool, bool) -> Result<usize, DataError>;
+
+/// What an update did to the data view, when the entries could be fetched.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum UpdateOutcome {
+    /// Other entries are shown
+    Updated,
+    /// The entries shown are kept: they already cover the range, there is nothing after them,
+    /// or nothing changed
+    Unchanged,
+    /// No entry was found in the range: the view is emptied if forced, else kept
+    Empty,
+}
+
+/// The entries fetched around the data view, to scroll without querying again.
+struct Prefetched<T> {
+    /// The index of the first entry fetched
+    first: usize,
+    entries: Vec<T>,
+    /// Whether the entries go up to the last one
+    at_end: bool,
+}
+
+/// The data of a `DataViewModel`.
+pub(crate) enum DataState<T> {
+    /// No entry was received yet: the first query is issued (or about to be)
+    Loading,
+    /// The entries returned by the last query (empty if nothing matched)
+    Loaded(Vec<T>),
+}
 
 /// A model representing a view of data, typically used for managing and displaying
 /// a subset of entries with filtering and pagination capabilities.
@@ -37,23 +104,56 @@ pub(crate) type ListFunction<T> =
 /// - `T`: The type of the entries being managed.
 ///
 /// # Fields
-/// - `entries`: An optional vector containing the entries of type `T` to be displayed.
+/// - `data`: The entries of type `T` to be displayed, once loaded.
 /// - `list_fn`: A boxed function or closure responsible for fetching or generating the list of entries.
 /// - `first`: The index of the first entry in the current view.
 /// - `length`: The number of entries to display in the current view.
 /// - `filter`: A string used to filter the entries based on some criteria.
-pub(crate) struct DataViewModel<T> {
+/// - `error`: Whether the last fetch failed (the previous entries are kept).
+/// - `count`: The number of entries matching the filter, if a filter is set (cached until the
+///   filter changes).
+/// - `sort_mode`: The order of the entries, or `None` if they can't be sorted.
+/// - `include_hidden`: Whether the hidden entries are listed, or `None` if there are no hidden
+///   entries.
+/// - `more_hint`: Whether the number of entries after the loaded ones is published, the
+///   entries being counted even without filter.
+/// - `total`: The number of entries matching the filter, or of all the entries without filter
+///   (counted on demand, unless hinted).
+/// - `counted`: Whether `total` is up to date with the filter.
+/// - `prefetched`: The entries fetched with the data view and a margin of half of its length
+///   before and after it, serving the small scrolls.
+/// - `stale`: Whether the loaded and prefetched entries are outdated (see `invalidate`).
+/// - `selected`: The absolute position of the selected entry, if any, kept into the loaded
+///   entries by the selection methods.
+/// - `searching`: Whether a search was announced (see `begin_search`) and its query did not
+///   return yet.
+/// - `slow`: Whether the last query took at least `slow_query`.
+pub(crate) struct DataViewModel<T: Ordered> {
     objects_type: String,
     tx: broadcast::Sender<GenericEvent>,
-    pub(crate) entries: Option<Vec<T>>,
+    pub(crate) data: DataState<T>,
     pub(crate) list_fn: Box<ListFunction<T>>,
     pub(crate) first: usize,
     pub(crate) length: u16,
     filter: String,
     fuzzy_match: bool,
+    pub(crate) error: bool,
+    count_fn: Option<Box<CountFunction>>,
+    pub(crate) count: Option<usize>,
+    pub(crate) sort_mode: Option<T::Order>,
+    pub(crate) include_hidden: Option<bool>,
+    more_hint: bool,
+    total: Option<usize>,
+    counted: bool,
+    prefetched: Option<Prefetched<T>>,
+    stale: bool,
+    selected: Option<usize>,
+    searching: bool,
+    slow: bool,
+    pub(crate) slow_query: Duration,
 }
 
-impl<T: Clone> DataViewModel<T> {
+impl<T: Clone + Ordered> DataViewModel<T> {
     /// Creates a new instance of `DataViewModel`.
     ///
     /// ### Parameters
@@ -66,26 +166,96 @@ impl<T: Clone> DataViewModel<T> {
         objects_type: String,
         tx: broadcast::Sender<GenericEvent>,
         list_fn: Box<ListFunction<T>>,
+        count_fn: Option<Box<CountFunction>>,
         fuzzy_match: bool,
+        sort_mode: Option<T::Order>,
+        include_hidden: Option<bool>,
     ) -> Self {
         DataViewModel {
             objects_type,
             tx,
-            entries: Option::None,
+            data: DataState::Loading,
             list_fn,
             first: 0,
             length: 0,
             filter: String::new(),
             fuzzy_match,
+            error: false,
+            count_fn,
+            count: None,
+            sort_mode,
+            include_hidden,
+            more_hint: false,
+            total: None,
+            counted: false,
+            prefetched: None,
+            stale: false,
+            selected: None,
+            searching: false,
+            slow: false,
+            slow_query: SLOW_QUERY,
         }
     }
 
+    /// Publishes the number of entries after the loaded ones from now on.
+    pub(crate) fn set_more_hint(&mut self) {
+        self.more_hint = true;
+        self.refresh_count();
+    }
+
+    /// Returns the number of entries after the loaded ones, if hinted and any.
+    pub(crate) fn more(&self) -> Option<usize> {
+        if !self.more_hint {
+            return None;
+        }
+        self.total
+            .map(|total| total.saturating_sub(self.first + self.length as usize))
+            .filter(|more| *more > 0)
+    }
+
+    pub(crate) fn objects_type(&self) -> &str { &self.objects_type }
+
+    /// Returns the text the entries are filtered with, empty if none.
+    pub(crate) fn filter(&self) -> &str { &self.filter }
+
+    /// Returns the entries to display, or `None` if there is none (yet).
+    pub(crate) fn entries(&self) -> Option<&Vec<T>> {
+        match &self.data {
+            DataState::Loaded(entries) if !entries.is_empty() => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the first entries are still being fetched.
+    pub(crate) fn is_loading(&self) -> bool { matches!(self.data, DataState::Loading) }
+
+    /// Returns whether a search was announced and its query did not return yet.
+    pub(crate) fn is_searching(&self) -> bool { self.searching }
+
+    /// Returns whether the last query was slow: the next search should be announced, so that
+    /// the user knows that the entries are outdated until it returns.
+    pub(crate) fn is_slow(&self) -> bool { self.slow }
+
+    /// Announces that a search is about to run: the searching state is published until the
+    /// next update.
+    pub(crate) fn begin_search(&mut self) {
+        debug!("begin_search");
+        self.searching = true;
+        self.publish();
+    }
+
     fn publish(&self) {
         let event = GenericEvent::ApplicationEvent(ApplicationEvent {
             id: String::from("data.payload"),
             payload: Some(Arc::new(DataStatePayload {
                 objects_type: self.objects_type.clone(),
                 is_empty: self.length == 0,
+                is_error: self.error,
+                count: self.count,
+                sort_name: self.sort_mode.map(ListOrder::name),
+                include_hidden: self.include_hidden.unwrap_or(false),
+                more: self.more(),
+                is_searching: self.searching,
             })),
         });
         debug!("model sending event={:?}", event);
@@ -105,11 +275,26 @@ impl<T: Clone> DataViewModel<T> {
     /// `true` if the current data view is a subset of the specified range and filter;
     /// otherwise, `false`.
     fn is_a_subset_of(&mut self, first: usize, length: u16) -> bool {
-        self.entries.is_some()
+        self.entries().is_some()
             && (first >= self.first)
             && (first + length as usize <= self.first + self.length as usize)
     }
 
+    /// Whether exactly the range is loaded, with the current filter, order and entries:
+    /// nothing was invalidated since, and the last fetch did not fail. A range past the last
+    /// entry is loaded up to it, if the entries are counted.
+    fn is_loaded(&self, first: usize, length: u16) -> bool {
+        let available = match self.total {
+            Some(total) if self.counted => (length as usize).min(total.saturating_sub(first)),
+            _ => length as usize,
+        };
+        !self.stale
+            && !self.error
+            && self.entries().is_some()
+            && first == self.first
+            && available == self.length as usize
+    }
+
     /// Updates the current data view to a subset of the specified range and filter,
     /// if possible.
     ///
@@ -124,9 +309,10 @@ impl<T: Clone> DataViewModel<T> {
         if !self.is_a_subset_of(first, length) {
             return false;
         }
-        if let Some(self_entries) = &self.entries {
-            let offset = self.first - first;
-            self.entries = Some(self_entries[offset..(length as usize)].to_vec());
+        if let Some(self_entries) = self.entries() {
+            let offset = (first - self.first).min(self_entries.len());
+            let end = (offset + length as usize).min(self_entries.len());
+            self.data = DataState::Loaded(self_entries[offset..end].to_vec());
         }
         self.first = first;
         self.length = length;
@@ -134,78 +320,277 @@ impl<T: Clone> DataViewModel<T> {
         true
     }
 
-    pub(crate) fn set_fuzzy_match(&mut self, fuzzy_match: bool) {
+    /// Marks the entries as outdated, e.g. because the query or the database changed: the next
+    /// update queries the entries, even if the range is already loaded or prefetched.
+    pub(crate) fn invalidate(&mut self) {
+        trace!("invalidate");
+        self.prefetched = None;
+        self.stale = true;
+    }
+
+    pub(crate) fn set_fuzzy_match(
+        &mut self,
+        fuzzy_match: bool,
+    ) -> Result<UpdateOutcome, DataError> {
         debug!("fuzzy_match={}", fuzzy_match);
         if self.fuzzy_match == fuzzy_match {
-            return;
+            return Ok(UpdateOutcome::Unchanged);
         }
         self.fuzzy_match = fuzzy_match;
-        self.update(self.first, self.length, true);
+        self.refresh_count();
+        self.invalidate();
+        self.update(self.first, self.length, true)
+    }
+
+    /// Switches to the next sort mode, if the entries can be sorted, and shows the first
+    /// entries in this new order.
+    ///
+    /// ### Parameters
+    /// - `length`: The length of the range to view.
+    ///
+    /// ### Returns
+    /// See `update`.
+    pub(crate) fn cycle_sort_mode(&mut self, length: u16) -> Result<UpdateOutcome, DataError> {
+        let Some(sort_mode) = self.sort_mode else {
+            return Ok(UpdateOutcome::Unchanged);
+        };
+        self.sort_mode = Some(sort_mode.next());
+        debug!("sort_mode={:?}", self.sort_mode);
+        self.invalidate();
+        let result = self.update(0, length, true);
+        self.select_first();
+        result
     }
 
-    pub(crate) fn update_filter(&mut self, length: u16, filter: &str, fuzzy: bool) {
-        self.filter = String::from(filter);
-        self.fuzzy_match = fuzzy;
-        self.update(0, length, true);
+    /// Switches between listing the hidden entries or not, if there are hidden entries, and
+    /// shows the first entries: the positions of the entries change.
+    ///
+    /// ### Parameters
+    /// - `length`: The length of the range to view.
+    ///
+    /// ### Returns
+    /// See `update`.
+    pub(crate) fn toggle_include_hidden(
+        &mut self,
+        length: u16,
+    ) -> Result<UpdateOutcome, DataError> {
+        let Some(include_hidden) = self.include_hidden else {
+            return Ok(UpdateOutcome::Unchanged);
+        };
+        self.include_hidden = Some(!include_hidden);
+        debug!("include_hidden={:?}", self.include_hidden);
+        self.refresh_count();
+        self.invalidate();
+        let result = self.update(0, length, true);
+        self.select_first();
+        result
+    }
+
+    fn list(&self, first: usize, length: usize) -> Result<Vec<T>, DataError> {
+        (self.list_fn)(
+            first,
+            length,
+            &self.filter,
+            self.fuzzy_match,
+            self.sort_mode.unwrap_or_default(),
+            self.include_hidden.unwrap_or(false),
+        )
+    }
+
+    /// Fetches the entries of a range: from the prefetched entries if they cover it, otherwise
+    /// with the list function, along with a margin of half of the range before and after it,
+    /// which are kept for the next calls.
+    ///
+    /// ### Parameters
+    /// - `first`: The starting index of the range.
+    /// - `length`: The length of the range.
+    ///
+    /// ### Returns
+    /// The entries of the range (fewer at the end of the list), or the error of the query.
+    fn fetch(&mut self, first: usize, length: usize) -> Result<Vec<T>, DataError> {
+        if let Some(prefetched) = &self.prefetched
+            && first >= prefetched.first
+            && (first + length <= prefetched.first + prefetched.entries.len() || prefetched.at_end)
+        {
+            trace!("prefetched first={} length={}", first, length);
+            let start = (first - prefetched.first).min(prefetched.entries.len());
+            let end = (start + length).min(prefetched.entries.len());
+            return Ok(prefetched.entries[start..end].to_vec());
+        }
+        let margin = length / 2;
+        let prefetch_first = first.saturating_sub(margin);
+        let prefetch_length = first - prefetch_first + length + margin;
+        let start = Instant::now();
+        let entries = self.list(prefetch_first, prefetch_length);
+        let elapsed = start.elapsed();
+        trace!("list first={} in {:?}", prefetch_first, elapsed);
+        self.slow = elapsed >= self.slow_query;
+        let entries = entries?;
+        let start = (first - prefetch_first).min(entries.len());
+        let end = (start + length).min(entries.len());
+        let range = entries[start..end].to_vec();
+        self.prefetched = Some(Prefetched {
+            first: prefetch_first,
+            at_end: entries.len() < prefetch_length,
+            entries,
+        });
+        self.stale = false;
+        Ok(range)
+    }
+
+    pub(crate) fn update_filter(
+        &mut self,
+        length: u16,
+        filter: &str,
+        fuzzy: bool,
+    ) -> Result<UpdateOutcome, DataError> {
+        if self.filter != filter || self.fuzzy_match != fuzzy {
+            self.filter = String::from(filter);
+            self.fuzzy_match = fuzzy;
+            self.refresh_count();
+            self.invalidate();
+        }
+        // the new result set is shown from its first page, the selected row being kept
+        let row = self.selected_row();
+        let result = self.update(0, length, true);
+        self.select_row(row);
+        self.clamp_selection();
+        result
+    }
+
+    /// Counts the entries matching the filter, if any, or all the entries if hinted; else the
+    /// entries are only counted when `total` is called.
+    fn refresh_count(&mut self) {
+        self.total = None;
+        self.counted = false;
+        if !self.filter.is_empty() || self.more_hint {
+            self.count_total();
+        }
+        self.count = if self.filter.is_empty() {
+            None
+        } else {
+            self.total
+        };
+    }
+
+    fn count_total(&mut self) {
+        self.counted = true;
+        self.total = self.count_fn.as_ref().and_then(|count_fn| {
+            match count_fn(
+                &self.filter,
+                self.fuzzy_match,
+                self.include_hidden.unwrap_or(false),
+            ) {
+                Ok(count) => Some(count),
+                Err(err) => {
+                    error!("Failed to count the entries: {}", err);
+                    None
+                }
+            }
+        });
+    }
+
+    /// Returns the number of entries matching the filter, or of all the entries without
+    /// filter. They are counted once, until the filter (or what is listed) changes.
+    ///
+    /// ### Returns
+    /// The number of entries, or `None` if there is no count function or counting failed.
+    pub(crate) fn total(&mut self) -> Option<usize> {
+        if !self.counted {
+            self.count_total();
+        }
+        self.total
+    }
+
+    /// Records that fetching the entries failed: the previous entries are kept and the error
+    /// state is published.
+    fn set_error(&mut self, err: &DataError) {
+        error!(
+            "Failed to fetch the entries with filter '{}': {}",
+            self.filter, err
+        );
+        self.error = true;
+        if self.is_loading() {
+            // nothing to keep: the list is shown empty
+            self.data = DataState::Loaded(vec![]);
+        }
+        self.publish();
     }
 
     /// Updates the data view with new entries based on the specified range and filter.
     /// If the requested range is already a subset of the current data, no update occurs.
+    /// The entries are taken from the prefetched ones if possible, unless they were invalidated
+    /// (see `invalidate`).
     ///
     /// If the range [first, first + length] exceeds the available data or if the result
-    /// is a subset of the current view, the update is not performed.
+    /// is a subset of the current view, the update is not performed. Neither is it if the
+    /// range is exactly the loaded one, even if forced (see `invalidate` to query again).
     ///
     /// ### Parameters
     /// - `first`: The starting index of the range.
     /// - `length`: The length of the range.
-    /// - `text`: The filter text.
-    /// - `force`: A boolean indicating whether to force the update even if no data is found (if not a subset of the current view).
+    /// - `force`: Whether the entries found replace the current ones even if they are fewer
+    ///   than the length (or none), i.e. the view moves to this range whatever is found.
     ///
     /// ### Returns
-    /// `true` if the data view was updated; otherwise, `false`.
-    pub(crate) fn update(&mut self, first: usize, length: u16, force: bool) -> bool {
+    /// What the update did (see `UpdateOutcome`), or the error raised while fetching the
+    /// entries, in which case the previous entries are kept.
+    pub(crate) fn update(
+        &mut self,
+        first: usize,
+        length: u16,
+        force: bool,
+    ) -> Result<UpdateOutcome, DataError> {
         trace!("update first={} length={} force={}", first, length, force);
-        if !force && !self.fuzzy_match && self.update_into_subset(first, length) {
+        if !force && !self.stale && !self.fuzzy_match && self.update_into_subset(first, length) {
             trace!("subset found");
-            return false;
+            return Ok(UpdateOutcome::Unchanged);
         }
-        let new_entries: Result<Vec<T>, rusqlite::Error> =
-            (self.list_fn)(first, length as usize, &self.filter, self.fuzzy_match);
+        if self.is_loaded(first, length) {
+            // forcing only matters for an empty result: invalidate to query again
+            trace!("range already loaded");
+            self.searching = false;
+            self.publish();
+            return Ok(UpdateOutcome::Unchanged);
+        }
+        let new_entries: Result<Vec<T>, DataError> = self.fetch(first, length as usize);
+        // the announced search is over, whatever it found
+        self.searching = false;
         match new_entries {
             Ok(new_entries) => {
+                self.error = false;
                 let new_length = new_entries.len();
                 if !force && (new_length != length as usize) {
                     // If we have less data than requested and it is a subset, we don't update
                     // This is the case for a scroll out of the data.
                     if self.is_a_subset_of(first, new_length as u16) {
                         trace!("Data is a subset, no update");
-                        return false;
+                        return Ok(UpdateOutcome::Unchanged);
                     }
                 }
                 if new_length > 0 {
-                    self.entries = Some(new_entries);
+                    self.data = DataState::Loaded(new_entries);
                     self.first = first;
                     self.length = new_length as u16;
                     trace!("Updated length={}", self.length);
                     self.publish();
 
-                    true
+                    Ok(UpdateOutcome::Updated)
                 } else {
                     debug!("No data found");
                     if force {
-                        self.entries = Option::None;
+                        self.data = DataState::Loaded(vec![]);
                         self.first = 0;
                         self.length = 0;
                         trace!("Forced update length={}", self.length);
                         self.publish();
-                        return true;
                     }
-                    false
+                    Ok(UpdateOutcome::Empty)
                 }
             }
             Err(err) => {
-                error!("update_data_pl: {}", err);
-                false
+                self.set_error(&err);
+                Err(err)
             }
         }
     }
@@ -219,8 +604,12 @@ impl<T: Clone> DataViewModel<T> {
     /// - `text`: The filter text.
     ///
     /// ### Returns
-    /// `true` if the data view was updated; otherwise, `false`.
-    pub(crate) fn update_to_offset(&mut self, offset: i64, length: u16) -> bool {
+    /// See `update`.
+    pub(crate) fn update_to_offset(
+        &mut self,
+        offset: i64,
+        length: u16,
+    ) -> Result<UpdateOutcome, DataError> {
         let first: usize = if self.first as i64 + offset < 0 {
             0
         } else {
@@ -230,42 +619,257 @@ impl<T: Clone> DataViewModel<T> {
         self.update(first, length, false)
     }
 
+    /// Updates the data view to the last page: the last `length` entries, or all of them if
+    /// there are fewer, which needs the entries to be counted.
+    ///
+    /// ### Parameters
+    /// - `length`: The length of the range to view.
+    ///
+    /// ### Returns
+    /// The position of the last entry, where the selection should land, or `None` if there
+    /// is no entry or they can't be counted (the data view is then unchanged); or the error
+    /// raised while fetching the entries, as for `update`.
+    pub(crate) fn update_to_end(&mut self, length: u16) -> Result<Option<usize>, DataError> {
+        let Some(total) = self.total() else {
+            debug!("No total, can't go to the end");
+            return Ok(None);
+        };
+        let first = total.saturating_sub(length as usize);
+        trace!("update_to_end total={} first={}", total, first);
+        self.update(first, length, true)?;
+        Ok((self.length > 0).then(|| self.first + self.length as usize - 1))
+    }
+
+    /// Fetches entries with the current filter, without changing the data view.
+    ///
+    /// ### Parameters
+    /// - `first`: The starting index of the range.
+    /// - `length`: The length of the range.
+    pub(crate) fn peek(&self, first: usize, length: u16) -> Result<Vec<T>, DataError> {
+        self.list(first, length as usize)
+    }
+
+    /// Fetches the entries from the current starting index again, e.g. to list the ones added
+    /// into the database meanwhile, and the count of the entries.
+    ///
+    /// ### Parameters
+    /// - `length`: The length of the range to view.
+    ///
+    /// ### Returns
+    /// See `update`.
+    pub(crate) fn refresh(&mut self, length: u16) -> Result<UpdateOutcome, DataError> {
+        self.refresh_count();
+        self.invalidate();
+        self.update(self.first, length, true)
+    }
+
     /// Reloads the current data view by fetching new entries based on the existing
     /// starting index, length, and filter.
-    pub(crate) fn reload(&mut self) {
-        let new_entries: Result<Vec<T>, rusqlite::Error> = (self.list_fn)(
-            self.first,
-            self.length as usize,
-            self.filter.as_str(),
-            self.fuzzy_match,
-        );
+    /// If fetching fails, the previous entries are kept and the error is returned.
+    pub(crate) fn reload(&mut self) -> Result<(), DataError> {
+        // entries may have been deleted
+        self.refresh_count();
+        self.invalidate();
+        let new_entries: Result<Vec<T>, DataError> = self.list(self.first, self.length as usize);
         match new_entries {
             Ok(new_entries) => {
+                self.error = false;
                 let new_length = new_entries.len();
                 if new_length > 0 {
-                    self.entries = Some(new_entries);
+                    self.data = DataState::Loaded(new_entries);
                     self.length = new_length as u16;
                     trace!("Updated");
                     self.publish();
                 } else {
                     debug!("No data found");
-                    self.entries = Option::None;
+                    self.data = DataState::Loaded(vec![]);
                     self.length = 0;
                     self.publish();
                 }
+                self.clamp_selection();
+                Ok(())
             }
             Err(err) => {
-                debug!("No data found {}", err);
+                self.set_error(&err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Resizes the data view to `length` entries, the selected entry staying selected and
+    /// visible: the window keeps its first entry when it grows, or when it shrinks above the
+    /// selection; else it is anchored on the selection, then on its last row.
+    ///
+    /// ### Parameters
+    /// - `length`: The new length of the range to view.
+    ///
+    /// ### Returns
+    /// See `update`. The new row of the selection is given by `selected_row`.
+    pub(crate) fn resize(&mut self, length: u16) -> Result<UpdateOutcome, DataError> {
+        let selected = self.selected();
+        let mut first = self.first;
+        if let Some(selected) = selected
+            && length > 0
+            && selected >= first + length as usize
+        {
+            first = selected + 1 - length as usize;
+        }
+        trace!("resize first={} length={}", first, length);
+        let result = self.update(first, length, true);
+        if let Some(selected) = selected {
+            self.select(selected);
+        }
+        result
+    }
+
+    /// Returns the absolute position of the selected entry, if any.
+    pub(crate) fn selected(&self) -> Option<usize> { self.selected }
+
+    /// Returns the row of the selected entry, relative to the loaded entries, if any.
+    pub(crate) fn selected_row(&self) -> Option<usize> {
+        self.selected
+            .map(|position| position.saturating_sub(self.first))
+    }
+
+    /// Selects a row, relative to the loaded entries, or nothing. The row may not be loaded
+    /// yet, e.g. before the first entries are received.
+    pub(crate) fn select_row(&mut self, row: Option<usize>) {
+        self.selected = row.map(|row| self.first + row);
+    }
+
+    /// Selects the entry at an absolute position, clamped into the loaded entries.
+    pub(crate) fn select(&mut self, position: usize) {
+        let last = (self.length as usize).saturating_sub(1);
+        self.selected = Some(self.first + position.saturating_sub(self.first).min(last));
+    }
+
+    /// Selects the first loaded entry, if any.
+    fn select_first(&mut self) {
+        self.selected = Some(self.first);
+        self.clamp_selection();
+    }
+
+    /// Clamps the selection into the loaded entries, e.g. after a reload returned fewer entries;
+    /// nothing is selected if there is none.
+    pub(crate) fn clamp_selection(&mut self) {
+        if let Some(position) = self.selected {
+            if self.length == 0 {
+                self.selected = None;
+            } else {
+                self.select(position);
             }
         }
     }
+
+    /// Moves the selection down, scrolling the window of `length` rows when the selection
+    /// goes past its last row, or by the same number of rows for a page. If the scroll fails,
+    /// the window and the selection are kept and the error is returned.
+    fn select_down(&mut self, rows: usize, page: bool, length: u16) -> Result<(), DataError> {
+        let (Some(current_row), Some(_)) = (self.selected_row(), self.entries()) else {
+            debug!("No selected entry");
+            return Ok(());
+        };
+        // the entry to select, computed before the window may move
+        let target = self.first + current_row + rows;
+        if current_row + 1 >= length as usize || page {
+            self.update_to_offset(rows as i64, length)?;
+        }
+        self.select(target);
+        Ok(())
+    }
+
+    /// Moves the selection up, scrolling the window of `length` rows when the selection goes
+    /// before its first row, or by the same number of rows for a page. If the scroll fails,
+    /// the window and the selection are kept and the error is returned.
+    fn select_up(&mut self, rows: usize, page: bool, length: u16) -> Result<(), DataError> {
+        let (Some(current_row), Some(_)) = (self.selected_row(), self.entries()) else {
+            debug!("No selected entry");
+            return Ok(());
+        };
+        // the entry to select, computed before the window may move
+        let target = (self.first + current_row).saturating_sub(rows);
+        if current_row == 0 || page {
+            self.update_to_offset(-(rows as i64), length)?;
+        }
+        self.select(target);
+        Ok(())
+    }
+
+    /// Selects the entry `rows` after the selected one, scrolling the window of `length` rows
+    /// if it is not visible.
+    pub(crate) fn select_next(&mut self, rows: usize, length: u16) -> Result<(), DataError> {
+        self.select_down(rows, false, length)
+    }
+
+    /// Selects the entry `rows` before the selected one, scrolling the window of `length` rows
+    /// if it is not visible.
+    pub(crate) fn select_prev(&mut self, rows: usize, length: u16) -> Result<(), DataError> {
+        self.select_up(rows, false, length)
+    }
+
+    /// Scrolls the window of `length` rows down by `rows` rows, the selection moving along.
+    pub(crate) fn select_page_down(&mut self, rows: usize, length: u16) -> Result<(), DataError> {
+        self.select_down(rows, true, length)
+    }
+
+    /// Scrolls the window of `length` rows up by `rows` rows, the selection moving along.
+    pub(crate) fn select_page_up(&mut self, rows: usize, length: u16) -> Result<(), DataError> {
+        self.select_up(rows, true, length)
+    }
+
+    /// Shows the first `length` entries and selects the first one.
+    pub(crate) fn select_home(&mut self, length: u16) -> Result<(), DataError> {
+        self.update(0, length, true)?;
+        self.select_row(Some(0));
+        Ok(())
+    }
+
+    /// Shows the last `length` entries and selects the last one, if the entries can be
+    /// counted.
+    pub(crate) fn select_end(&mut self, length: u16) -> Result<(), DataError> {
+        if let Some(last) = self.update_to_end(length)? {
+            self.selected = Some(last);
+        }
+        Ok(())
+    }
+
+    /// Shows and selects the first entry matching a predicate, looked for among the first
+    /// `SEARCH_LIMIT` entries with the current filter and order. The entry is shown at the top
+    /// of the `length` rows, unless it is among the first ones.
+    ///
+    /// ### Returns
+    /// Whether an entry matches (the data view and the selection are unchanged otherwise), or
+    /// the error raised while fetching the entries.
+    pub(crate) fn select_matching(
+        &mut self,
+        length: u16,
+        matches: impl Fn(&T) -> bool,
+    ) -> Result<bool, DataError> {
+        let entries = self.list(0, SEARCH_LIMIT)?;
+        let Some(position) = entries.iter().position(matches) else {
+            return Ok(false);
+        };
+        trace!("select_matching position={}", position);
+        let top = if position < length as usize {
+            0
+        } else {
+            position
+        };
+        self.update(top, length, true)?;
+        self.selected = Some(position);
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use tokio::sync::broadcast;
 
-    use crate::{model::DataViewModel, store::Store, tui::GenericEvent};
+    use crate::{
+        model::{DataStatePayload, DataViewModel, ListFunction, UpdateOutcome},
+        store::{Path, SortMode, Store},
+        tui::GenericEvent,
+    };
 
     #[test]
     fn test_scroll() {
@@ -280,65 +884,1020 @@ mod tests {
         let mut model = DataViewModel::new(
             "test".to_string(),
             tx,
-            Box::new(move |pos, len, text, fuzzy| store.list_paths(pos, len, text, fuzzy)),
+            Box::new(move |pos, len, text, fuzzy, _, include_hidden| {
+                Ok(store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+            }),
+            None,
             false,
+            None,
+            None,
         );
-        assert!(model.entries.is_none());
+        assert!(model.entries().is_none());
+        assert!(model.is_loading());
 
-        model.update(0, 2, false);
+        model.update(0, 2, false).unwrap();
+        assert!(!model.is_loading());
         assert_eq!(model.first, 0);
-        assert_eq!(model.entries.as_ref().unwrap().len(), 2);
-        assert_eq!(model.entries.as_ref().unwrap()[0].path, "/1");
-        assert_eq!(model.entries.as_ref().unwrap()[1].path, "/2");
+        assert_eq!(model.entries().unwrap().len(), 2);
+        assert_eq!(model.entries().unwrap()[0].path, "/1");
+        assert_eq!(model.entries().unwrap()[1].path, "/2");
 
-        model.update(1, 2, false);
+        model.update(1, 2, false).unwrap();
         assert_eq!(model.first, 1);
-        assert_eq!(model.entries.as_ref().unwrap().len(), 2);
-        assert_eq!(model.entries.as_ref().unwrap()[0].path, "/2");
-        assert_eq!(model.entries.as_ref().unwrap()[1].path, "/3");
+        assert_eq!(model.entries().unwrap().len(), 2);
+        assert_eq!(model.entries().unwrap()[0].path, "/2");
+        assert_eq!(model.entries().unwrap()[1].path, "/3");
 
-        model.update(2, 2, false);
+        model.update(2, 2, false).unwrap();
         assert_eq!(model.first, 2);
-        assert_eq!(model.entries.as_ref().unwrap().len(), 2);
-        assert_eq!(model.entries.as_ref().unwrap()[0].path, "/3");
-        assert_eq!(model.entries.as_ref().unwrap()[1].path, "/4");
+        assert_eq!(model.entries().unwrap().len(), 2);
+        assert_eq!(model.entries().unwrap()[0].path, "/3");
+        assert_eq!(model.entries().unwrap()[1].path, "/4");
 
-        model.update(3, 2, false);
+        model.update(3, 2, false).unwrap();
         assert_eq!(model.first, 3);
-        assert_eq!(model.entries.as_ref().unwrap().len(), 2);
-        assert_eq!(model.entries.as_ref().unwrap()[0].path, "/4");
-        assert_eq!(model.entries.as_ref().unwrap()[1].path, "/5");
+        assert_eq!(model.entries().unwrap().len(), 2);
+        assert_eq!(model.entries().unwrap()[0].path, "/4");
+        assert_eq!(model.entries().unwrap()[1].path, "/5");
 
         // The model won't update as it would only remain ["/5"] which is a subset of the current view
-        model.update(4, 2, false);
+        model.update(4, 2, false).unwrap();
         assert_eq!(model.first, 3);
-        assert_eq!(model.entries.as_ref().unwrap().len(), 2);
-        assert_eq!(model.entries.as_ref().unwrap()[0].path, "/4");
-        assert_eq!(model.entries.as_ref().unwrap()[1].path, "/5");
+        assert_eq!(model.entries().unwrap().len(), 2);
+        assert_eq!(model.entries().unwrap()[0].path, "/4");
+        assert_eq!(model.entries().unwrap()[1].path, "/5");
 
         // The model won't update as it would only remain []
-        model.update(5, 2, false);
-        assert_eq!(model.entries.as_ref().unwrap().len(), 2);
-        assert_eq!(model.entries.as_ref().unwrap()[0].path, "/4");
-        assert_eq!(model.entries.as_ref().unwrap()[1].path, "/5");
+        model.update(5, 2, false).unwrap();
+        assert_eq!(model.entries().unwrap().len(), 2);
+        assert_eq!(model.entries().unwrap()[0].path, "/4");
+        assert_eq!(model.entries().unwrap()[1].path, "/5");
 
         // Scroll back to 2
-        model.update(2, 2, false);
+        model.update(2, 2, false).unwrap();
         assert_eq!(model.first, 2);
-        assert_eq!(model.entries.as_ref().unwrap().len(), 2);
-        assert_eq!(model.entries.as_ref().unwrap()[0].path, "/3");
-        assert_eq!(model.entries.as_ref().unwrap()[1].path, "/4");
+        assert_eq!(model.entries().unwrap().len(), 2);
+        assert_eq!(model.entries().unwrap()[0].path, "/3");
+        assert_eq!(model.entries().unwrap()[1].path, "/4");
 
         // The model will update as ["/5"] is not a subset of the current view
-        model.update(4, 2, false);
+        model.update(4, 2, false).unwrap();
         assert_eq!(model.first, 4);
-        assert_eq!(model.entries.as_ref().unwrap().len(), 1);
-        assert_eq!(model.entries.as_ref().unwrap()[0].path, "/5");
+        assert_eq!(model.entries().unwrap().len(), 1);
+        assert_eq!(model.entries().unwrap()[0].path, "/5");
 
         // The model won't update as it would only remain []
-        model.update(5, 2, false);
+        model.update(5, 2, false).unwrap();
+        assert_eq!(model.first, 4);
+        assert_eq!(model.entries().unwrap().len(), 1);
+        assert_eq!(model.entries().unwrap()[0].path, "/5");
+    }
+
+    #[test]
+    fn test_update_into_subset() {
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let store = Store::setup_test_store();
+        for i in 0..10 {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+        }
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, fuzzy, _, include_hidden| {
+                Ok(store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+            }),
+            None,
+            false,
+            None,
+            None,
+        );
+        let paths = |model: &DataViewModel<Path>| -> Vec<String> {
+            model
+                .entries()
+                .unwrap()
+                .iter()
+                .map(|p| p.path.clone())
+                .collect()
+        };
+        model.update(2, 6, true).unwrap();
+        assert_eq!(paths(&model), vec!["/2", "/3", "/4", "/5", "/6", "/7"]);
+
+        // A window inside the loaded one, after its start
+        assert_eq!(model.update(4, 3, false).unwrap(), UpdateOutcome::Unchanged);
         assert_eq!(model.first, 4);
-        assert_eq!(model.entries.as_ref().unwrap().len(), 1);
-        assert_eq!(model.entries.as_ref().unwrap()[0].path, "/5");
+        assert_eq!(model.length, 3);
+        assert_eq!(paths(&model), vec!["/4", "/5", "/6"]);
+
+        // At its start
+        assert_eq!(model.update(4, 2, false).unwrap(), UpdateOutcome::Unchanged);
+        assert_eq!(paths(&model), vec!["/4", "/5"]);
+
+        // At its end
+        model.update(2, 6, true).unwrap();
+        assert_eq!(model.update(6, 2, false).unwrap(), UpdateOutcome::Unchanged);
+        assert_eq!(model.first, 6);
+        assert_eq!(paths(&model), vec!["/6", "/7"]);
+
+        // Overlapping before and after: not a subset, the entries are fetched
+        model.update(5, 2, false).unwrap();
+        assert_eq!(paths(&model), vec!["/5", "/6"]);
+        model.update(6, 3, false).unwrap();
+        assert_eq!(paths(&model), vec!["/6", "/7", "/8"]);
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let store = Store::setup_test_store();
+        for i in 0..30 {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+        }
+        let queries = std::rc::Rc::new(std::cell::Cell::new(0));
+        let list_queries = queries.clone();
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, fuzzy, _, include_hidden| {
+                list_queries.set(list_queries.get() + 1);
+                Ok(store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+            }),
+            None,
+            false,
+            None,
+            None,
+        );
+        let first_path = |model: &DataViewModel<Path>| model.entries().unwrap()[0].path.clone();
+
+        // 10 rows and 5 more after them
+        model.update(0, 10, true).unwrap();
+        assert_eq!(queries.get(), 1);
+
+        // Single-row moves within the prefetched entries
+        for i in 1..=5 {
+            model.update_to_offset(1, 10).unwrap();
+            assert_eq!(model.first, i);
+            assert_eq!(first_path(&model), format!("/{}", i));
+            assert_eq!(model.entries().unwrap().len(), 10);
+        }
+        model.update_to_offset(-3, 10).unwrap();
+        assert_eq!(first_path(&model), "/2");
+        assert_eq!(queries.get(), 1);
+
+        // The margin is exhausted: fetched again around the new range
+        model.update(6, 10, false).unwrap();
+        assert_eq!(first_path(&model), "/6");
+        assert_eq!(queries.get(), 2);
+        model.update_to_offset(-5, 10).unwrap();
+        assert_eq!(first_path(&model), "/1");
+        assert_eq!(queries.get(), 2);
+
+        // At the end of the list, the missing entries are not queried again
+        model.update(20, 10, false).unwrap();
+        assert_eq!(queries.get(), 3);
+        model.update(21, 10, false).unwrap();
+        assert_eq!(model.first, 20);
+        assert_eq!(first_path(&model), "/20");
+        assert_eq!(queries.get(), 3);
+
+        // Forced: the entries found, even if fewer
+        model.update(25, 10, true).unwrap();
+        assert_eq!(model.first, 25);
+        assert_eq!(model.entries().unwrap().len(), 5);
+        assert_eq!(queries.get(), 3);
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let store = Store::setup_test_store();
+        for i in 0..30 {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+        }
+        let list_store = store.clone();
+        let queries = std::rc::Rc::new(std::cell::Cell::new(0));
+        let list_queries = queries.clone();
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, fuzzy, _, include_hidden| {
+                list_queries.set(list_queries.get() + 1);
+                Ok(list_store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+            }),
+            None,
+            false,
+            None,
+            None,
+        );
+        model.update(0, 10, true).unwrap();
+        model.update(0, 10, false).unwrap();
+        model.update(0, 10, true).unwrap();
+        model.update(1, 10, false).unwrap();
+        assert_eq!(queries.get(), 1);
+
+        // Same range, prefetched range, and forced: all queried once invalidated
+        store.add_path_with_time("/new", 2000).unwrap();
+        model.invalidate();
+        model.update(1, 10, false).unwrap();
+        assert_eq!(queries.get(), 2);
+        assert_eq!(model.entries().unwrap()[0].path, "/0");
+        model.invalidate();
+        model.update(2, 10, false).unwrap();
+        assert_eq!(queries.get(), 3);
+        model.invalidate();
+        model.update(2, 10, true).unwrap();
+        assert_eq!(queries.get(), 4);
+
+        // Queried once only
+        model.update(2, 10, false).unwrap();
+        assert_eq!(queries.get(), 4);
+    }
+
+    #[test]
+    fn test_error_keeps_the_previous_entries() {
+        let (tx, mut rx) = broadcast::channel::<GenericEvent>(16);
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(|pos, len, text, _, _, _| {
+                if text == "bad" {
+                    return Err("invalid query".into());
+                }
+                Ok((pos..pos + len)
+                    .take_while(|i| *i < 3)
+                    .map(|i| Path {
+                        id: i as i64,
+                        date: 0,
+                        visits: 1,
+                        created: 0,
+                        hidden: false,
+                        via: None,
+                        path: format!("/{}", i),
+                    })
+                    .collect())
+            }),
+            None,
+            false,
+            None,
+            None,
+        );
+        let is_error = |rx: &mut broadcast::Receiver<GenericEvent>| {
+            let mut is_error = None;
+            while let Ok(event) = rx.try_recv() {
+                if let GenericEvent::ApplicationEvent(ae) = event
+                    && let Some(payload) = ae.payload
+                    && let Some(payload) = payload.downcast_ref::<DataStatePayload>()
+                {
+                    is_error = Some(payload.is_error);
+                }
+            }
+            is_error
+        };
+
+        assert_eq!(
+            model.update_filter(2, "", false).unwrap(),
+            UpdateOutcome::Updated
+        );
+        assert_eq!(is_error(&mut rx), Some(false));
+
+        // The error is returned and published, the previous entries remain
+        assert!(model.update_filter(2, "bad", false).is_err());
+        assert!(model.error);
+        assert_eq!(is_error(&mut rx), Some(true));
+        assert_eq!(model.length, 2);
+        assert_eq!(model.entries().unwrap()[1].path, "/1");
+        assert!(model.reload().is_err());
+        assert_eq!(model.length, 2);
+
+        // A valid filter clears the error
+        assert_eq!(
+            model.update_filter(2, "ok", false).unwrap(),
+            UpdateOutcome::Updated
+        );
+        assert!(!model.error);
+        assert_eq!(is_error(&mut rx), Some(false));
+    }
+
+    #[test]
+    fn test_count() {
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let store = Store::setup_test_store();
+        for i in 0..15 {
+            store.add_path(&format!("/{}", i)).unwrap();
+        }
+        let count_store = store.clone();
+        let counts = std::rc::Rc::new(std::cell::Cell::new(0));
+        let count_calls = counts.clone();
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, fuzzy, _, include_hidden| {
+                Ok(store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+            }),
+            Some(Box::new(move |text, fuzzy, include_hidden| {
+                count_calls.set(count_calls.get() + 1);
+                Ok(count_store.count_paths(text, fuzzy, include_hidden)?)
+            })),
+            false,
+            None,
+            None,
+        );
+
+        // No filter, no count
+        model.update_filter(5, "", false).unwrap();
+        assert_eq!(model.count, None);
+        assert_eq!(counts.get(), 0);
+
+        model.update_filter(5, "1", false).unwrap();
+        assert_eq!(model.count, Some(6));
+        assert_eq!(counts.get(), 1);
+
+        // Navigating does not count again
+        model.update_to_offset(1, 5).unwrap();
+        model.update_to_offset(1, 5).unwrap();
+        model.update_filter(5, "1", false).unwrap();
+        assert_eq!(counts.get(), 1);
+
+        // Changing the filter does
+        model.update_filter(5, "12", false).unwrap();
+        assert_eq!(model.count, Some(1));
+        assert_eq!(counts.get(), 2);
+        assert_eq!(model.total(), Some(1));
+        assert_eq!(counts.get(), 2);
+
+        // Without filter, all the entries are counted on demand, once
+        model.update_filter(5, "", false).unwrap();
+        assert_eq!(counts.get(), 2);
+        assert_eq!(model.total(), Some(15));
+        assert_eq!(model.total(), Some(15));
+        assert_eq!(counts.get(), 3);
+        assert_eq!(model.count, None);
+    }
+
+    #[test]
+    fn test_total_without_count_function() {
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let store = Store::setup_test_store();
+        store.add_path("/a").unwrap();
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, fuzzy, _, include_hidden| {
+                Ok(store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+            }),
+            None,
+            false,
+            None,
+            None,
+        );
+        model.update_filter(5, "a", false).unwrap();
+        assert_eq!(model.total(), None);
+        assert_eq!(model.count, None);
+    }
+
+    #[test]
+    fn test_cycle_sort_mode() {
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let store = Store::setup_test_store();
+        store.add_path_with_time("/b", 2).unwrap();
+        store.add_path_with_time("/a", 1).unwrap();
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, _, sort_mode, include_hidden| {
+                Ok(store.list_paths_sorted(pos, len, text, sort_mode, include_hidden)?)
+            }),
+            None,
+            false,
+            Some(SortMode::Recency),
+            None,
+        );
+        let paths = |model: &DataViewModel<Path>| -> Vec<String> {
+            model
+                .entries()
+                .unwrap()
+                .iter()
+                .map(|p| p.path.clone())
+                .collect()
+        };
+
+        model.update(0, 5, true).unwrap();
+        assert_eq!(paths(&model), vec!["/b", "/a"]);
+
+        model.cycle_sort_mode(5).unwrap();
+        assert_eq!(model.sort_mode, Some(SortMode::Frequency));
+        model.cycle_sort_mode(5).unwrap();
+        model.cycle_sort_mode(5).unwrap();
+        assert_eq!(model.sort_mode, Some(SortMode::Alphabetical));
+        assert_eq!(paths(&model), vec!["/a", "/b"]);
+    }
+
+    /// A model over the paths /0 to /9, listed in this order, with a window of 3 entries
+    /// showing the first ones and the first one selected.
+    fn navigation_model() -> DataViewModel<Path> {
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let store = Store::setup_test_store();
+        for i in 0..10 {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+        }
+        let count_store = store.clone();
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, fuzzy, _, include_hidden| {
+                Ok(store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+            }),
+            Some(Box::new(move |text, fuzzy, include_hidden| {
+                Ok(count_store.count_paths(text, fuzzy, include_hidden)?)
+            })),
+            false,
+            None,
+            None,
+        );
+        model.select_row(Some(0));
+        model.update(0, 3, true).unwrap();
+        model
+    }
+
+    /// The selected path of a model.
+    fn selected_path(model: &DataViewModel<Path>) -> Option<String> {
+        model
+            .selected_row()
+            .and_then(|row| model.entries()?.get(row))
+            .map(|p| p.path.clone())
+    }
+
+    #[test]
+    fn test_select_next() {
+        let mut model = navigation_model();
+        assert_eq!(selected_path(&model).as_deref(), Some("/0"));
+
+        model.select_next(1, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (0, Some(1)));
+        model.select_next(1, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (0, Some(2)));
+        // past the last row: the window scrolls
+        model.select_next(1, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (1, Some(3)));
+        assert_eq!(model.selected_row(), Some(2));
+        assert_eq!(selected_path(&model).as_deref(), Some("/3"));
+
+        // a jump
+        model.select_next(4, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (5, Some(7)));
+
+        // at the end of the list, the last entry stays selected
+        model.select_next(2, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (7, Some(9)));
+        assert_eq!(selected_path(&model).as_deref(), Some("/9"));
+        model.select_next(1, 3).unwrap();
+        assert_eq!(selected_path(&model).as_deref(), Some("/9"));
+    }
+
+    #[test]
+    fn test_select_prev() {
+        let mut model = navigation_model();
+        model.update(5, 3, true).unwrap();
+        model.select(7);
+        assert_eq!(model.selected_row(), Some(2));
+
+        model.select_prev(1, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (5, Some(6)));
+        model.select_prev(1, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (5, Some(5)));
+        // before the first row: the window scrolls
+        model.select_prev(1, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (4, Some(4)));
+        assert_eq!(selected_path(&model).as_deref(), Some("/4"));
+
+        // at the beginning of the list, the first entry stays selected
+        model.select_prev(10, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (0, Some(0)));
+        model.select_prev(1, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (0, Some(0)));
+    }
+
+    #[test]
+    fn test_select_page() {
+        let mut model = navigation_model();
+        model.select_next(1, 3).unwrap();
+
+        // the window and the selection move together
+        model.select_page_down(3, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (3, Some(4)));
+        assert_eq!(model.selected_row(), Some(1));
+        model.select_page_down(3, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (6, Some(7)));
+        // the last page is kept, the selection is clamped into it
+        model.select_page_down(3, 3).unwrap();
+        assert_eq!(selected_path(&model).as_deref(), Some("/9"));
+
+        model.select_page_up(3, 3).unwrap();
+        assert_eq!(model.selected(), Some(6));
+        assert_eq!(selected_path(&model).as_deref(), Some("/6"));
+        model.select_page_up(3, 3).unwrap();
+        model.select_page_up(3, 3).unwrap();
+        model.select_page_up(3, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (0, Some(0)));
+    }
+
+    #[test]
+    fn test_select_home_and_end() {
+        let mut model = navigation_model();
+
+        model.select_end(3).unwrap();
+        assert_eq!((model.first, model.selected()), (7, Some(9)));
+        assert_eq!(selected_path(&model).as_deref(), Some("/9"));
+
+        model.select_home(3).unwrap();
+        assert_eq!((model.first, model.selected()), (0, Some(0)));
+        assert_eq!(selected_path(&model).as_deref(), Some("/0"));
+    }
+
+    #[test]
+    fn test_select_matching() {
+        let mut model = navigation_model();
+
+        // on the first page, the window stays at the top
+        assert!(model.select_matching(3, |p| p.path == "/2").unwrap());
+        assert_eq!((model.first, model.selected()), (0, Some(2)));
+        assert_eq!(selected_path(&model).as_deref(), Some("/2"));
+
+        // further, it is shown at the top
+        assert!(model.select_matching(3, |p| p.path == "/8").unwrap());
+        assert_eq!((model.first, model.selected()), (8, Some(8)));
+        assert_eq!(selected_path(&model).as_deref(), Some("/8"));
+
+        // nothing changes without a match
+        assert!(!model.select_matching(3, |p| p.path == "/none").unwrap());
+        assert_eq!((model.first, model.selected()), (8, Some(8)));
+
+        // the entries are looked for with a single query
+        let queries = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+        let list_fn =
+            std::mem::replace(&mut model.list_fn, Box::new(|_, _, _, _, _, _| Ok(vec![])));
+        model.list_fn = Box::new({
+            let queries = queries.clone();
+            move |pos, len, text, fuzzy, sort_mode, include_hidden| {
+                queries.borrow_mut().push((pos, len));
+                list_fn(pos, len, text, fuzzy, sort_mode, include_hidden)
+            }
+        });
+        assert!(!model.select_matching(3, |p| p.path == "/none").unwrap());
+        assert_eq!(*queries.borrow(), vec![(0, super::SEARCH_LIMIT)]);
+    }
+
+    #[test]
+    fn test_selection_follows_the_filter() {
+        let mut model = navigation_model();
+        model.select_next(1, 3).unwrap();
+        model.select_next(1, 3).unwrap();
+        model.select_next(1, 3).unwrap();
+        assert_eq!((model.first, model.selected()), (1, Some(3)));
+
+        // the filtered entries are shown from the first one, the selected row being kept
+        model.update_filter(3, "/", false).unwrap();
+        assert_eq!((model.first, model.selected()), (0, Some(2)));
+
+        // fewer entries than the selected row: the selection is clamped
+        model.update_filter(3, "5", false).unwrap();
+        assert_eq!(model.selected(), Some(0));
+        assert_eq!(selected_path(&model).as_deref(), Some("/5"));
+
+        // no entry: nothing is selected
+        model.update_filter(3, "none", false).unwrap();
+        assert_eq!(model.selected(), None);
+    }
+
+    #[test]
+    fn test_searching() {
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let mut rx = tx.subscribe();
+        let store = Store::setup_test_store();
+        store.add_path("/a").unwrap();
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, fuzzy, _, include_hidden| {
+                Ok(store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+            }),
+            None,
+            false,
+            None,
+            None,
+        );
+        let mut searching = || -> Vec<bool> {
+            let mut states = vec![];
+            while let Ok(GenericEvent::ApplicationEvent(ae)) = rx.try_recv() {
+                let payload = ae.payload.unwrap();
+                states.push(
+                    payload
+                        .downcast_ref::<DataStatePayload>()
+                        .unwrap()
+                        .is_searching,
+                );
+            }
+            states
+        };
+
+        model.update(0, 5, true).unwrap();
+        assert!(!model.is_slow());
+        assert_eq!(searching(), vec![false]);
+
+        model.slow_query = std::time::Duration::ZERO;
+        model.update_filter(5, "a", false).unwrap();
+        assert!(model.is_slow());
+        assert_eq!(searching(), vec![false]);
+
+        // the searching state is published until the query returns
+        model.begin_search();
+        assert!(model.is_searching());
+        assert_eq!(searching(), vec![true]);
+        model.update_filter(5, "b", false).unwrap();
+        assert!(!model.is_searching());
+        assert_eq!(searching(), vec![false]);
+    }
+
+    #[test]
+    fn test_sort_change_queries() {
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let store = Store::setup_test_store();
+        for i in 0..10 {
+            store.add_path_with_time(&format!("/{}", i), i).unwrap();
+        }
+        let queries = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+        let list_queries = queries.clone();
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, _, sort_mode, include_hidden| {
+                list_queries.borrow_mut().push(sort_mode);
+                Ok(store.list_paths_sorted(pos, len, text, sort_mode, include_hidden)?)
+            }),
+            None,
+            false,
+            Some(SortMode::Recency),
+            None,
+        );
+        model.update_filter(4, "/", false).unwrap();
+        model.update(2, 4, false).unwrap();
+        assert_eq!(model.first, 2);
+        queries.borrow_mut().clear();
+
+        // same filter and range, but another order: queried again from the first entry
+        model.cycle_sort_mode(4).unwrap();
+        assert_eq!(*queries.borrow(), vec![SortMode::Frequency]);
+        assert_eq!(model.first, 0);
+
+        // back to the first order after a whole cycle: still queried
+        for _ in 0..4 {
+            model.cycle_sort_mode(4).unwrap();
+        }
+        assert_eq!(queries.borrow().len(), 5);
+        assert_eq!(queries.borrow().last(), Some(&SortMode::Recency));
+        assert_eq!(model.entries().unwrap()[0].path, "/9");
+    }
+
+    /// A list function over the paths "/0" to "/19", filtered by substring, which records the
+    /// number of queries.
+    fn counting_source() -> (Box<ListFunction<Path>>, std::rc::Rc<std::cell::Cell<usize>>) {
+        let queries = std::rc::Rc::new(std::cell::Cell::new(0));
+        let list_queries = queries.clone();
+        let list_fn = Box::new(move |pos, len, text: &str, _, _, _| {
+            list_queries.set(list_queries.get() + 1);
+            Ok((0..20)
+                .map(|i| format!("/{}", i))
+                .filter(|path| path.contains(text))
+                .enumerate()
+                .skip(pos)
+                .take(len)
+                .map(|(i, path)| Path {
+                    id: i as i64,
+                    date: 0,
+                    visits: 1,
+                    created: 0,
+                    hidden: false,
+                    via: None,
+                    path,
+                })
+                .collect())
+        });
+        (list_fn, queries)
+    }
+
+    /// An operation on a model
+    enum Step {
+        /// `update(first, length, force)`
+        Update(usize, u16, bool),
+        /// `update_to_offset(offset, length)`
+        Offset(i64, u16),
+        /// `update_filter(length, filter, false)`
+        Filter(&'static str, u16),
+    }
+
+    /// A named sequence of steps, then the first entry, the entries and the number of queries
+    /// expected.
+    type Case = (&'static str, Vec<Step>, usize, Vec<String>, usize);
+
+    /// The paths "/first" to "/end - 1"
+    fn paths(range: std::ops::Range<usize>) -> Vec<String> {
+        range.map(|i| format!("/{}", i)).collect()
+    }
+
+    #[test]
+    fn test_update_cases() {
+        use Step::*;
+        let cases: Vec<Case> = vec![
+            ("initial load", vec![Update(0, 5, false)], 0, paths(0..5), 1),
+            (
+                "scroll forward within the prefetched entries",
+                vec![Update(0, 5, false), Offset(1, 5), Offset(1, 5)],
+                2,
+                paths(2..7),
+                1,
+            ),
+            (
+                "scroll forward past the prefetched entries",
+                vec![
+                    Update(0, 5, false),
+                    Offset(1, 5),
+                    Offset(1, 5),
+                    Offset(1, 5),
+                ],
+                3,
+                paths(3..8),
+                2,
+            ),
+            (
+                "scroll back within the prefetched entries",
+                vec![Update(10, 5, false), Offset(-2, 5)],
+                8,
+                paths(8..13),
+                1,
+            ),
+            (
+                "scroll back past the prefetched entries",
+                vec![Update(10, 5, false), Offset(-2, 5), Offset(-1, 5)],
+                7,
+                paths(7..12),
+                2,
+            ),
+            (
+                "scroll past the end",
+                vec![Update(15, 5, false), Offset(1, 5), Offset(10, 5)],
+                15,
+                paths(15..20),
+                1,
+            ),
+            (
+                "scroll before the beginning",
+                vec![Update(2, 5, false), Offset(-5, 5)],
+                0,
+                paths(0..5),
+                1,
+            ),
+            (
+                "filter change",
+                vec![Update(0, 5, false), Filter("1", 5)],
+                0,
+                vec!["/1", "/10", "/11", "/12", "/13"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                2,
+            ),
+            (
+                "same filter",
+                vec![Filter("1", 5), Filter("1", 5)],
+                0,
+                vec!["/1", "/10", "/11", "/12", "/13"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                1,
+            ),
+            (
+                "shrinking results",
+                vec![Filter("1", 5), Offset(3, 5), Filter("19", 5)],
+                0,
+                vec![String::from("/19")],
+                3,
+            ),
+            (
+                "empty result with force",
+                vec![Update(0, 5, false), Update(30, 5, true)],
+                0,
+                vec![],
+                2,
+            ),
+            (
+                "empty result without force",
+                vec![Update(0, 5, false), Update(30, 5, false)],
+                0,
+                paths(0..5),
+                2,
+            ),
+            (
+                "terminal shrunk",
+                vec![Update(0, 5, false), Update(0, 3, false)],
+                0,
+                paths(0..3),
+                1,
+            ),
+            (
+                "terminal grown within the prefetched entries",
+                vec![Update(0, 5, false), Update(0, 7, true)],
+                0,
+                paths(0..7),
+                1,
+            ),
+            (
+                "terminal grown past the prefetched entries",
+                vec![Update(0, 5, false), Update(0, 10, true)],
+                0,
+                paths(0..10),
+                2,
+            ),
+        ];
+
+        for (name, steps, first, entries, queries) in cases {
+            let (list_fn, count) = counting_source();
+            let tx = broadcast::channel::<GenericEvent>(16).0;
+            let mut model =
+                DataViewModel::new("test".to_string(), tx, list_fn, None, false, None, None);
+            for step in steps {
+                match step {
+                    Update(first, length, force) => model.update(first, length, force),
+                    Offset(offset, length) => model.update_to_offset(offset, length),
+                    Filter(filter, length) => model.update_filter(length, filter, false),
+                }
+                .unwrap();
+            }
+            let loaded: Vec<String> = model
+                .entries()
+                .map(|entries| entries.iter().map(|p| p.path.clone()).collect())
+                .unwrap_or_default();
+            assert_eq!(model.first, first, "{}: first", name);
+            assert_eq!(loaded, entries, "{}: entries", name);
+            assert_eq!(model.length as usize, entries.len(), "{}: length", name);
+            assert_eq!(count.get(), queries, "{}: queries", name);
+        }
+    }
+
+    #[test]
+    fn test_update_to_end() {
+        let (list_fn, _) = counting_source();
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx.clone(),
+            list_fn,
+            Some(Box::new(|text: &str, _, _| {
+                Ok((0..20).filter(|i| format!("/{}", i).contains(text)).count())
+            })),
+            false,
+            None,
+            None,
+        );
+        let loaded = |model: &DataViewModel<Path>| -> Vec<String> {
+            model
+                .entries()
+                .map(|entries| entries.iter().map(|p| p.path.clone()).collect())
+                .unwrap_or_default()
+        };
+
+        // The last full page
+        assert_eq!(model.update_to_end(5).unwrap(), Some(19));
+        assert_eq!(model.first, 15);
+        assert_eq!(loaded(&model), paths(15..20));
+
+        // Fewer entries than a page: all of them from the first one
+        model.update_filter(5, "1", false).unwrap();
+        assert_eq!(model.update_to_end(20).unwrap(), Some(10));
+        assert_eq!(model.first, 0);
+        assert_eq!(model.length, 11);
+
+        // No entry
+        model.update_filter(5, "none", false).unwrap();
+        assert_eq!(model.update_to_end(5).unwrap(), None);
+        assert!(model.entries().is_none());
+
+        // Without counting, the view is unchanged
+        let (list_fn, _) = counting_source();
+        let mut model =
+            DataViewModel::new("test".to_string(), tx, list_fn, None, false, None, None);
+        model.update(0, 5, true).unwrap();
+        assert_eq!(model.update_to_end(5).unwrap(), None);
+        assert_eq!(model.first, 0);
+        assert_eq!(loaded(&model), paths(0..5));
+    }
+
+    #[test]
+    fn test_resize() {
+        // (name, window before, selected, new length, first, selected row)
+        let cases: [(&str, usize, usize, u16, usize, usize); 8] = [
+            ("top, grown", 5, 5, 10, 5, 0),
+            ("middle, grown", 5, 8, 10, 5, 3),
+            ("bottom, grown", 5, 9, 10, 5, 4),
+            ("top, shrunk", 5, 5, 3, 5, 0),
+            ("middle, shrunk", 5, 7, 3, 5, 2),
+            ("bottom, shrunk", 5, 9, 3, 7, 2),
+            ("bottom, shrunk to a row", 5, 9, 1, 9, 0),
+            // at the end of the list: fewer entries than the length
+            ("bottom, grown past the end", 15, 19, 10, 15, 4),
+        ];
+        for (name, first, selected, length, new_first, row) in cases {
+            let (list_fn, _) = counting_source();
+            let tx = broadcast::channel::<GenericEvent>(16).0;
+            let mut model =
+                DataViewModel::new("test".to_string(), tx, list_fn, None, false, None, None);
+            model.update(first, 5, true).unwrap();
+            model.select(selected);
+
+            model.resize(length).unwrap();
+            assert_eq!(model.first, new_first, "{}: first", name);
+            assert_eq!(model.selected(), Some(selected), "{}: selected", name);
+            assert_eq!(model.selected_row(), Some(row), "{}: selected row", name);
+            assert!(row < model.length as usize, "{}: visible", name);
+        }
+    }
+
+    #[test]
+    fn test_update_outcome() {
+        let (list_fn, _) = counting_source();
+        let failing = std::rc::Rc::new(std::cell::Cell::new(false));
+        let list_failing = failing.clone();
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            Box::new(move |pos, len, text, fuzzy, sort_mode, include_hidden| {
+                if list_failing.get() {
+                    return Err("database is locked".into());
+                }
+                list_fn(pos, len, text, fuzzy, sort_mode, include_hidden)
+            }),
+            None,
+            false,
+            None,
+            None,
+        );
+
+        assert_eq!(model.update(0, 5, false).unwrap(), UpdateOutcome::Updated);
+        assert_eq!(model.update(1, 3, false).unwrap(), UpdateOutcome::Unchanged);
+        assert_eq!(model.update(30, 5, false).unwrap(), UpdateOutcome::Empty);
+        assert_eq!(model.first, 1);
+
+        // A failure is not a no-op: the error is returned, the entries are kept
+        failing.set(true);
+        let err = model.update(10, 5, false).unwrap_err();
+        assert_eq!(err.to_string(), "database is locked");
+        assert!(model.error);
+        assert_eq!(model.first, 1);
+        model.select_row(Some(0));
+        assert!(model.select_page_down(10, 3).is_err());
+        assert_eq!(model.selected(), Some(1));
+        assert_eq!(model.first, 1);
+
+        failing.set(false);
+        assert_eq!(model.update(30, 5, true).unwrap(), UpdateOutcome::Empty);
+        assert!(model.entries().is_none());
+        assert!(!model.error);
+    }
+
+    #[test]
+    fn test_short_last_page_loaded() {
+        let (list_fn, queries) = counting_source();
+        let tx = broadcast::channel::<GenericEvent>(16).0;
+        let mut model = DataViewModel::new(
+            "test".to_string(),
+            tx,
+            list_fn,
+            Some(Box::new(|_, _, _| Ok(20))),
+            false,
+            None,
+            None,
+        );
+        model.total();
+
+        // the last 5 entries, requested as a page of 10
+        model.update(15, 10, true).unwrap();
+        assert_eq!(model.length, 5);
+        let before = queries.get();
+        assert_eq!(
+            model.update(15, 10, true).unwrap(),
+            UpdateOutcome::Unchanged
+        );
+        assert_eq!(queries.get(), before);
+        // a range which is not loaded is fetched
+        model.update(0, 10, true).unwrap();
+        assert_eq!(queries.get(), before + 1);
     }
 }
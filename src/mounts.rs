@@ -0,0 +1,203 @@
+use log::{debug, warn};
+
+// The table of the mounted file systems, on Linux
+const MOUNTS_FILE: &str = "/proc/mounts";
+
+// The types of the file systems accessed through the network
+const NETWORK_FS_TYPES: [&str; 14] = [
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "ncpfs",
+    "afs",
+    "9p",
+    "ceph",
+    "glusterfs",
+    "lustre",
+    "fuse.sshfs",
+    "fuse.rclone",
+    "davfs",
+];
+
+/// A mounted file system
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Mount {
+    /// The directory where it is mounted
+    pub(crate) point: String,
+    /// Its type, e.g. `ext4` or `nfs4`
+    pub(crate) fs_type: String,
+}
+
+impl Mount {
+    /// Whether the file system is accessed through the network.
+    pub(crate) fn is_network(&self) -> bool { NETWORK_FS_TYPES.contains(&self.fs_type.as_str()) }
+
+    /// Whether a path is in the file system, i.e. is its mount point or below it.
+    fn contains(&self, path: &str) -> bool {
+        match path.strip_prefix(&self.point) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/') || self.point.ends_with('/'),
+            None => false,
+        }
+    }
+}
+
+/// The mounted file systems, best-effort: the table is empty if it can't be read (e.g. on
+/// macOS), and then no path is on a network file system.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Mounts {
+    mounts: Vec<Mount>,
+}
+
+impl Mounts {
+    /// Reads the mounted file systems of the system.
+    pub(crate) fn read() -> Mounts {
+        match std::fs::read_to_string(MOUNTS_FILE) {
+            Ok(content) => Mounts::parse(&content),
+            Err(e) => {
+                warn!("Failed to read {}: {}", MOUNTS_FILE, e);
+                Mounts::default()
+            }
+        }
+    }
+
+    /// Parses a mounts table, in the format of `/proc/mounts`: a file system per line, with
+    /// its device, mount point and type first, separated by spaces (escaped as `\040`).
+    pub(crate) fn parse(content: &str) -> Mounts {
+        let mounts = content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let _device = fields.next()?;
+                let point = unescape(fields.next()?);
+                let fs_type = fields.next()?.to_string();
+                Some(Mount { point, fs_type })
+            })
+            .collect::<Vec<_>>();
+        debug!("{} mounts", mounts.len());
+        Mounts { mounts }
+    }
+
+    /// Returns the file system of a path: the one mounted on its closest ancestor (the last one
+    /// if several are mounted on the same directory).
+    pub(crate) fn mount_of(&self, path: &str) -> Option<&Mount> {
+        // max_by_key returns the last of the maximal elements, i.e. the one mounted on top
+        self.mounts
+            .iter()
+            .filter(|mount| mount.contains(path))
+            .max_by_key(|mount| mount.point.len())
+    }
+
+    /// Whether a path is on a network file system, e.g. NFS or SMB.
+    pub(crate) fn is_network_path(&self, path: &str) -> bool {
+        self.mount_of(path).is_some_and(Mount::is_network)
+    }
+}
+
+/// Replaces the octal escapes of a mounts table (e.g. `\040` for a space) by their character.
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\'
+            && let Some(octal) = field.get(i + 1..i + 4)
+            && let Ok(byte) = u8::from_str_radix(octal, 8)
+        {
+            unescaped.push(byte);
+            i += 4;
+        } else {
+            unescaped.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&unescaped).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTS: &str = "\
+sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0
+/dev/sda1 / ext4 rw,relatime 0 0
+server:/export/home /home nfs4 rw,relatime,vers=4.2 0 0
+/dev/sdb1 /home/user/local ext4 rw,relatime 0 0
+//nas/share /mnt/my\\040share cifs rw,relatime 0 0
+user@host:/ /mnt/remote fuse.sshfs rw,nosuid,nodev 0 0
+";
+
+    #[test]
+    fn test_parse() {
+        let mounts = Mounts::parse(MOUNTS);
+        assert_eq!(mounts.mounts.len(), 6);
+        assert_eq!(
+            mounts.mounts[4],
+            Mount {
+                point: String::from("/mnt/my share"),
+                fs_type: String::from("cifs"),
+            }
+        );
+        assert!(Mounts::parse("").mounts.is_empty());
+        assert!(Mounts::parse("incomplete /line\n").mounts.is_empty());
+    }
+
+    #[test]
+    fn test_mount_of() {
+        let mounts = Mounts::parse(MOUNTS);
+        let point = |path: &str| mounts.mount_of(path).map(|mount| mount.point.as_str());
+        assert_eq!(point("/"), Some("/"));
+        assert_eq!(point("/usr/bin"), Some("/"));
+        assert_eq!(point("/home"), Some("/home"));
+        assert_eq!(point("/home/user/src"), Some("/home"));
+        assert_eq!(point("/home/user/local/src"), Some("/home/user/local"));
+        // on a component boundary only
+        assert_eq!(point("/homework"), Some("/"));
+        assert_eq!(point("relative"), None);
+    }
+
+    #[test]
+    fn test_mount_of_stacked() {
+        // the last file system mounted on a directory hides the previous ones
+        let mounts = Mounts::parse(
+            "/dev/sda1 / ext4 rw 0 0\n\
+             /dev/sda2 /home ext4 rw 0 0\n\
+             server:/export/home /home nfs4 rw 0 0\n",
+        );
+        assert_eq!(
+            mounts
+                .mount_of("/home/user")
+                .map(|mount| mount.fs_type.as_str()),
+            Some("nfs4")
+        );
+        assert!(mounts.is_network_path("/home/user"));
+
+        let mounts = Mounts::parse(
+            "server:/export/home /home nfs4 rw 0 0\n\
+             /dev/sda2 /home ext4 rw 0 0\n",
+        );
+        assert!(!mounts.is_network_path("/home/user"));
+    }
+
+    #[test]
+    fn test_is_network_path() {
+        let mounts = Mounts::parse(MOUNTS);
+        assert!(mounts.is_network_path("/home/user/src"));
+        assert!(!mounts.is_network_path("/home/user/local"));
+        assert!(mounts.is_network_path("/mnt/my share/docs"));
+        assert!(mounts.is_network_path("/mnt/remote"));
+        assert!(!mounts.is_network_path("/mnt"));
+        assert!(!mounts.is_network_path("/tmp"));
+
+        // nothing is known without a table
+        assert!(!Mounts::default().is_network_path("/home/user/src"));
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape("/mnt/a\\040b"), "/mnt/a b");
+        assert_eq!(unescape("/mnt/a\\134b"), "/mnt/a\\b");
+        assert_eq!(unescape("/mnt/a\\b"), "/mnt/a\\b");
+    }
+}
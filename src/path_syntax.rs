@@ -0,0 +1,179 @@
+// Whether the paths follow the Windows syntax: `\` or `/` separators, drive letters, and names
+// compared regardless of the case
+pub(crate) const WINDOWS: bool = cfg!(windows);
+
+/// Returns whether a character separates the components of a path.
+pub(crate) fn is_separator(c: char, windows: bool) -> bool { c == '/' || (windows && c == '\\') }
+
+/// Returns whether a path is absolute: it starts with `/`, or on Windows with a drive letter
+/// followed by a separator (e.g. `C:\`) or with `\\` (a network share).
+///
+/// ### Parameters
+/// - `path`: the path
+/// - `windows`: whether the path follows the Windows syntax
+pub(crate) fn is_absolute(path: &str, windows: bool) -> bool {
+    if path.starts_with('/') {
+        return true;
+    }
+    if !windows {
+        return false;
+    }
+    let mut chars = path.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some('\\'), Some('\\'), _) => true,
+        (Some(drive), Some(':'), Some(separator)) => {
+            drive.is_ascii_alphabetic() && is_separator(separator, windows)
+        }
+        _ => false,
+    }
+}
+
+/// Returns a path without its trailing separators, except the root one (e.g. `/` or `C:\`).
+pub(crate) fn trim_separators(path: &str, windows: bool) -> &str {
+    let trimmed = path.trim_end_matches(|c| is_separator(c, windows));
+    if trimmed.is_empty() || (windows && trimmed.len() == 2 && trimmed.ends_with(':')) {
+        // the root keeps its separator
+        &path[..path.len().min(trimmed.len() + 1)]
+    } else {
+        trimmed
+    }
+}
+
+/// Returns the rest of a path after a directory it is in, e.g. `/src` for `/home/user/src` in
+/// `/home/user`: empty if the path is the directory, and starting with a separator otherwise.
+/// On Windows, the separators are equivalent and the case is ignored.
+///
+/// ### Parameters
+/// - `path`: the path
+/// - `directory`: the directory, without trailing separator
+/// - `windows`: whether the paths follow the Windows syntax
+///
+/// ### Returns
+/// The rest of the path, or `None` if the path is not in the directory.
+pub(crate) fn strip_directory<'a>(
+    path: &'a str,
+    directory: &str,
+    windows: bool,
+) -> Option<&'a str> {
+    let rest = if windows {
+        // the ASCII case folding keeps the lengths of the strings
+        let (start, rest) = path.split_at_checked(directory.len())?;
+        let same = start.chars().zip(directory.chars()).all(|(a, b)| {
+            a.eq_ignore_ascii_case(&b) || (is_separator(a, windows) && is_separator(b, windows))
+        });
+        if !same {
+            return None;
+        }
+        rest
+    } else {
+        path.strip_prefix(directory)?
+    };
+    match rest.chars().next() {
+        None => Some(rest),
+        Some(c) if is_separator(c, windows) => Some(rest),
+        Some(_) => None,
+    }
+}
+
+/// Returns a path in the form it is recorded in: on Windows, a path from a drive has `\`
+/// separators and an upper case drive letter, so that the spellings of a directory are a
+/// single entry of the history. The other paths, e.g. `/c/Users` in Git Bash, are kept.
+///
+/// ### Parameters
+/// - `path`: the path
+/// - `windows`: whether the path follows the Windows syntax
+pub(crate) fn recorded_form(path: &str, windows: bool) -> String {
+    if !windows {
+        return path.to_string();
+    }
+    let mut chars = path.chars();
+    match (chars.next(), chars.next()) {
+        (Some(drive), Some(':')) if drive.is_ascii_alphabetic() => format!(
+            "{}{}",
+            drive.to_ascii_uppercase(),
+            path[1..].replace('/', "\\")
+        ),
+        (Some('\\'), Some('\\')) => path.replace('/', "\\"),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_absolute() {
+        assert!(is_absolute("/home/user", false));
+        assert!(!is_absolute("home/user", false));
+        assert!(!is_absolute("C:\\Users\\user", false));
+
+        assert!(is_absolute("/home/user", true));
+        assert!(is_absolute("C:\\Users\\user", true));
+        assert!(is_absolute("d:/work", true));
+        assert!(is_absolute("\\\\server\\share", true));
+        assert!(!is_absolute("C:", true));
+        assert!(!is_absolute("C:work", true));
+        assert!(!is_absolute("Users\\user", true));
+        assert!(!is_absolute("", true));
+    }
+
+    #[test]
+    fn test_trim_separators() {
+        assert_eq!(trim_separators("/home/user/", false), "/home/user");
+        assert_eq!(trim_separators("/", false), "/");
+        assert_eq!(trim_separators("//", false), "/");
+        assert_eq!(trim_separators("", false), "");
+        assert_eq!(trim_separators("C:\\Users\\", false), "C:\\Users\\");
+
+        assert_eq!(
+            trim_separators("C:\\Users\\user\\", true),
+            "C:\\Users\\user"
+        );
+        assert_eq!(trim_separators("C:\\Users/", true), "C:\\Users");
+        assert_eq!(trim_separators("C:\\", true), "C:\\");
+        assert_eq!(trim_separators("C:", true), "C:");
+        assert_eq!(trim_separators("/", true), "/");
+    }
+
+    #[test]
+    fn test_strip_directory() {
+        assert_eq!(
+            strip_directory("/home/user/src", "/home/user", false),
+            Some("/src")
+        );
+        assert_eq!(strip_directory("/home/user", "/home/user", false), Some(""));
+        assert_eq!(strip_directory("/home/username", "/home/user", false), None);
+        assert_eq!(strip_directory("/Home/user/src", "/home/user", false), None);
+        // the directory is expected without trailing separator
+        assert_eq!(strip_directory("/tmp", "/", false), None);
+
+        assert_eq!(
+            strip_directory("C:\\Users\\user\\src", "C:\\Users\\user", true),
+            Some("\\src")
+        );
+        assert_eq!(
+            strip_directory("c:/users/USER/src", "C:\\Users\\user", true),
+            Some("/src")
+        );
+        assert_eq!(
+            strip_directory("C:\\Users\\username", "C:\\Users\\user", true),
+            None
+        );
+        assert_eq!(strip_directory("C:\\Users", "C:\\Users\\user", true), None);
+        assert_eq!(strip_directory("D:\\work", "C:\\", true), None);
+        // a multibyte character at the end of the directory
+        assert_eq!(strip_directory("/tmp/é", "/tmp/e", true), None);
+    }
+
+    #[test]
+    fn test_recorded_form() {
+        assert_eq!(recorded_form("/home/user/src", false), "/home/user/src");
+        assert_eq!(recorded_form("c:/Users/user", false), "c:/Users/user");
+
+        assert_eq!(recorded_form("c:/Users/user", true), "C:\\Users\\user");
+        assert_eq!(recorded_form("C:\\Users\\user", true), "C:\\Users\\user");
+        assert_eq!(recorded_form("\\\\server/share", true), "\\\\server\\share");
+        assert_eq!(recorded_form("/c/Users/user", true), "/c/Users/user");
+    }
+}
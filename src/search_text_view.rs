@@ -6,14 +6,16 @@ use std::{
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use log::{debug, error, warn};
 use ratatui::{
-    layout::{Constraint, Layout, Position, Rect},
-    style::Style,
+    layout::{Alignment, Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
     widgets::Paragraph,
 };
 use tokio::sync::broadcast::Sender;
 
 use crate::{
-    config::Config,
+    config::{Config, DEFAULT_PROFILE_NAME},
+    model::DataStatePayload,
+    tableview::DisplayModePayload,
     tui::{
         EventCaptured, GenericEvent, ManagerAction, View, ViewBuilder, ViewManager,
         event::ApplicationEvent,
@@ -28,29 +30,77 @@ pub struct SearchTextState {
     search_string: String,
     search_string_cursor_index: usize,
     fuzzy_match: bool,
+    recent_searches: Vec<String>,
+    recent_search_index: Option<usize>,
+    // the type of the objects filtered, or `None` if the search is shared by all the views
+    objects_type: Option<String>,
 }
 
 pub struct SearchDescriptionPayload {
     pub search_string: String,
     pub fuzzy_match: bool,
+    /// The type of the objects filtered, or `None` for all of them
+    pub objects_type: Option<String>,
 }
 
 impl SearchTextState {
-    pub fn new(view_manager: Rc<ViewManager>) -> SearchTextState {
+    pub fn new(view_manager: Rc<ViewManager>, recent_searches: Vec<String>) -> SearchTextState {
         SearchTextState {
             tx: view_manager.tx(),
             search_string: String::new(),
             search_string_cursor_index: 0,
             fuzzy_match: false,
+            recent_searches,
+            recent_search_index: None,
+            objects_type: None,
         }
     }
 
+    /// Restricts the search to the objects of a type, the other views keeping their own search.
+    pub fn with_objects_type(mut self, objects_type: &str) -> SearchTextState {
+        self.objects_type = Some(objects_type.to_string());
+        self
+    }
+
+    /// Starts in fuzzy mode rather than exact mode.
+    pub fn with_fuzzy_match(mut self, fuzzy_match: bool) -> SearchTextState {
+        self.fuzzy_match = fuzzy_match;
+        self
+    }
+
+    /// Starts with a search text, e.g. given on the command line.
+    pub fn with_search_string(mut self, search_string: &str) -> SearchTextState {
+        self.search_string = search_string.to_string();
+        self.search_string_cursor_index = self.search_string.len();
+        self
+    }
+
+    pub fn search_string(&self) -> &str { &self.search_string }
+
+    /// Replace the search string by the previous recent search (the most recent first),
+    /// cycling back to the most recent one after the oldest.
+    fn cycle_recent_searches(&mut self) {
+        if self.recent_searches.is_empty() {
+            debug!("No recent search");
+            return;
+        }
+        let index = match self.recent_search_index {
+            Some(index) => (index + 1) % self.recent_searches.len(),
+            None => 0,
+        };
+        self.recent_search_index = Some(index);
+        self.search_string = self.recent_searches[index].clone();
+        self.search_string_cursor_index = self.search_string.len();
+        self.publish();
+    }
+
     fn publish(&self) {
         let event = GenericEvent::ApplicationEvent(ApplicationEvent {
             id: String::from("search.description"),
             payload: Some(Arc::new(SearchDescriptionPayload {
                 search_string: self.search_string.clone(),
                 fuzzy_match: self.fuzzy_match,
+                objects_type: self.objects_type.clone(),
             })),
         });
         let result = self.tx.send(event);
@@ -63,11 +113,51 @@ impl SearchTextState {
 pub struct SearchTextView {
     config: Arc<Config>,
     state: Arc<Mutex<SearchTextState>>,
+    objects_type: String,
+    no_match: bool,
+    // the name of the order of the list, if it can be sorted
+    sort_name: Option<&'static str>,
+    // the way the paths are displayed
+    display_with_shortcuts: bool,
+    display_with_home_tilde: bool,
 }
 
 impl SearchTextView {
-    pub fn builder(config: Arc<Config>, state: Arc<Mutex<SearchTextState>>) -> ViewBuilder {
-        ViewBuilder::from(Box::new(SearchTextView { config, state }))
+    pub fn builder(
+        config: Arc<Config>,
+        state: Arc<Mutex<SearchTextState>>,
+        objects_type: String,
+    ) -> ViewBuilder {
+        ViewBuilder::from(Box::new(Self::new(config, state, objects_type)))
+            .with_publish_events(true)
+    }
+
+    fn new(
+        config: Arc<Config>,
+        state: Arc<Mutex<SearchTextState>>,
+        objects_type: String,
+    ) -> SearchTextView {
+        let display_with_shortcuts = config.shorten_paths;
+        let display_with_home_tilde = config.substitute_home;
+        SearchTextView {
+            config,
+            state,
+            objects_type,
+            no_match: false,
+            sort_name: None,
+            display_with_shortcuts,
+            display_with_home_tilde,
+        }
+    }
+
+    /// Returns the compact name of the way the paths are displayed, empty for the default one.
+    fn display_mode_name(&self) -> &'static str {
+        match (self.display_with_shortcuts, self.display_with_home_tilde) {
+            (true, true) => "",
+            (false, true) => "no shortcuts",
+            (true, false) => "no ~",
+            (false, false) => "full paths",
+        }
     }
 }
 
@@ -89,14 +179,37 @@ impl View for SearchTextView {
         let search_text_area: Rect;
         {
             // bottom line
-            let horizontal =
-                Layout::horizontal([Constraint::Length(4), Constraint::Percentage(100)]).spacing(0);
             let left: Rect;
-            [left, search_text_area] = horizontal.areas(input);
+            let right: Rect;
+            // the active profile (unless the default one), the display mode and the sort mode
+            let mut right_text: Vec<String> = vec![];
+            if self.config.profile != DEFAULT_PROFILE_NAME {
+                right_text.push(format!("[{}]", self.config.profile));
+            }
+            let display_mode_name = self.display_mode_name();
+            if !display_mode_name.is_empty() {
+                right_text.push(display_mode_name.to_string());
+            }
+            if let Some(sort_name) = self.sort_name {
+                right_text.push(sort_name.to_string());
+            }
+            let right_text = right_text.join(" ");
+            [left, search_text_area, right] = Layout::horizontal([
+                Constraint::Length(4),
+                Constraint::Fill(1),
+                // with a space before the text, if any
+                Constraint::Length(match right_text.chars().count() {
+                    0 => 0,
+                    len => len as u16 + 1,
+                }),
+            ])
+            .areas(input);
 
             // The left exact/fuzzy indicator
 
-            let mut pa = if state_lock.fuzzy_match {
+            let mut pa = if self.config.type_ahead {
+                Paragraph::new("[t]")
+            } else if state_lock.fuzzy_match {
                 Paragraph::new("[f]")
             } else {
                 Paragraph::new("[e]")
@@ -110,23 +223,40 @@ impl View for SearchTextView {
             );
             frame.render_widget(pa, left);
 
-            // Draw the free text area
+            // Draw the free text area (dimmed when nothing matches)
 
-            let pa = Paragraph::new(format!("{}{}", SEARCH_PROMPT, search_string.as_str())).style(
-                self.config.styles.path_style.bg(self
-                    .config
-                    .styles
-                    .free_text_area_bg_color
-                    .unwrap()),
-            );
+            let mut style = self.config.styles.path_style.bg(self
+                .config
+                .styles
+                .free_text_area_bg_color
+                .unwrap());
+            if self.no_match && !search_string.is_empty() {
+                style = style.dim();
+            }
+            let pa =
+                Paragraph::new(format!("{}{}", SEARCH_PROMPT, search_string.as_str())).style(style);
             frame.render_widget(pa, search_text_area);
+
+            // The right profile and sort mode indicator
+            let pa = Paragraph::new(right_text)
+                .style(
+                    self.config.styles.date_style.bg(self
+                        .config
+                        .styles
+                        .free_text_area_bg_color
+                        .unwrap()),
+                )
+                .alignment(Alignment::Right);
+            frame.render_widget(pa, right);
         }
 
-        if active {
+        if active && !self.config.type_ahead {
             // Don't activate the cursor if not active...
             let search_string_cursor_index = state_lock.search_string_cursor_index;
+            let cursor_x =
+                search_text_area.x + search_string_cursor_index as u16 + SEARCH_PROMPT.len() as u16;
             frame.set_cursor_position(Position::new(
-                search_text_area.x + search_string_cursor_index as u16 + SEARCH_PROMPT.len() as u16,
+                cursor_x.min(search_text_area.right().saturating_sub(1)),
                 search_text_area.y,
             ));
         }
@@ -136,6 +266,16 @@ impl View for SearchTextView {
         let _ = key_event;
         debug!("handle_key_event");
 
+        if self.config.type_ahead {
+            // the keys are used by the type-ahead of the list
+            return (EventCaptured::No, ManagerAction::new(false));
+        }
+
+        if key_event.code != KeyCode::Char('r') || key_event.modifiers != KeyModifiers::CONTROL {
+            // any other key ends the cycling through the recent searches
+            self.state.lock().unwrap().recent_search_index = None;
+        }
+
         match key_event.code {
             KeyCode::Backspace => {
                 let mut state_lock = self.state.lock().unwrap();
@@ -181,6 +321,8 @@ impl View for SearchTextView {
                     let mut state_lock = self.state.lock().unwrap();
                     state_lock.fuzzy_match = !state_lock.fuzzy_match;
                     state_lock.publish();
+                } else if c == 'r' {
+                    self.state.lock().unwrap().cycle_recent_searches();
                 }
             }
             _ => {
@@ -190,4 +332,234 @@ impl View for SearchTextView {
 
         (EventCaptured::No, ManagerAction::new(false))
     }
+
+    fn handle_application_event(&mut self, ae: &ApplicationEvent) {
+        if ae.id == "data.payload"
+            && let Some(payload) = &ae.payload
+            && let Some(payload) = payload.downcast_ref::<DataStatePayload>()
+            && payload.objects_type == self.objects_type
+        {
+            self.no_match = payload.is_empty;
+            self.sort_name = payload.sort_name;
+        }
+        if ae.id == "display.mode"
+            && let Some(payload) = &ae.payload
+            && let Some(payload) = payload.downcast_ref::<DisplayModePayload>()
+        {
+            self.display_with_shortcuts = payload.display_with_shortcuts;
+            self.display_with_home_tilde = payload.display_with_home_tilde;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend, style::Modifier};
+
+    use super::*;
+    use crate::store::{ListOrder, SortMode};
+
+    fn press(view: &mut SearchTextView, code: KeyCode, modifiers: KeyModifiers) {
+        view.handle_key_event(KeyEvent::new(code, modifiers));
+    }
+
+    #[test]
+    fn test_cycle_recent_searches() {
+        let state = Arc::new(Mutex::new(SearchTextState::new(
+            Rc::new(ViewManager::new()),
+            vec!["src".to_string(), "doc".to_string()],
+        )));
+        let mut view = SearchTextView::new(
+            Arc::new(Config::default()),
+            state.clone(),
+            "path".to_string(),
+        );
+
+        // The most recent first, then older ones, then back to the most recent
+        press(&mut view, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(state.lock().unwrap().search_string(), "src");
+        press(&mut view, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(state.lock().unwrap().search_string(), "doc");
+        press(&mut view, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(state.lock().unwrap().search_string(), "src");
+
+        // Editing ends the cycling, the cursor is at the end of the recalled search
+        press(&mut view, KeyCode::Char('x'), KeyModifiers::NONE);
+        assert_eq!(state.lock().unwrap().search_string(), "srcx");
+        press(&mut view, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(state.lock().unwrap().search_string(), "src");
+    }
+
+    #[test]
+    fn test_no_recent_searches() {
+        let state = Arc::new(Mutex::new(SearchTextState::new(
+            Rc::new(ViewManager::new()),
+            vec![],
+        )));
+        let mut view = SearchTextView::new(
+            Arc::new(Config::default()),
+            state.clone(),
+            "path".to_string(),
+        );
+        press(&mut view, KeyCode::Char('a'), KeyModifiers::NONE);
+        press(&mut view, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(state.lock().unwrap().search_string(), "a");
+    }
+
+    #[test]
+    fn test_dimmed_when_nothing_matches() {
+        let state = Arc::new(Mutex::new(SearchTextState::new(
+            Rc::new(ViewManager::new()),
+            vec![],
+        )));
+        let mut view = SearchTextView::new(
+            Arc::new(Config::default()),
+            state.clone(),
+            "path".to_string(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(20, 1)).unwrap();
+        let mut is_dimmed = |view: &mut SearchTextView, is_empty: bool, objects_type: &str| {
+            view.handle_application_event(&ApplicationEvent {
+                id: String::from("data.payload"),
+                payload: Some(Arc::new(DataStatePayload {
+                    objects_type: objects_type.to_string(),
+                    is_empty,
+                    is_error: false,
+                    count: None,
+                    sort_name: None,
+                    include_hidden: false,
+                    more: None,
+                    is_searching: false,
+                })),
+            });
+            terminal
+                .draw(|frame| view.draw(frame, frame.area(), false))
+                .unwrap();
+            // the first character of the search string
+            terminal.backend().buffer().content[6]
+                .modifier
+                .contains(Modifier::DIM)
+        };
+
+        press(&mut view, KeyCode::Char('x'), KeyModifiers::NONE);
+        assert!(!is_dimmed(&mut view, false, "path"));
+        assert!(is_dimmed(&mut view, true, "path"));
+        // another list does not change the state
+        assert!(is_dimmed(&mut view, false, "shortcut"));
+        assert!(!is_dimmed(&mut view, false, "path"));
+    }
+
+    #[test]
+    fn test_type_ahead_disables_the_search() {
+        let state = Arc::new(Mutex::new(SearchTextState::new(
+            Rc::new(ViewManager::new()),
+            vec!["src".to_string()],
+        )));
+        let mut view = SearchTextView::new(
+            Arc::new(Config {
+                type_ahead: true,
+                ..Config::default()
+            }),
+            state.clone(),
+            "path".to_string(),
+        );
+        press(&mut view, KeyCode::Char('a'), KeyModifiers::NONE);
+        press(&mut view, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        press(&mut view, KeyCode::Char('f'), KeyModifiers::CONTROL);
+        let state = state.lock().unwrap();
+        assert_eq!(state.search_string(), "");
+        assert!(!state.fuzzy_match);
+    }
+
+    #[test]
+    fn test_sort_mode() {
+        let state = Arc::new(Mutex::new(SearchTextState::new(
+            Rc::new(ViewManager::new()),
+            vec![],
+        )));
+        let mut view = SearchTextView::new(Arc::new(Config::default()), state, "path".to_string());
+        let mut terminal = Terminal::new(TestBackend::new(30, 1)).unwrap();
+        let mut render = |view: &mut SearchTextView, sort_mode: Option<SortMode>| -> String {
+            view.handle_application_event(&ApplicationEvent {
+                id: String::from("data.payload"),
+                payload: Some(Arc::new(DataStatePayload {
+                    objects_type: "path".to_string(),
+                    is_empty: false,
+                    is_error: false,
+                    count: None,
+                    sort_name: sort_mode.map(ListOrder::name),
+                    include_hidden: false,
+                    more: None,
+                    is_searching: false,
+                })),
+            });
+            terminal
+                .draw(|frame| view.draw(frame, frame.area(), false))
+                .unwrap();
+            terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect()
+        };
+
+        assert_eq!(render(&mut view, None).trim_end(), "[e] >");
+        assert!(render(&mut view, Some(SortMode::Frecency)).ends_with(" frecency"));
+
+        view.config = Arc::new(Config {
+            profile: "work".to_string(),
+            ..Config::default()
+        });
+        assert!(render(&mut view, Some(SortMode::Recency)).ends_with(" [work] recency"));
+        assert!(render(&mut view, None).ends_with(" [work]"));
+    }
+
+    #[test]
+    fn test_display_mode() {
+        let state = Arc::new(Mutex::new(SearchTextState::new(
+            Rc::new(ViewManager::new()),
+            vec![],
+        )));
+        let mut view = SearchTextView::new(
+            Arc::new(Config {
+                substitute_home: false,
+                ..Config::default()
+            }),
+            state,
+            "path".to_string(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(30, 1)).unwrap();
+        let mut render = |view: &mut SearchTextView| -> String {
+            terminal
+                .draw(|frame| view.draw(frame, frame.area(), false))
+                .unwrap();
+            terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect()
+        };
+        let display_mode = |view: &mut SearchTextView, shortcuts: bool, home_tilde: bool| {
+            view.handle_application_event(&ApplicationEvent {
+                id: String::from("display.mode"),
+                payload: Some(Arc::new(DisplayModePayload {
+                    display_with_shortcuts: shortcuts,
+                    display_with_home_tilde: home_tilde,
+                })),
+            });
+        };
+
+        // the configured default
+        assert!(render(&mut view).ends_with(" no ~"));
+        display_mode(&mut view, false, false);
+        assert!(render(&mut view).ends_with(" full paths"));
+        display_mode(&mut view, false, true);
+        assert!(render(&mut view).ends_with(" no shortcuts"));
+        display_mode(&mut view, true, true);
+        assert_eq!(render(&mut view).trim_end(), "[e] >");
+    }
 }
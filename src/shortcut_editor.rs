@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{env, sync::Arc};
 
 use crossterm::event::{KeyCode, KeyEvent};
 use log::{debug, error};
@@ -12,57 +12,207 @@ use tui_textarea::{Input, TextArea};
 
 use crate::{
     config::Config,
+    path_syntax::{WINDOWS, is_absolute, is_separator},
     store,
     store::Shortcut,
     tui::{EventCaptured, ManagerAction, View, ViewBuilder},
 };
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
 enum EditorField {
     Name,
     Description,
+    Path,
     YesButton,
     CancelButton,
 }
 
+const FIELDS_EDITOR_FIELDS: [EditorField; 4] = [
+    EditorField::Name,
+    EditorField::Description,
+    EditorField::YesButton,
+    EditorField::CancelButton,
+];
+
+const PATH_EDITOR_FIELDS: [EditorField; 3] = [
+    EditorField::Path,
+    EditorField::YesButton,
+    EditorField::CancelButton,
+];
+
+/// Expands a path typed by the user: a leading `~` is replaced by the home directory, and the
+/// `$NAME` or `${NAME}` environment variables by their value.
+///
+/// ### Parameters
+/// path: the path to expand
+/// home: the home directory, if any
+/// var: returns the value of an environment variable, if it is defined
+///
+/// ### Returns
+/// The expanded path, or a message if it is empty, uses an undefined variable or is not absolute.
+pub(crate) fn expand_path(
+    path: &str,
+    home: Option<String>,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(String::from("the path is empty"));
+    }
+
+    let mut expanded = String::new();
+    let mut rest = path;
+    if let Some(after) = rest.strip_prefix('~')
+        && (after.is_empty() || after.starts_with(|c| is_separator(c, WINDOWS)))
+    {
+        let home = home.ok_or_else(|| String::from("no home directory"))?;
+        expanded.push_str(&home);
+        rest = &rest[1..];
+    }
+
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+        let (name, len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => return Err(format!("unclosed variable: ${}", after)),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        if name.is_empty() {
+            // a lone '$' is kept as is
+            expanded.push('$');
+        } else {
+            let value = var(name).ok_or_else(|| format!("undefined variable: ${}", name))?;
+            expanded.push_str(&value);
+        }
+        rest = &after[len..];
+    }
+    expanded.push_str(rest);
+
+    if !is_absolute(&expanded, WINDOWS) {
+        return Err(format!("not an absolute path: {}", expanded));
+    }
+    Ok(expanded)
+}
+
+/// The part of a shortcut which is edited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShortcutEditMode {
+    /// The name and the description
+    Fields,
+    /// The path, e.g. when its directory was moved
+    Path,
+}
+
 pub struct ShortcutEditor {
     store: store::Store,
     config: Arc<Config>,
+    mode: ShortcutEditMode,
     shortcut: Option<Shortcut>,
     name_textarea: Option<TextArea<'static>>,
     description_textarea: Option<TextArea<'static>>,
+    path_textarea: Option<TextArea<'static>>,
     selected_field: EditorField,
+    /// the reason why the path could not be saved
+    error: Option<String>,
 }
 
 impl ShortcutEditor {
-    pub fn builder(store: store::Store, config: Arc<Config>, shortcut: Shortcut) -> ViewBuilder {
-        ViewBuilder::from(Box::new(Self {
+    /// Builds the editor of a shortcut.
+    ///
+    /// ### Parameters
+    /// - `store`: the store where the shortcut is saved
+    /// - `config`: the configuration, for the styles
+    /// - `shortcut`: the edited shortcut
+    /// - `mode`: whether the name and description, or the path of the shortcut is edited
+    pub fn builder(
+        store: store::Store,
+        config: Arc<Config>,
+        shortcut: Shortcut,
+        mode: ShortcutEditMode,
+    ) -> ViewBuilder {
+        ViewBuilder::from(Box::new(Self::new(store, config, shortcut, mode)))
+    }
+
+    fn new(
+        store: store::Store,
+        config: Arc<Config>,
+        shortcut: Shortcut,
+        mode: ShortcutEditMode,
+    ) -> Self {
+        let mut editor = Self {
             store,
             config,
+            mode,
             shortcut: Some(shortcut),
             name_textarea: None,
             description_textarea: None,
+            path_textarea: None,
             selected_field: EditorField::Name,
-        }))
+            error: None,
+        };
+        editor.selected_field = editor.fields()[0];
+        editor
+    }
+
+    /// The fields of the editor, in the order of the Tab key
+    fn fields(&self) -> &'static [EditorField] {
+        match self.mode {
+            ShortcutEditMode::Fields => &FIELDS_EDITOR_FIELDS,
+            ShortcutEditMode::Path => &PATH_EDITOR_FIELDS,
+        }
+    }
+
+    /// Selects the next field, or the previous one if `forward` is false
+    fn select_next_field(&mut self, forward: bool) {
+        let fields = self.fields();
+        let index = fields
+            .iter()
+            .position(|field| *field == self.selected_field)
+            .unwrap_or(0);
+        let next = if forward {
+            (index + 1) % fields.len()
+        } else {
+            (index + fields.len() - 1) % fields.len()
+        };
+        self.selected_field = fields[next];
+    }
+
+    fn new_textarea(&self, title: &'static str, text: Option<&str>) -> TextArea<'static> {
+        let mut textarea = TextArea::default();
+        textarea.set_block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_style(self.config.styles.title_style)
+                .border_style(Style::default().fg(self.config.styles.border_color.unwrap())),
+        );
+        textarea.set_cursor_line_style(self.config.styles.text_style);
+        if let Some(text) = text {
+            textarea.insert_str(text);
+        }
+        textarea
+    }
+
+    fn first_line<'a>(textarea: Option<&'a TextArea<'static>>) -> Option<&'a str> {
+        textarea
+            .and_then(|textarea| textarea.lines().first())
+            .map(|line| line.as_str())
     }
 
     fn save_shortcut(&mut self) {
         // Save the shortcut
         debug!("Saving shortcut");
-        if let Some(name_textarea) = self.name_textarea.as_ref()
-            && let Some(description_textarea) = self.description_textarea.as_ref()
-        {
-            let name = if name_textarea.lines().is_empty() {
-                ""
-            } else {
-                name_textarea.lines()[0].as_str()
-            };
-
-            let description = if description_textarea.lines().is_empty() {
-                None
-            } else {
-                Some(description_textarea.lines()[0].as_str())
-            };
+        if self.name_textarea.is_some() && self.description_textarea.is_some() {
+            let name = Self::first_line(self.name_textarea.as_ref()).unwrap_or("");
+            let description = Self::first_line(self.description_textarea.as_ref());
 
             debug!("Saving name: {:?}, description: {:?}", name, description);
             if let Some(shortcut) = self.shortcut.as_ref()
@@ -77,48 +227,105 @@ impl ShortcutEditor {
             }
         }
     }
-}
 
-impl View for ShortcutEditor {
-    fn init(&mut self) {
-        debug!("Initializing ShortcutEditor view");
+    /// Saves the path typed in the editor, once expanded, if it is an existing directory.
+    ///
+    /// ### Returns
+    /// Ok(()) if the path was saved, otherwise the message to display in the editor.
+    fn save_path(&mut self) -> Result<(), String> {
+        let text = Self::first_line(self.path_textarea.as_ref()).unwrap_or("");
+        let path = expand_path(
+            text,
+            dirs::home_dir().map(|home| home.to_string_lossy().to_string()),
+            |name| env::var(name).ok(),
+        )?;
+        if !std::path::Path::new(&path).is_dir() {
+            return Err(format!("not a directory: {}", path));
+        }
 
-        // Initialize name textarea
-        let mut name_textarea = TextArea::default();
-        name_textarea.set_block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Name")
-                .title_style(self.config.styles.title_style)
-                .border_style(Style::default().fg(self.config.styles.border_color.unwrap())),
-        );
-        name_textarea.set_cursor_line_style(self.config.styles.text_style);
+        debug!("Saving path: {}", path);
         if let Some(shortcut) = self.shortcut.as_ref() {
-            name_textarea.insert_str(shortcut.name.as_str());
+            self.store
+                .update_shortcut_path(&shortcut.name, &path)
+                .map_err(|err| {
+                    error!("Error updating the shortcut path: {}", err);
+                    format!("update failed: {}", err)
+                })?;
         }
-        self.name_textarea = Some(name_textarea);
+        Ok(())
+    }
 
-        // Initialize description textarea
-        let mut description_textarea = TextArea::default();
-        description_textarea.set_block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Description")
-                .title_style(self.config.styles.title_style)
-                .border_style(Style::default().fg(self.config.styles.border_color.unwrap())),
-        );
-        description_textarea.set_cursor_line_style(self.config.styles.text_style);
-        if let Some(description) = self.shortcut.as_ref().unwrap().description.as_ref() {
-            description_textarea.insert_str(description.as_str());
+    /// Saves the edited fields.
+    ///
+    /// ### Returns
+    /// Whether the editor can be closed.
+    fn save(&mut self) -> bool {
+        match self.mode {
+            ShortcutEditMode::Fields => {
+                self.save_shortcut();
+                true
+            }
+            ShortcutEditMode::Path => match self.save_path() {
+                Ok(()) => true,
+                Err(message) => {
+                    self.error = Some(message);
+                    self.selected_field = EditorField::Path;
+                    false
+                }
+            },
         }
-        self.description_textarea = Some(description_textarea);
+    }
+
+    fn style_textarea(&mut self, field: EditorField, title: &'static str) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_style(self.config.styles.text_style)
+            .border_style(Style::default().fg(self.config.styles.border_color.unwrap()));
+        let selected = self.selected_field == field;
+        let textarea = match field {
+            EditorField::Name => self.name_textarea.as_mut(),
+            EditorField::Description => self.description_textarea.as_mut(),
+            EditorField::Path => self.path_textarea.as_mut(),
+            _ => None,
+        };
+        if let Some(textarea) = textarea {
+            textarea.set_block(block);
+            // Show cursor only if this field is selected
+            if selected {
+                textarea.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+            } else {
+                textarea.set_cursor_style(Style::default());
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        self.shortcut = None;
+        self.name_textarea = None;
+        self.description_textarea = None;
+        self.path_textarea = None;
+    }
+}
+
+impl View for ShortcutEditor {
+    fn init(&mut self) {
+        debug!("Initializing ShortcutEditor view");
+        let shortcut = self.shortcut.clone().unwrap();
+        self.name_textarea = Some(self.new_textarea("Name", Some(shortcut.name.as_str())));
+        self.description_textarea =
+            Some(self.new_textarea("Description", shortcut.description.as_deref()));
+        self.path_textarea = Some(self.new_textarea("Path", Some(shortcut.path.as_str())));
     }
 
     fn draw(&mut self, frame: &mut Frame, modal_area: Rect, _active: bool) {
         debug!("Drawing shortcut editor");
 
         // Only draw if we have textareas initialized
-        if self.name_textarea.is_none() || self.description_textarea.is_none() {
+        if self.name_textarea.is_none()
+            || self.description_textarea.is_none()
+            || self.path_textarea.is_none()
+        {
             return;
         }
 
@@ -145,70 +352,59 @@ impl View for ShortcutEditor {
         }
 
         // Draw the outer border
+        let title = match self.mode {
+            ShortcutEditMode::Fields => "Edit Shortcut",
+            ShortcutEditMode::Path => "Edit Shortcut Path",
+        };
         let block = Block::default()
-            .title("Edit Shortcut")
+            .title(title)
             .title_style(self.config.styles.title_style)
             .borders(Borders::ALL)
             .border_style(Style::default().fg(self.config.styles.border_color.unwrap()))
             .style(self.config.styles.text_style);
         frame.render_widget(block, modal_area);
 
-        // Split modal into name, description, and buttons
+        // Split modal into the textareas, and buttons
         let inner = Rect {
             x: modal_area.x + 1,
             y: modal_area.y + 1,
-            width: modal_area.width - 2,
-            height: modal_area.height - 2,
+            width: modal_area.width.saturating_sub(2),
+            height: modal_area.height.saturating_sub(2),
         };
         let vchunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // name textarea
-                Constraint::Length(3), // description textarea
+                Constraint::Length(3), // name or path textarea
+                Constraint::Length(3), // description textarea or error
                 Constraint::Fill(1),   // spacing
                 Constraint::Length(1), // buttons
             ])
             .split(inner);
 
         // Update border styles and cursor visibility based on selected field
-        if let Some(name_textarea) = self.name_textarea.as_mut() {
-            name_textarea.set_block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Name")
-                    .title_style(self.config.styles.text_style)
-                    .border_style(Style::default().fg(self.config.styles.border_color.unwrap())),
-            );
-            // Show cursor only if this field is selected
-            if self.selected_field == EditorField::Name {
-                name_textarea.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
-            } else {
-                name_textarea.set_cursor_style(Style::default());
+        match self.mode {
+            ShortcutEditMode::Fields => {
+                self.style_textarea(EditorField::Name, "Name");
+                self.style_textarea(EditorField::Description, "Description");
+                if let Some(name_textarea) = self.name_textarea.as_ref() {
+                    frame.render_widget(name_textarea, vchunks[0]);
+                }
+                if let Some(description_textarea) = self.description_textarea.as_ref() {
+                    frame.render_widget(description_textarea, vchunks[1]);
+                }
             }
-        }
-        if let Some(name_textarea) = self.name_textarea.as_ref() {
-            frame.render_widget(name_textarea, vchunks[0]);
-        }
-
-        if let Some(description_textarea) = self.description_textarea.as_mut() {
-            description_textarea.set_block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Description")
-                    .title_style(self.config.styles.text_style)
-                    .border_style(Style::default().fg(self.config.styles.border_color.unwrap())),
-            );
-            // Show cursor only if this field is selected
-            if self.selected_field == EditorField::Description {
-                description_textarea
-                    .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
-            } else {
-                description_textarea.set_cursor_style(Style::default());
+            ShortcutEditMode::Path => {
+                self.style_textarea(EditorField::Path, "Path");
+                if let Some(path_textarea) = self.path_textarea.as_ref() {
+                    frame.render_widget(path_textarea, vchunks[0]);
+                }
+                if let Some(error) = self.error.as_ref() {
+                    let error =
+                        Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
+                    frame.render_widget(error, vchunks[1]);
+                }
             }
         }
-        if let Some(description_textarea) = self.description_textarea.as_ref() {
-            frame.render_widget(description_textarea, vchunks[1]);
-        }
 
         // Buttons at the bottom
         let button_layout = Layout::default()
@@ -254,29 +450,17 @@ impl View for ShortcutEditor {
         match key_event.code {
             KeyCode::Esc => {
                 // Cancel - close without saving
-                self.shortcut = None;
-                self.name_textarea = None;
-                self.description_textarea = None;
+                self.close();
                 close = true;
             }
             KeyCode::Tab => {
                 // Navigate between fields
-                self.selected_field = match self.selected_field {
-                    EditorField::Name => EditorField::Description,
-                    EditorField::Description => EditorField::YesButton,
-                    EditorField::YesButton => EditorField::CancelButton,
-                    EditorField::CancelButton => EditorField::Name,
-                };
+                self.select_next_field(true);
                 redraw = true;
             }
             KeyCode::BackTab => {
                 // Navigate backwards between fields (Shift+Tab)
-                self.selected_field = match self.selected_field {
-                    EditorField::Name => EditorField::CancelButton,
-                    EditorField::Description => EditorField::Name,
-                    EditorField::YesButton => EditorField::Description,
-                    EditorField::CancelButton => EditorField::YesButton,
-                };
+                self.select_next_field(false);
                 redraw = true;
             }
             KeyCode::Left | KeyCode::Right
@@ -294,30 +478,24 @@ impl View for ShortcutEditor {
                 redraw = true;
             }
             KeyCode::Enter => {
-                // Handle button press
-                if self.selected_field != EditorField::CancelButton {
-                    self.save_shortcut();
+                // Handle button press, the editor stays open if the input is invalid
+                if self.selected_field == EditorField::CancelButton || self.save() {
+                    self.close();
+                    close = true;
                 }
-                // Close regardless of Yes or Cancel
-                self.shortcut = None;
-                self.name_textarea = None;
-                self.description_textarea = None;
-                close = true;
+                redraw = true;
             }
             _ => {
                 // Handle input for the selected textarea
-                match self.selected_field {
-                    EditorField::Name => {
-                        if let Some(textarea) = self.name_textarea.as_mut() {
-                            textarea.input(Input::from(key_event));
-                        }
-                    }
-                    EditorField::Description => {
-                        if let Some(textarea) = self.description_textarea.as_mut() {
-                            textarea.input(Input::from(key_event));
-                        }
-                    }
-                    _ => {}
+                let textarea = match self.selected_field {
+                    EditorField::Name => self.name_textarea.as_mut(),
+                    EditorField::Description => self.description_textarea.as_mut(),
+                    EditorField::Path => self.path_textarea.as_mut(),
+                    _ => None,
+                };
+                if let Some(textarea) = textarea {
+                    textarea.input(Input::from(key_event));
+                    self.error = None;
                 }
                 redraw = true;
             }
@@ -329,3 +507,106 @@ impl View for ShortcutEditor {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn press(editor: &mut ShortcutEditor, code: KeyCode) -> bool {
+        let (_, action) = editor.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+        action.close
+    }
+
+    fn type_text(editor: &mut ShortcutEditor, text: &str) {
+        for c in text.chars() {
+            press(editor, KeyCode::Char(c));
+        }
+    }
+
+    fn path_editor(store: &store::Store) -> ShortcutEditor {
+        store.add_shortcut("docs", "/home/user/docs", None).unwrap();
+        let shortcut = store.find_shortcut("docs").unwrap().unwrap();
+        let mut editor = ShortcutEditor::new(
+            store.clone(),
+            Arc::new(Config::default()),
+            shortcut,
+            ShortcutEditMode::Path,
+        );
+        editor.init();
+        editor
+    }
+
+    #[test]
+    fn test_expand_path() {
+        let expand = |path: &str| {
+            expand_path(path, Some("/home/testuser".to_string()), |name| {
+                (name == "ROOT").then(|| "/data".to_string())
+            })
+        };
+        assert_eq!(expand(" ~ ").unwrap(), "/home/testuser");
+        assert_eq!(expand("~/src").unwrap(), "/home/testuser/src");
+        assert_eq!(expand("/data/~user").unwrap(), "/data/~user");
+        assert_eq!(expand("$ROOT/src").unwrap(), "/data/src");
+        assert_eq!(expand("${ROOT}_old/$").unwrap(), "/data_old/$");
+
+        assert!(expand("").is_err());
+        assert!(expand("src").is_err());
+        assert!(expand("$UNDEFINED/src").is_err());
+        assert!(expand("${ROOT").is_err());
+        assert!(expand_path("~/src", None, |_| None).is_err());
+    }
+
+    #[test]
+    fn test_edit_path() {
+        let store = store::Store::in_memory().unwrap();
+        let mut editor = path_editor(&store);
+        assert_eq!(editor.selected_field, EditorField::Path);
+
+        let dir = tempfile::tempdir().unwrap();
+        let new_path = dir.path().to_string_lossy().to_string();
+        // the input is prefilled with the current path
+        for _ in 0.."/home/user/docs".len() {
+            press(&mut editor, KeyCode::Backspace);
+        }
+        type_text(&mut editor, &new_path);
+        assert!(press(&mut editor, KeyCode::Enter));
+        assert_eq!(store.find_shortcut("docs").unwrap().unwrap().path, new_path);
+    }
+
+    #[test]
+    fn test_edit_path_invalid() {
+        let store = store::Store::in_memory().unwrap();
+        let mut editor = path_editor(&store);
+
+        // not a directory: the editor stays open with the reason
+        type_text(&mut editor, "/missing");
+        press(&mut editor, KeyCode::Tab);
+        assert_eq!(editor.selected_field, EditorField::YesButton);
+        assert!(!press(&mut editor, KeyCode::Enter));
+        assert_eq!(editor.selected_field, EditorField::Path);
+        assert!(
+            editor
+                .error
+                .as_ref()
+                .unwrap()
+                .starts_with("not a directory")
+        );
+        assert_eq!(
+            store.find_shortcut("docs").unwrap().unwrap().path,
+            "/home/user/docs"
+        );
+
+        // typing clears the error, and cancel closes without saving
+        type_text(&mut editor, "x");
+        assert_eq!(editor.error, None);
+        press(&mut editor, KeyCode::BackTab);
+        assert_eq!(editor.selected_field, EditorField::CancelButton);
+        assert!(press(&mut editor, KeyCode::Enter));
+        assert_eq!(
+            store.find_shortcut("docs").unwrap().unwrap().path,
+            "/home/user/docs"
+        );
+    }
+}
@@ -10,10 +10,13 @@ use ratatui::layout::{Constraint, Layout, Rect};
 use crate::{
     config::Config,
     list_indicator_view::ListIndicatorView,
-    model::ListFunction,
+    model::{CountFunction, ListFunction},
     search_text_view::{SearchTextState, SearchTextView},
     store::Shortcut,
-    tableview::{DeleteFn, EditorViewBuilder, RowifyFn, TableView, TableViewState},
+    tableview::{
+        ActionFn, ColumnWidthFn, DetailsFn, EditorViewBuilder, ExactChoiceFn, LastChoiceFn,
+        RefreshFn, RowifyFn, StringifyFn, TableView, TableViewState,
+    },
     tui::{EventCaptured, ManagerAction, View, ViewBuilder, ViewManager},
 };
 
@@ -21,20 +24,30 @@ const SHORTCUT_VIEW_ID: u16 = 0;
 const SEARCH_TEXT_VIEW_1: u16 = 1;
 const LIST_INDICATOR_VIEW: u16 = 2;
 
+const LIST_INDICATOR_WIDTH: u16 = 16;
+
 pub struct ShortcutViewContainer {}
 
 impl ShortcutViewContainer {
+    #[allow(clippy::too_many_arguments)]
     pub fn builder(
         vm: Rc<ViewManager>,
         column_names: Vec<String>,
         column_constraints: Vec<Constraint>,
+        optional_columns: usize,
+        fit_column: Option<(usize, ColumnWidthFn<Shortcut>)>,
         list_fn: Box<ListFunction<Shortcut>>,
+        count_fn: Box<CountFunction>,
         rowify: RowifyFn<Shortcut>,
-        stringify: fn(&Shortcut) -> String,
+        stringify: StringifyFn<Shortcut>,
         config: Arc<Config>,
         view_state: Arc<Mutex<TableViewState>>,
-        delete_fn: DeleteFn<Shortcut>,
+        action_fn: ActionFn<Shortcut>,
         editor_modal_view_builder: Option<EditorViewBuilder<Shortcut>>,
+        details_fn: DetailsFn<Shortcut>,
+        exact_choice_fn: Option<ExactChoiceFn>,
+        last_choice_fn: Option<LastChoiceFn>,
+        refresh_fn: Option<RefreshFn>,
         search_text_state: Arc<Mutex<SearchTextState>>,
     ) -> ViewBuilder {
         ViewBuilder::from(Box::new(Self {}))
@@ -45,19 +58,41 @@ impl ShortcutViewContainer {
                     "shortcut".to_string(),
                     column_names,
                     column_constraints,
+                    optional_columns,
+                    fit_column,
                     list_fn,
+                    count_fn,
+                    Some(config.shortcut_sort),
+                    None,
+                    config.show_more_hint,
                     rowify,
                     stringify,
                     config.clone(),
                     view_state,
-                    delete_fn,
+                    action_fn,
                     editor_modal_view_builder,
+                    details_fn,
+                    exact_choice_fn,
+                    last_choice_fn,
+                    refresh_fn,
+                    Some(
+                        search_text_state
+                            .lock()
+                            .unwrap()
+                            .search_string()
+                            .to_string(),
+                    )
+                    .filter(|search_string| !search_string.is_empty()),
                 )
                 .with_publish_events(true),
             )
             .child(
                 SEARCH_TEXT_VIEW_1,
-                SearchTextView::builder(config.clone(), search_text_state.clone()),
+                SearchTextView::builder(
+                    config.clone(),
+                    search_text_state.clone(),
+                    "shortcut".to_string(),
+                ),
             )
             .child(
                 LIST_INDICATOR_VIEW,
@@ -73,8 +108,15 @@ impl View for ShortcutViewContainer {
         let vertical = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).spacing(0);
         let [main, bottom] = vertical.areas(area);
 
+        // on narrow terminals, the room is left to the search text
+        let indicator_width = if bottom.width < LIST_INDICATOR_WIDTH * 2 {
+            0
+        } else {
+            LIST_INDICATOR_WIDTH
+        };
         let horizontal =
-            Layout::horizontal([Constraint::Fill(1), Constraint::Length(14)]).spacing(0);
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(indicator_width)])
+                .spacing(0);
         let [search_text_area, right] = horizontal.areas(bottom);
 
         vec![
@@ -1,41 +1,116 @@
 use std::{
+    borrow::Cow,
     fmt, fs,
     rc::Rc,
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use log::{debug, error, info, trace};
+use log::{debug, error, info, trace, warn};
 use nucleo_matcher::{
     Config, Matcher, Utf32Str,
     pattern::{CaseMatching, Normalization, Pattern},
 };
-use rusqlite::{Connection, Result, params};
+use rusqlite::{Connection, OptionalExtension, Result, params};
+
+use crate::{
+    config::ShortcutSort,
+    path_syntax::{WINDOWS, recorded_form},
+    unicode::normalize_unicode,
+};
 
 // Update this when the database schema changes with the max value value of the sql
 // files in ../dbschema (e.g. if 1.sql is the latest, this should be 1)
-const CURRENT_SCHEMA_VERSION: i64 = 2;
+const CURRENT_SCHEMA_VERSION: i64 = 11;
+
+// The meta key holding the recent searches (one per line, most recent first)
+const RECENT_SEARCHES_KEY: &str = "recent_searches";
+
+// The meta key holding the name of the view the GUI was last quit from
+const LAST_VIEW_KEY: &str = "last_view";
+
+// The meta key holding the path chosen the last time the GUI exited with one
+const LAST_PATH_KEY: &str = "last_path";
+
+// The prefix of the meta keys holding the time when a path was entered, until it is left
+const PENDING_ENTER_KEY_PREFIX: &str = "pending_enter:";
+
+// The prefix of the meta keys holding the display toggles of the GUI, when remembered
+const TOGGLE_KEY_PREFIX: &str = "toggle:";
+
+// The maximum duration of a visit: a path left open longer (e.g. a terminal forgotten over the
+// night) is only credited with this duration
+const MAX_DWELL_SECONDS: u64 = 4 * 3600;
+
+// Matches the paths starting with the pattern of `prefix_pattern`, bound as is (not built with
+// `||`) so that SQLite can use the index on the paths
+const PREFIX_CLAUSE: &str = "path LIKE (?1) ESCAPE '\\'";
+
+// The prefix of the search terms filtering the paths by the command which was running
+const VIA_FILTER_PREFIX: &str = "via:";
+
+// The greatest LIMIT and OFFSET of the queries, SQLite integers being signed
+const MAX_PAGE_BOUND: usize = i64::MAX as usize;
+
+// The current time in the queries
+macro_rules! sql_now {
+    () => {
+        "CAST(strftime('%s','now') AS INTEGER)"
+    };
+}
+
+// The frecency score of a path at a time: its visits, weighted by the age of the last one
+macro_rules! frecency_score {
+    ($now:expr) => {
+        concat!(
+            "visits * CASE ",
+            "WHEN ",
+            $now,
+            " - date < 3600 THEN 4.0 ",
+            "WHEN ",
+            $now,
+            " - date < 86400 THEN 2.0 ",
+            "WHEN ",
+            $now,
+            " - date < 604800 THEN 0.5 ",
+            "ELSE 0.25 END"
+        )
+    };
+}
+
+// The order of the paths by the score stored (see `Store::reage_scores`)
+const STORED_FRECENCY_ORDER: &str = "score desc, date desc, id desc";
 
 /// Represents a path entry in the database
 /// id: auto increment primary key
 /// path: the file path
 /// date: the timestamp when the path was added (in seconds since EPOCH)
+/// visits: the number of times the path was added
+/// created: the timestamp when the path was added for the first time (in seconds since EPOCH)
+/// hidden: whether the path is hidden (archived) from the lists
+/// via: the command which was running when the path was visited, if given
 #[derive(Debug, Clone)]
 pub(crate) struct Path {
     pub(crate) id: i64,
     pub(crate) date: i64,
     pub(crate) path: String,
+    pub(crate) visits: i64,
+    pub(crate) created: i64,
+    pub(crate) hidden: bool,
+    pub(crate) via: Option<String>,
 }
 
 /// Represents a shortcut entry in the database
 /// id: auto increment primary key
 /// name: the name of the shortcut
 /// path: the file path associated with the shortcut
+/// created: the timestamp when the shortcut was created (in seconds since EPOCH), 0 if unknown
 #[derive(Debug, Clone)]
 pub(crate) struct Shortcut {
     pub(crate) id: i64,
     pub(crate) name: String,
     pub(crate) path: String,
     pub(crate) description: Option<String>,
+    pub(crate) created: i64,
 }
 
 impl fmt::Display for Shortcut {
@@ -48,11 +123,281 @@ impl fmt::Display for Shortcut {
     }
 }
 
+/// An entry of the database, identified by a key which stays the same when it is modified
+pub(crate) trait Identified {
+    type Key: PartialEq;
+
+    fn key(&self) -> Self::Key;
+}
+
+impl Identified for Path {
+    type Key = String;
+
+    // a visit inserts the path again, with another id
+    fn key(&self) -> String { self.path.clone() }
+}
+
+impl Identified for Shortcut {
+    type Key = i64;
+
+    // the name and the path of a shortcut are edited
+    fn key(&self) -> i64 { self.id }
+}
+
+/// An order of the entries of a list, switched with ctrl+s
+pub(crate) trait ListOrder: Copy + std::fmt::Debug + Default + PartialEq + 'static {
+    /// Returns the order following this one, cycling back to the first one after the last.
+    fn next(self) -> Self;
+
+    /// Returns the name of the order, as displayed.
+    fn name(self) -> &'static str;
+}
+
+/// An entry of the database listed in its own orders
+pub(crate) trait Ordered {
+    type Order: ListOrder;
+}
+
+impl Ordered for Path {
+    type Order = SortMode;
+}
+
+impl Ordered for Shortcut {
+    type Order = ShortcutSort;
+}
+
+/// The order of the paths listed from the history
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) enum SortMode {
+    /// The most recent first
+    #[default]
+    Recency,
+    /// The most visited first
+    Frequency,
+    /// The most visited first, the visits being weighted by the age of the last one
+    Frecency,
+    /// By path
+    Alphabetical,
+    /// The most time spent in first (see `Store::record_enter`)
+    Dwell,
+}
+
+impl ListOrder for SortMode {
+    fn next(self) -> SortMode {
+        match self {
+            SortMode::Recency => SortMode::Frequency,
+            SortMode::Frequency => SortMode::Frecency,
+            SortMode::Frecency => SortMode::Alphabetical,
+            SortMode::Alphabetical => SortMode::Dwell,
+            SortMode::Dwell => SortMode::Recency,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SortMode::Recency => "recency",
+            SortMode::Frequency => "frequency",
+            SortMode::Frecency => "frecency",
+            SortMode::Alphabetical => "alphabetical",
+            SortMode::Dwell => "time spent",
+        }
+    }
+}
+
+impl SortMode {
+    /// Returns the order of the paths.
+    fn order_by(self) -> &'static str {
+        match self {
+            SortMode::Recency => "date desc, id desc",
+            SortMode::Frequency => "visits desc, date desc, id desc",
+            SortMode::Frecency => concat!(frecency_score!(sql_now!()), " desc, date desc, id desc"),
+            SortMode::Alphabetical => "path asc, id desc",
+            SortMode::Dwell => "dwell_seconds desc, date desc, id desc",
+        }
+    }
+}
+
+/// Returns the order of the shortcuts. The shortcuts created before their creation date was
+/// recorded are the last ones by recency.
+fn shortcut_order_by(sort: ShortcutSort) -> &'static str {
+    match sort {
+        ShortcutSort::Name => "name asc, id desc",
+        ShortcutSort::Path => "path asc, name asc, id desc",
+        ShortcutSort::Recent => "created desc, id desc",
+    }
+}
+
+/// A term of the exact search text
+#[derive(Debug, PartialEq)]
+struct SearchTerm {
+    /// The text to find, matched literally
+    text: String,
+    /// Whether the text must not be found
+    negated: bool,
+}
+
+/// Returns the page of a list to read, as the position of its first entry and its number of
+/// entries: at least one entry is read, and no more than SQLite can count.
+///
+/// ### Returns
+/// The position and the number of entries, or `None` if the position is beyond any list, i.e.
+/// the page is empty without querying the database.
+fn clamp_page(pos: usize, len: usize) -> Option<(usize, usize)> {
+    if pos >= MAX_PAGE_BOUND {
+        debug!("page at pos={} beyond any list", pos);
+        return None;
+    }
+    if len == 0 {
+        debug!("page of no entry at pos={}, one is read", pos);
+    }
+    Some((pos, len.clamp(1, MAX_PAGE_BOUND)))
+}
+
+/// Returns the LIKE pattern matching the texts starting with a prefix, the wildcards of the
+/// prefix being escaped.
+fn prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Splits the exact search text into terms.
+/// The terms are separated by spaces, except inside a double-quoted phrase which is a single
+/// term, spaces included. A quote may be adjacent to other characters (`my"dir name"` is the
+/// term `mydir name`), and `\"` is a literal quote. A quote without closing one starts a phrase
+/// up to the end of the text, so that the phrase being typed is already matched.
+/// A term starting with an unquoted '-' is negated.
+fn tokenize_search_text(text: &str) -> Vec<SearchTerm> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut terms = vec![];
+    let mut current = String::new();
+    let mut started = false;
+    let mut negated = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && chars.get(i + 1) == Some(&'"') {
+            current.push('"');
+            started = true;
+            i += 2;
+        } else if c == '"' {
+            started = true;
+            match find_closing_quote(&chars, i + 1) {
+                Some(end) => {
+                    current.push_str(&unescape_quotes(&chars[i + 1..end]));
+                    i = end + 1;
+                }
+                None => {
+                    current.push_str(&unescape_quotes(&chars[i + 1..]));
+                    i = chars.len();
+                }
+            }
+        } else if c.is_whitespace() {
+            if !current.is_empty() {
+                terms.push(SearchTerm {
+                    text: std::mem::take(&mut current),
+                    negated,
+                });
+            }
+            started = false;
+            negated = false;
+            i += 1;
+        } else {
+            if c == '-' && !started {
+                negated = true;
+            } else {
+                current.push(c);
+            }
+            started = true;
+            i += 1;
+        }
+    }
+    if !current.is_empty() {
+        terms.push(SearchTerm {
+            text: current,
+            negated,
+        });
+    }
+    terms
+}
+
+/// Returns the terms of the exact search text found in the matching paths, to highlight them:
+/// the negated terms and the `via:<command>` filters are left out.
+pub(crate) fn highlighted_terms(text: &str) -> Vec<String> {
+    tokenize_search_text(text)
+        .into_iter()
+        .filter(|term| {
+            !term.negated
+                && term
+                    .text
+                    .strip_prefix(VIA_FILTER_PREFIX)
+                    .is_none_or(str::is_empty)
+        })
+        .map(|term| term.text)
+        .collect()
+}
+
+/// Returns the position of the first unescaped quote from `start`, if any.
+fn find_closing_quote(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if chars.get(i + 1) == Some(&'"') => i += 2,
+            '"' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn unescape_quotes(chars: &[char]) -> String {
+    chars.iter().collect::<String>().replace("\\\"", "\"")
+}
+
+/// An error raised when a store can't be created
+#[derive(Debug)]
+pub(crate) enum StoreError {
+    /// The database could not be opened
+    Open(rusqlite::Error),
+    /// The schema could not be created in the database
+    Schema(rusqlite::Error),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Open(e) => write!(f, "failed to open the database: {}", e),
+            StoreError::Schema(e) => write!(f, "failed to create the database schema: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StoreError::Open(e) | StoreError::Schema(e) => Some(e),
+        }
+    }
+}
+
 /// Store struct to manage database connection and operations
 /// db_conn: the SQLite database connection
+/// revisit_window: the number of seconds within which a path recorded again is not a new visit
+/// normalize_unicode: whether the paths and the search texts are normalized to NFC
+/// lenient_shortcut_lookup: whether the shortcut names typed are trimmed and case-insensitive
 #[derive(Debug)]
 pub(crate) struct Store {
     db_conn: Rc<Connection>,
+    revisit_window: u64,
+    stored_frecency: bool,
+    normalize_unicode: bool,
+    lenient_shortcut_lookup: bool,
 }
 
 impl Store {
@@ -87,6 +432,10 @@ impl Store {
                     panic!("Failed to open connection to database")
                 }
             },
+            revisit_window: 0,
+            stored_frecency: false,
+            normalize_unicode: false,
+            lenient_shortcut_lookup: false,
         };
 
         if !db_exists {
@@ -98,6 +447,105 @@ impl Store {
         store
     }
 
+    /// Creates a store in an in-memory database, with the current schema.
+    /// Nothing is persisted: the data is lost when the store is dropped.
+    ///
+    /// ### Returns
+    /// The store, or the error raised when opening the database or creating the schema.
+    pub(crate) fn in_memory() -> Result<Store, StoreError> {
+        let db_conn = Connection::open_in_memory().map_err(StoreError::Open)?;
+        db_conn
+            .execute_batch(include_str!("../dbschema/current.sql"))
+            .and_then(|_| {
+                db_conn.execute(
+                    "INSERT INTO version (version) VALUES (?1)",
+                    params![CURRENT_SCHEMA_VERSION],
+                )
+            })
+            .map_err(|e| {
+                error!("Failed to create the in-memory database: {}", e);
+                StoreError::Schema(e)
+            })?;
+        Ok(Store {
+            db_conn: Rc::new(db_conn),
+            revisit_window: 0,
+            stored_frecency: false,
+            normalize_unicode: false,
+            lenient_shortcut_lookup: false,
+        })
+    }
+
+    /// Sets the window within which a path recorded again, while it is still the most recent
+    /// one, only has its date updated instead of counting a new visit (e.g. a `cd .`, or the
+    /// prompt hooks of several tools recording the same directory).
+    ///
+    /// ### Parameters
+    /// seconds: the length of the window, 0 counts every recording as a visit
+    ///
+    /// ### Returns
+    /// The store, with the window set.
+    pub(crate) fn with_revisit_window(mut self, seconds: u64) -> Store {
+        self.revisit_window = seconds;
+        self
+    }
+
+    /// Sorts the paths by frecency with the score stored with each path, using an index,
+    /// instead of computing it for each query. The scores are aged by `reage_scores`.
+    ///
+    /// ### Parameters
+    /// stored_frecency: whether the stored score is used
+    ///
+    /// ### Returns
+    /// The store, with the frecency strategy set.
+    pub(crate) fn with_stored_frecency(mut self, stored_frecency: bool) -> Store {
+        self.stored_frecency = stored_frecency;
+        self
+    }
+
+    /// Normalizes the paths recorded and the search texts to their composed form (NFC), so that
+    /// the two spellings of an accented letter, e.g. the decomposed names of macOS, are the
+    /// same path and match each other. The paths already stored are not changed.
+    ///
+    /// ### Parameters
+    /// normalize_unicode: whether the paths and the search texts are normalized
+    ///
+    /// ### Returns
+    /// The store, with the normalization set.
+    pub(crate) fn with_normalize_unicode(mut self, normalize_unicode: bool) -> Store {
+        self.normalize_unicode = normalize_unicode;
+        self
+    }
+
+    /// Resolves the shortcut names typed by the user (see `resolve_shortcut`) without their
+    /// surrounding spaces and regardless of the case, e.g. `Work ` for `work`.
+    ///
+    /// ### Parameters
+    /// lenient_shortcut_lookup: whether the names are trimmed and case-insensitive
+    ///
+    /// ### Returns
+    /// The store, with the lookup set.
+    pub(crate) fn with_lenient_shortcut_lookup(mut self, lenient_shortcut_lookup: bool) -> Store {
+        self.lenient_shortcut_lookup = lenient_shortcut_lookup;
+        self
+    }
+
+    /// Returns a path or a search text in the form of the stored paths (see
+    /// `with_normalize_unicode`).
+    fn normalized<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.normalize_unicode {
+            Cow::Owned(normalize_unicode(text))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// Returns a recorded path in the form of the stored paths: normalized (see
+    /// `with_normalize_unicode`), and on Windows with `\` separators and an upper case drive
+    /// letter.
+    fn recorded_path(&self, path: &str) -> String {
+        recorded_form(&self.normalized(path.trim()), WINDOWS)
+    }
+
     fn set_schema_version(&self, version: i64) {
         match self.db_conn.execute("DELETE FROM version", params![]) {
             Ok(_) => {}
@@ -149,6 +597,15 @@ impl Store {
         let u = [
             include_str!("../dbschema/1.sql"),
             include_str!("../dbschema/2.sql"),
+            include_str!("../dbschema/3.sql"),
+            include_str!("../dbschema/4.sql"),
+            include_str!("../dbschema/5.sql"),
+            include_str!("../dbschema/6.sql"),
+            include_str!("../dbschema/7.sql"),
+            include_str!("../dbschema/8.sql"),
+            include_str!("../dbschema/9.sql"),
+            include_str!("../dbschema/10.sql"),
+            include_str!("../dbschema/11.sql"),
             // add other upgrade scripts here
         ];
 
@@ -167,6 +624,21 @@ impl Store {
         self.set_schema_version(CURRENT_SCHEMA_VERSION);
     }
 
+    /// Returns the version of the database schema.
+    pub(crate) fn schema_version(&self) -> i64 { self.find_schema_version() }
+
+    /// Returns the size of the database, i.e. its number of pages by the size of a page.
+    ///
+    /// ### Returns
+    /// The size in bytes, or the error raised by the query.
+    pub(crate) fn database_size(&self) -> Result<u64, rusqlite::Error> {
+        self.db_conn.query_row(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+            [],
+            |row| row.get(0),
+        )
+    }
+
     fn find_schema_version(&self) -> i64 {
         let version: i64;
 
@@ -205,7 +677,126 @@ impl Store {
         version
     }
 
-    /// Adds a new path to the database with the current timestamp.
+    /// Returns the value associated with a key in the meta table.
+    ///
+    /// ### Parameters
+    /// key: the key to look for
+    ///
+    /// ### Returns
+    /// The value if the key exists, None otherwise, or an error.
+    pub(crate) fn get_meta(&self, key: &str) -> Result<Option<String>, rusqlite::Error> {
+        debug!("get_meta key={}", key);
+        let mut stmt = self
+            .db_conn
+            .prepare("SELECT value FROM meta WHERE key=(?1)")?;
+        let mut rows = stmt.query_map([key], |row| row.get::<_, String>(0))?;
+        rows.next().transpose().map_err(|e| {
+            error!("Failed to read meta key '{}': {}", key, e);
+            e
+        })
+    }
+
+    /// Sets the value associated with a key in the meta table.
+    /// If the key already exists, its value is replaced.
+    ///
+    /// ### Parameters
+    /// key: the key to set
+    /// value: the value to associate with the key
+    ///
+    /// ### Returns
+    /// Ok(()) if the operation was successful, otherwise an error
+    pub(crate) fn set_meta(&self, key: &str, value: &str) -> Result<(), rusqlite::Error> {
+        debug!("set_meta key={}", key);
+        self.db_conn
+            .execute(
+                "INSERT OR REPLACE INTO meta (key, value) VALUES ((?1),(?2))",
+                (key, value),
+            )
+            .map_err(|e| {
+                error!("Failed to write meta key '{}': {}", key, e);
+                e
+            })
+            .map(|_l| ())
+    }
+
+    /// Lists the recent searches, the most recent first.
+    ///
+    /// ### Returns
+    /// A vector of search strings if the operation was successful, otherwise an error.
+    pub(crate) fn list_recent_searches(&self) -> Result<Vec<String>, rusqlite::Error> {
+        Ok(self
+            .get_meta(RECENT_SEARCHES_KEY)?
+            .map(|value| value.lines().map(String::from).collect())
+            .unwrap_or_default())
+    }
+
+    /// Records a search in the recent searches.
+    /// Empty searches are ignored, and a search identical to the most recent one is not
+    /// recorded twice. Only the `max` most recent searches are kept.
+    ///
+    /// ### Parameters
+    /// search: the search string
+    /// max: the maximum number of searches to keep
+    ///
+    /// ### Returns
+    /// Ok(()) if the operation was successful, otherwise an error
+    pub(crate) fn add_recent_search(
+        &self,
+        search: &str,
+        max: usize,
+    ) -> Result<(), rusqlite::Error> {
+        debug!("add_recent_search search={} max={}", search, max);
+        // A search is stored on a single line
+        let search = search.lines().next().unwrap_or_default();
+        if search.trim().is_empty() {
+            return Ok(());
+        }
+        let mut searches = self.list_recent_searches()?;
+        if searches.first().map(String::as_str) != Some(search) {
+            searches.insert(0, search.to_string());
+        }
+        searches.truncate(max);
+        self.set_meta(RECENT_SEARCHES_KEY, &searches.join("\n"))
+    }
+
+    /// Returns the name of the view the GUI was last quit from, if recorded.
+    pub(crate) fn last_view(&self) -> Result<Option<String>, rusqlite::Error> {
+        self.get_meta(LAST_VIEW_KEY)
+    }
+
+    /// Records the name of the view the GUI is quit from, to show it at the next launch.
+    pub(crate) fn set_last_view(&self, name: &str) -> Result<(), rusqlite::Error> {
+        self.set_meta(LAST_VIEW_KEY, name)
+    }
+
+    /// Returns the value of a toggle of the GUI the last time it was quit, if recorded.
+    pub(crate) fn toggle(&self, name: &str) -> Result<Option<bool>, rusqlite::Error> {
+        Ok(self
+            .get_meta(&format!("{}{}", TOGGLE_KEY_PREFIX, name))?
+            .map(|value| value == "true"))
+    }
+
+    /// Records the value of a toggle of the GUI when it is quit, to restore it at the next
+    /// launch.
+    pub(crate) fn set_toggle(&self, name: &str, value: bool) -> Result<(), rusqlite::Error> {
+        self.set_meta(
+            &format!("{}{}", TOGGLE_KEY_PREFIX, name),
+            &value.to_string(),
+        )
+    }
+
+    /// Returns the path chosen the last time the GUI exited with one, if recorded.
+    pub(crate) fn last_path(&self) -> Result<Option<String>, rusqlite::Error> {
+        self.get_meta(LAST_PATH_KEY)
+    }
+
+    /// Records the path chosen when the GUI exits, e.g. to go back there (see `cdir --last`).
+    pub(crate) fn set_last_path(&self, path: &str) -> Result<(), rusqlite::Error> {
+        self.set_meta(LAST_PATH_KEY, path)
+    }
+
+    /// Adds a new path to the database with the current timestamp, without context (see
+    /// `add_path_with_context`).
     /// If the path already exists, it is updated with the new timestamp.
     //
     /// ### Parameters
@@ -213,19 +804,42 @@ impl Store {
     ///
     /// ### Returns
     /// Ok(()) if the operation was successful, otherwise an error
+    #[cfg(test)]
     pub(crate) fn add_path(&self, path: &str) -> Result<(), rusqlite::Error> {
-        debug!("add_path path={}", path);
-        self.add_path_with_time(
+        self.add_path_with_context(path, None)
+    }
+
+    /// Adds a new path to the database with the current timestamp, and the command which was
+    /// running when it was visited (e.g. `git`), if any.
+    /// If the path already exists, it is updated with the new timestamp; its command is kept
+    /// when none is given.
+    ///
+    /// ### Parameters
+    /// path: the file path to add
+    /// via: the command which was running, if any
+    ///
+    /// ### Returns
+    /// Ok(()) if the operation was successful, otherwise an error
+    pub(crate) fn add_path_with_context(
+        &self,
+        path: &str,
+        via: Option<&str>,
+    ) -> Result<(), rusqlite::Error> {
+        debug!("add_path path={} via={:?}", path, via);
+        self.add_path_with_time_and_context(
             path,
             SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs(),
+            via,
         )
     }
 
     /// Adds a new path to the database with a specified timestamp.
-    /// If the path already exists, it is updated with the new timestamp.
+    /// If the path already exists, it is updated with the new timestamp and its number of
+    /// visits is incremented; the timestamp of its creation and its hidden flag are kept.
+    /// The path is trimmed, and skipped if empty.
     ///
     /// ### Parameters
     /// path: the file path to add
@@ -234,7 +848,57 @@ impl Store {
     /// ### Returns
     /// Ok(()) if the operation was successful, otherwise an error
     pub(crate) fn add_path_with_time(&self, path: &str, epoc: u64) -> Result<(), rusqlite::Error> {
-        debug!("add_path_with_time path={} epoch={}", path, epoc);
+        self.add_path_with_time_and_context(path, epoc, None)
+    }
+
+    /// Adds a new path to the database with a specified timestamp and the command which was
+    /// running, if any (see `add_path_with_time` and `add_path_with_context`).
+    pub(crate) fn add_path_with_time_and_context(
+        &self,
+        path: &str,
+        epoc: u64,
+        via: Option<&str>,
+    ) -> Result<(), rusqlite::Error> {
+        debug!(
+            "add_path_with_time path={} epoch={} via={:?}",
+            path, epoc, via
+        );
+        let path = self.recorded_path(path);
+        let path = path.as_str();
+        if path.is_empty() {
+            info!("Skipping an empty path");
+            return Ok(());
+        }
+        let via = via.map(str::trim).filter(|via| !via.is_empty());
+        if self.revisit_window > 0 && self.touch_recent_path(path, epoc, via)? {
+            return self.update_score(path);
+        }
+        let (visits, created, dwell_seconds, hidden, previous_via): (
+            i64,
+            Option<i64>,
+            i64,
+            i64,
+            Option<String>,
+        ) = self
+            .db_conn
+            .query_row(
+                "SELECT COALESCE(MAX(visits), 0), MIN(created), COALESCE(MAX(dwell_seconds), 0), \
+                 COALESCE(MAX(hidden), 0), MAX(via) FROM paths WHERE path=(?1)",
+                [path],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .map_err(|e| {
+                error!("Failed to count the visits of path '{}': {}", path, e);
+                e
+            })?;
         {
             let mut stmt = self.db_conn.prepare("DELETE FROM paths WHERE path=(?1)")?;
             if let Err(err) = stmt.execute([path]) {
@@ -243,76 +907,472 @@ impl Store {
             }
         }
         {
-            let mut stmt = self
-                .db_conn
-                .prepare("INSERT INTO paths (path, date) VALUES ((?1),(?2))")?;
-            stmt.execute([path, &format!("{}", epoc)])
-                .map_err(|e| {
-                    error!("Failed to insert path '{}' time' {}: {}", path, epoc, e);
-                    e
-                })
-                .map(|_l| ())
+            let mut stmt = self.db_conn.prepare(
+                "INSERT INTO paths (path, date, visits, created, dwell_seconds, hidden, via) \
+                 VALUES ((?1),(?2),(?3),(?4),(?5),(?6),(?7))",
+            )?;
+            stmt.execute(params![
+                path,
+                epoc as i64,
+                visits + 1,
+                created.unwrap_or(epoc as i64),
+                dwell_seconds,
+                hidden,
+                via.or(previous_via.as_deref()),
+            ])
+            .map_err(|e| {
+                error!("Failed to insert path '{}' time' {}: {}", path, epoc, e);
+                e
+            })?;
         }
+        self.update_score(path)
     }
 
-    /// Deletes a path from the database by its ID.
+    /// Computes the score of a path, now that it was visited, if the scores are stored (see
+    /// `with_stored_frecency`).
+    fn update_score(&self, path: &str) -> Result<(), rusqlite::Error> {
+        if !self.stored_frecency {
+            // the scores are computed by the queries
+            return Ok(());
+        }
+        self.db_conn
+            .execute(
+                concat!(
+                    "UPDATE paths SET score = ",
+                    frecency_score!(sql_now!()),
+                    " WHERE path = (?1)"
+                ),
+                [path],
+            )
+            .map(|_| ())
+            .map_err(|e| {
+                error!("Failed to update the score of path '{}': {}", path, e);
+                e
+            })
+    }
+
+    /// Ages the scores of all the paths, as they are at a given time: the weight of the visits
+    /// decreases as the last one gets older (see `SortMode::Frecency`). The stored scores are
+    /// up to date when a path is visited, the other ones must be aged from time to time.
     ///
     /// ### Parameters
-    /// id: the ID of the path to delete
+    /// now: the time to compute the scores at (in seconds since EPOCH)
     ///
     /// ### Returns
-    /// Ok(()) if the operation was successful, otherwise an error
-    pub(crate) fn delete_path_by_id(&self, id: i64) -> Result<(), rusqlite::Error> {
-        let mut stmt = self.db_conn.prepare("DELETE FROM paths WHERE id=(?1)")?;
-        stmt.execute([id])
+    /// The number of paths whose score was computed, otherwise an error.
+    pub(crate) fn reage_scores(&self, now: u64) -> Result<usize, rusqlite::Error> {
+        debug!("reage_scores now={}", now);
+        self.db_conn
+            .execute(
+                concat!("UPDATE paths SET score = ", frecency_score!("(?1)")),
+                [now as i64],
+            )
             .map_err(|e| {
-                error!("Failed to delete path by id '{}',{}", id, e);
+                error!("Failed to age the scores: {}", e);
                 e
             })
-            .map(|_l: usize| ())
     }
 
-    /// Lists paths from the database with pagination and optional filtering.
-    /// The results are ordered by date (descending) and ID (descending).
-    /// If `like_text` is provided, only paths containing the text are returned.
+    /// Updates the date of a path if it is the most recent one and was recorded within the
+    /// revisit window, without counting a new visit.
     ///
     /// ### Parameters
-    /// pos: the starting position (offset) for pagination
-    /// len: the number of paths to return
-    /// like_text: optional text to filter paths (if empty, no filtering is applied)
+    /// path: the path recorded
+    /// epoc: the time of the recording (in seconds since EPOCH)
+    /// via: the command under which the path was recorded, if any
     ///
     /// ### Returns
-    /// A vector of Path entries if the operation was successful, otherwise an error.
-    pub(crate) fn list_paths(
+    /// true if the path was updated, false if it must be recorded as a new visit
+    fn touch_recent_path(
         &self,
-        pos: usize,
-        len: usize,
-        like_text: &str,
-        fuzzy: bool,
-    ) -> Result<Vec<Path>, rusqlite::Error> {
-        debug!(
-            "list_paths pos={} len={} like_text={} fuzzy={}",
-            pos, len, like_text, fuzzy
-        );
-        if like_text.is_empty() || !fuzzy {
-            self.list_path_exact(pos, len, like_text)
-        } else {
-            self.list_path_fuzzy(pos, len, like_text)
-        }
-    }
+        path: &str,
+        epoc: u64,
+        via: Option<&str>,
+    ) -> Result<bool, rusqlite::Error> {
+        self.db_conn
+            .execute(
+                "UPDATE paths SET date = MAX(date, (?2)), via = COALESCE((?3), via) \
+                 WHERE path = (?1) AND (?2) - date BETWEEN 0 AND (?4) \
+                 AND id = (SELECT id FROM paths ORDER BY date DESC, id DESC LIMIT 1)",
+                params![path, epoc as i64, via, self.revisit_window as i64],
+            )
+            .map(|updated| updated > 0)
+            .map_err(|e| {
+                error!("Failed to update the date of path '{}': {}", path, e);
+                e
+            })
+    }
+
+    /// Records that a path was entered, until `record_leave` is called for it.
+    /// A previous enter of the path which was not left (e.g. the shell was killed) is discarded.
+    ///
+    /// ### Parameters
+    /// path: the path entered
+    /// epoc: the time when the path was entered (in seconds since EPOCH)
+    ///
+    /// ### Returns
+    /// Ok(()) if the operation was successful, otherwise an error
+    pub(crate) fn record_enter(&self, path: &str, epoc: u64) -> Result<(), rusqlite::Error> {
+        debug!("record_enter path={} epoch={}", path, epoc);
+        self.set_meta(
+            &format!("{}{}", PENDING_ENTER_KEY_PREFIX, self.recorded_path(path)),
+            &epoc.to_string(),
+        )
+    }
+
+    /// Records that a path was left: the time spent since it was entered is added to its
+    /// cumulative time, capped to a few hours. Nothing is recorded if the path was not entered.
+    ///
+    /// ### Parameters
+    /// path: the path left
+    /// epoc: the time when the path was left (in seconds since EPOCH)
+    ///
+    /// ### Returns
+    /// Ok(()) if the operation was successful, otherwise an error
+    pub(crate) fn record_leave(&self, path: &str, epoc: u64) -> Result<(), rusqlite::Error> {
+        debug!("record_leave path={} epoch={}", path, epoc);
+        let path = self.recorded_path(path);
+        let path = path.as_str();
+        let key = format!("{}{}", PENDING_ENTER_KEY_PREFIX, path);
+        let Some(entered) = self.get_meta(&key)? else {
+            info!("Path '{}' left without being entered", path);
+            return Ok(());
+        };
+        self.db_conn
+            .execute("DELETE FROM meta WHERE key=(?1)", [&key])?;
+        let Ok(entered) = entered.parse::<u64>() else {
+            warn!("Invalid enter time '{}' for path '{}'", entered, path);
+            return Ok(());
+        };
+        let dwell = epoc.saturating_sub(entered).min(MAX_DWELL_SECONDS);
+        self.db_conn
+            .execute(
+                "UPDATE paths SET dwell_seconds = dwell_seconds + (?1) WHERE path=(?2)",
+                [&dwell.to_string(), path],
+            )
+            .map_err(|e| {
+                error!("Failed to record the time spent in '{}': {}", path, e);
+                e
+            })
+            .map(|_| ())
+    }
+
+    /// Hides a path from the lists, or shows it again.
+    ///
+    /// ### Parameters
+    /// id: the ID of the path
+    /// hidden: whether the path is hidden
+    ///
+    /// ### Returns
+    /// Ok(()) if the operation was successful, otherwise an error
+    pub(crate) fn set_path_hidden(&self, id: i64, hidden: bool) -> Result<(), rusqlite::Error> {
+        debug!("set_path_hidden id={} hidden={}", id, hidden);
+        self.db_conn
+            .execute(
+                "UPDATE paths SET hidden=(?1) WHERE id=(?2)",
+                [hidden as i64, id],
+            )
+            .map_err(|e| {
+                error!("Failed to set the hidden flag of path '{}': {}", id, e);
+                e
+            })
+            .map(|_| ())
+    }
+
+    /// Deletes a path from the database by its ID.
+    ///
+    /// ### Parameters
+    /// id: the ID of the path to delete
+    ///
+    /// ### Returns
+    /// Ok(()) if the operation was successful, otherwise an error
+    pub(crate) fn delete_path_by_id(&self, id: i64) -> Result<(), rusqlite::Error> {
+        let mut stmt = self.db_conn.prepare("DELETE FROM paths WHERE id=(?1)")?;
+        stmt.execute([id])
+            .map_err(|e| {
+                error!("Failed to delete path by id '{}',{}", id, e);
+                e
+            })
+            .map(|_l: usize| ())
+    }
+
+    /// Removes the paths which no longer exist on the file system.
+    ///
+    /// ### Parameters
+    /// dry_run: if true, the paths are not removed
+    ///
+    /// ### Returns
+    /// The paths removed (or which would be removed) if the operation was successful,
+    /// otherwise an error.
+    pub(crate) fn prune_paths(&self, dry_run: bool) -> Result<Vec<Path>, rusqlite::Error> {
+        debug!("prune_paths dry_run={}", dry_run);
+        let mut missing = vec![];
+        self.for_each_path("", true, |path| {
+            if !std::path::Path::new(&path.path).exists() {
+                missing.push(path);
+            }
+        })?;
+        if !dry_run && !missing.is_empty() {
+            // all the paths are removed, or none
+            let transaction = self.db_conn.unchecked_transaction()?;
+            for path in missing.iter() {
+                self.delete_path_by_id(path.id)?;
+            }
+            transaction.commit()?;
+        }
+        Ok(missing)
+    }
+
+    /// Merges the entries of a same path, e.g. imported several times: the most recent one is
+    /// kept, with the visits and the time spent of all of them, and the others are removed.
+    ///
+    /// ### Parameters
+    /// dry_run: if true, the paths are not merged
+    ///
+    /// ### Returns
+    /// The entries removed (or which would be removed) if the operation was successful,
+    /// otherwise an error.
+    pub(crate) fn dedup_paths(&self, dry_run: bool) -> Result<Vec<Path>, rusqlite::Error> {
+        debug!("dedup_paths dry_run={}", dry_run);
+        let condition = "id NOT IN (SELECT MAX(id) FROM paths AS kept \
+             WHERE date = (SELECT MAX(date) FROM paths WHERE path = kept.path) GROUP BY path)";
+        if dry_run {
+            return self.remove_paths_where(condition, vec![], true);
+        }
+        let transaction = self.db_conn.unchecked_transaction()?;
+        self.db_conn
+            .execute(
+                &format!(
+                    "UPDATE paths SET \
+                     visits = (SELECT SUM(visits) FROM paths AS same WHERE same.path = paths.path), \
+                     dwell_seconds = \
+                     (SELECT SUM(dwell_seconds) FROM paths AS same WHERE same.path = paths.path), \
+                     created = (SELECT MIN(created) FROM paths AS same WHERE same.path = paths.path) \
+                     WHERE NOT ({}) \
+                     AND (SELECT COUNT(*) FROM paths AS same WHERE same.path = paths.path) > 1",
+                    condition
+                ),
+                [],
+            )
+            .map_err(|e| {
+                error!("Failed to merge the visits of the duplicated paths: {}", e);
+                e
+            })?;
+        let removed = self.remove_paths_where(condition, vec![], false)?;
+        transaction.commit()?;
+        Ok(removed)
+    }
+
+    /// Removes the paths which were not visited since a date.
+    ///
+    /// ### Parameters
+    /// before: the paths last visited before this timestamp are removed (in seconds since EPOCH)
+    /// dry_run: if true, the paths are not removed
+    ///
+    /// ### Returns
+    /// The paths removed (or which would be removed) if the operation was successful,
+    /// otherwise an error.
+    pub(crate) fn expire_paths(
+        &self,
+        before: i64,
+        dry_run: bool,
+    ) -> Result<Vec<Path>, rusqlite::Error> {
+        debug!("expire_paths before={} dry_run={}", before, dry_run);
+        self.remove_paths_where("date < (?1)", vec![before.to_string()], dry_run)
+    }
+
+    /// Removes the oldest paths, to keep only the most recent ones.
+    ///
+    /// ### Parameters
+    /// max: the number of paths to keep
+    /// dry_run: if true, the paths are not removed
+    ///
+    /// ### Returns
+    /// The paths removed (or which would be removed) if the operation was successful,
+    /// otherwise an error.
+    pub(crate) fn trim_paths(
+        &self,
+        max: usize,
+        dry_run: bool,
+    ) -> Result<Vec<Path>, rusqlite::Error> {
+        debug!("trim_paths max={} dry_run={}", max, dry_run);
+        self.remove_paths_where(
+            "id NOT IN (SELECT id FROM paths ORDER BY date desc, id desc LIMIT (?1))",
+            vec![max.to_string()],
+            dry_run,
+        )
+    }
+
+    fn remove_paths_where(
+        &self,
+        condition: &str,
+        params: Vec<String>,
+        dry_run: bool,
+    ) -> Result<Vec<Path>, rusqlite::Error> {
+        let sql = format!(
+            "SELECT id, path, date, visits, created, hidden, via FROM paths WHERE {} \
+             ORDER BY date desc, id desc",
+            condition
+        );
+        let mut removed = vec![];
+        self.query_paths(&sql, params.clone(), |path| removed.push(path))?;
+        if !dry_run && !removed.is_empty() {
+            let sql = format!("DELETE FROM paths WHERE {}", condition);
+            self.db_conn
+                .execute(&sql, rusqlite::params_from_iter(params))
+                .map_err(|e| {
+                    error!("Failed to remove the paths {}: {}", sql, e);
+                    e
+                })?;
+        }
+        Ok(removed)
+    }
+
+    /// Lists paths from the database with pagination and optional filtering.
+    /// The results are ordered by date (descending) and ID (descending).
+    /// If `like_text` is provided, only paths containing the text are returned.
+    ///
+    /// ### Parameters
+    /// pos: the starting position (offset) for pagination, 0 for the first path; nothing is
+    /// returned beyond the last one
+    /// len: the number of paths to return, at least 1
+    /// like_text: optional text to filter paths (if empty, no filtering is applied)
+    /// fuzzy: whether to perform a fuzzy search
+    /// include_hidden: whether the hidden paths are listed
+    ///
+    /// ### Returns
+    /// A vector of Path entries if the operation was successful, otherwise an error.
+    pub(crate) fn list_paths(
+        &self,
+        pos: usize,
+        len: usize,
+        like_text: &str,
+        fuzzy: bool,
+        include_hidden: bool,
+    ) -> Result<Vec<Path>, rusqlite::Error> {
+        debug!(
+            "list_paths pos={} len={} like_text={} fuzzy={} include_hidden={}",
+            pos, len, like_text, fuzzy, include_hidden
+        );
+        let like_text = self.normalized(like_text);
+        let like_text = like_text.as_ref();
+        if like_text.is_empty() || !fuzzy {
+            self.list_path_exact(pos, len, like_text, SortMode::Recency, include_hidden)
+        } else {
+            self.list_path_fuzzy(pos, len, like_text, include_hidden)
+        }
+    }
+
+    /// Lists paths from the database with pagination and optional filtering (exact match),
+    /// in the given order.
+    ///
+    /// ### Parameters
+    /// pos: the starting position (offset) for pagination
+    /// len: the number of paths to return
+    /// like_text: optional text to filter paths (if empty, no filtering is applied)
+    /// sort_mode: the order of the paths
+    /// include_hidden: whether the hidden paths are listed
+    ///
+    /// ### Returns
+    /// A vector of Path entries if the operation was successful, otherwise an error.
+    pub(crate) fn list_paths_sorted(
+        &self,
+        pos: usize,
+        len: usize,
+        like_text: &str,
+        sort_mode: SortMode,
+        include_hidden: bool,
+    ) -> Result<Vec<Path>, rusqlite::Error> {
+        debug!(
+            "list_paths_sorted pos={} len={} like_text={} sort_mode={:?} include_hidden={}",
+            pos, len, like_text, sort_mode, include_hidden
+        );
+        let like_text = self.normalized(like_text);
+        let like_text = like_text.as_ref();
+        self.list_path_exact(pos, len, like_text, sort_mode, include_hidden)
+    }
+
+    /// Counts the paths matching the search text, with the same semantics as `list_paths`.
+    ///
+    /// ### Parameters
+    /// like_text: optional text to filter paths (if empty, no filtering is applied)
+    /// fuzzy: whether to perform a fuzzy search
+    /// include_hidden: whether the hidden paths are counted
+    ///
+    /// ### Returns
+    /// The number of matching paths if the operation was successful, otherwise an error.
+    pub(crate) fn count_paths(
+        &self,
+        like_text: &str,
+        fuzzy: bool,
+        include_hidden: bool,
+    ) -> Result<usize, rusqlite::Error> {
+        debug!(
+            "count_paths like_text={} fuzzy={} include_hidden={}",
+            like_text, fuzzy, include_hidden
+        );
+        let like_text = self.normalized(like_text);
+        let like_text = like_text.as_ref();
+        if like_text.is_empty() || !fuzzy {
+            let (sql, params) = Self::build_path_exact_query(
+                like_text,
+                SortMode::Recency.order_by(),
+                include_hidden,
+            );
+            let sql = format!("SELECT COUNT(*) FROM ({})", sql);
+            self.db_conn
+                .query_row(&sql, rusqlite::params_from_iter(params), |row| {
+                    row.get::<_, i64>(0)
+                })
+                .map(|count| count as usize)
+                .map_err(|e| {
+                    error!("count_paths failed {}: {}", sql, e);
+                    e
+                })
+        } else {
+            Ok(self
+                .list_path_fuzzy(0, usize::MAX, like_text, include_hidden)?
+                .len())
+        }
+    }
+
+    /// Returns the date of the most recent visit recorded, hidden paths included, e.g. to
+    /// detect the paths recorded by another shell while the GUI is open.
+    ///
+    /// ### Returns
+    /// The most recent date, or `None` if there is no path, if the operation was successful,
+    /// otherwise an error.
+    pub(crate) fn max_date(&self) -> Result<Option<i64>, rusqlite::Error> {
+        self.db_conn
+            .query_row("SELECT MAX(date) FROM paths", [], |row| {
+                row.get::<_, Option<i64>>(0)
+            })
+    }
 
     fn list_path_fuzzy(
         &self,
         pos: usize,
         len: usize,
         like_text: &str,
+        include_hidden: bool,
     ) -> Result<Vec<Path>, rusqlite::Error> {
         debug!(
             "list_path_fuzzy pos={} len={} like_text={}",
             pos, len, like_text
         );
+        let Some((pos, len)) = clamp_page(pos, len) else {
+            return Ok(vec![]);
+        };
 
-        let sql = String::from("SELECT id, path, date FROM paths ORDER BY date desc, id desc");
+        let sql = format!(
+            "SELECT id, path, date, visits, created, hidden, via FROM paths {} \
+             ORDER BY date desc, id desc",
+            if include_hidden {
+                ""
+            } else {
+                "WHERE hidden = 0"
+            }
+        );
         let mut stmt = match self.db_conn.prepare(sql.as_str()) {
             Ok(stmt) => stmt,
             Err(e) => {
@@ -329,6 +1389,10 @@ impl Store {
                 id: row.get(0)?,
                 path: row.get(1)?,
                 date: row.get(2)?,
+                visits: row.get(3)?,
+                created: row.get(4)?,
+                hidden: row.get(5)?,
+                via: row.get(6)?,
             })
         }) {
             Ok(rows) => rows.filter_map(|row| {
@@ -347,7 +1411,7 @@ impl Store {
 
         // Collect all (Path, score) pairs and sort by descending score
         let mut scored_paths: Vec<(Path, u32)> = rows.collect();
-        scored_paths.sort_by(|a, b| b.1.cmp(&a.1));
+        scored_paths.sort_by_key(|b| std::cmp::Reverse(b.1));
         // Paginate: skip `pos`, take `len`
         let paginated = scored_paths
             .into_iter()
@@ -358,31 +1422,169 @@ impl Store {
         Ok(paginated)
     }
 
+    /// Returns the order of the paths, the frecency being the stored score if configured (see
+    /// `with_stored_frecency`).
+    fn order_by(&self, sort_mode: SortMode) -> &'static str {
+        if sort_mode == SortMode::Frecency && self.stored_frecency {
+            STORED_FRECENCY_ORDER
+        } else {
+            sort_mode.order_by()
+        }
+    }
+
+    /// Builds the query selecting the paths matching the search text, in the given order.
+    /// The search text is split into terms (see `tokenize_search_text`): each term must be found
+    /// in the path, and a term prefixed with '-' must not. A `via:<command>` term matches the
+    /// paths visited while the command was running instead. The hidden paths are excluded unless `include_hidden` is set.
+    ///
+    /// ### Returns
+    /// The SQL query and its parameters.
+    fn build_path_exact_query(
+        like_text: &str,
+        order_by: &str,
+        include_hidden: bool,
+    ) -> (String, Vec<String>) {
+        let mut params: Vec<String> = vec![];
+        let mut sql =
+            String::from("SELECT id, path, date, visits, created, hidden, via FROM paths");
+
+        let mut clauses: Vec<String> = vec![];
+        if !include_hidden {
+            clauses.push(String::from("hidden = 0"));
+        }
+        for term in tokenize_search_text(like_text) {
+            if let Some(via) = term.text.strip_prefix(VIA_FILTER_PREFIX)
+                && !via.is_empty()
+            {
+                // the command itself, or with arguments; its wildcards are matched literally
+                params.push(via.to_string());
+                params.push(prefix_pattern(&format!("{} ", via)));
+                let clause = format!(
+                    "(via = (?{}) OR via LIKE (?{}) ESCAPE '\\')",
+                    params.len() - 1,
+                    params.len()
+                );
+                clauses.push(if term.negated {
+                    format!("(via IS NULL OR NOT {})", clause)
+                } else {
+                    clause
+                });
+                continue;
+            }
+            let operator = if term.negated { "NOT LIKE" } else { "LIKE" };
+            params.push(term.text);
+            clauses.push(format!(
+                "path {} '%' || (?{}) || '%'",
+                operator,
+                params.len()
+            ));
+        }
+        if !clauses.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+        sql.push_str(" ORDER BY ");
+        sql.push_str(order_by);
+        (sql, params)
+    }
+
     fn list_path_exact(
         &self,
         pos: usize,
         len: usize,
         like_text: &str,
+        sort_mode: SortMode,
+        include_hidden: bool,
     ) -> Result<Vec<Path>, rusqlite::Error> {
         debug!(
             "list_path_exact pos={} len={} like_text={}",
             pos, len, like_text
         );
+        let Some((pos, len)) = clamp_page(pos, len) else {
+            return Ok(vec![]);
+        };
 
-        let mut params: Vec<String> = vec![];
-        let mut sql = String::from("SELECT id, path, date FROM paths");
-
-        if !like_text.is_empty() {
-            sql.push_str(" WHERE path like '%' || (?1) || '%'");
-            sql.push_str(" ORDER BY date desc, id desc LIMIT (?2) OFFSET (?3)");
-            params.push(like_text.to_string());
-        } else {
-            sql.push_str(" ORDER BY date desc, id desc LIMIT (?1) OFFSET (?2)");
-        }
+        let (mut sql, mut params) =
+            Self::build_path_exact_query(like_text, self.order_by(sort_mode), include_hidden);
+        sql.push_str(&format!(
+            " LIMIT (?{}) OFFSET (?{})",
+            params.len() + 1,
+            params.len() + 2
+        ));
         params.push(format!("{}", len));
         params.push(format!("{}", pos));
 
-        let mut stmt = match self.db_conn.prepare(sql.as_str()) {
+        let mut paths = Vec::new();
+        self.query_paths(&sql, params, |path| paths.push(path))?;
+        Ok(paths)
+    }
+
+    /// Lists the visible paths starting with a prefix (ignoring the ASCII case), the most recent
+    /// first. The prefix is anchored, so the query can use the index on the paths.
+    ///
+    /// ### Parameters
+    /// pos: the starting position (offset) for pagination
+    /// len: the number of paths to return
+    /// prefix: the beginning of the paths; `%` and `_` are matched literally
+    ///
+    /// ### Returns
+    /// A vector of Path entries if the operation was successful, otherwise an error.
+    pub(crate) fn list_paths_prefix(
+        &self,
+        pos: usize,
+        len: usize,
+        prefix: &str,
+    ) -> Result<Vec<Path>, rusqlite::Error> {
+        debug!(
+            "list_paths_prefix pos={} len={} prefix={}",
+            pos, len, prefix
+        );
+        let Some((pos, len)) = clamp_page(pos, len) else {
+            return Ok(vec![]);
+        };
+        let sql = format!(
+            "SELECT id, path, date, visits, created, hidden, via FROM paths \
+             WHERE hidden = 0 AND {} ORDER BY {} LIMIT (?2) OFFSET (?3)",
+            PREFIX_CLAUSE,
+            SortMode::Recency.order_by()
+        );
+        let params = vec![prefix_pattern(prefix), len.to_string(), pos.to_string()];
+        let mut paths = Vec::new();
+        self.query_paths(&sql, params, |path| paths.push(path))?;
+        Ok(paths)
+    }
+
+    /// Calls a function on each path matching the search text (exact match), the most recent
+    /// first. The paths are read one by one, without loading the whole list.
+    ///
+    /// ### Parameters
+    /// like_text: optional text to filter paths (if empty, no filtering is applied)
+    /// include_hidden: whether the function is called on the hidden paths too
+    /// f: the function called on each path
+    ///
+    /// ### Returns
+    /// Ok(()) if the operation was successful, otherwise an error.
+    pub(crate) fn for_each_path(
+        &self,
+        like_text: &str,
+        include_hidden: bool,
+        f: impl FnMut(Path),
+    ) -> Result<(), rusqlite::Error> {
+        debug!("for_each_path like_text={}", like_text);
+        let like_text = self.normalized(like_text);
+        let like_text = like_text.as_ref();
+        let (sql, params) =
+            Self::build_path_exact_query(like_text, SortMode::Recency.order_by(), include_hidden);
+        self.query_paths(&sql, params, f)
+    }
+
+    fn query_paths(
+        &self,
+        sql: &str,
+        params: Vec<String>,
+        mut f: impl FnMut(Path),
+    ) -> Result<(), rusqlite::Error> {
+        let mut stmt = match self.db_conn.prepare(sql) {
             Ok(stmt) => stmt,
             Err(e) => {
                 error!("list_paths failed in prepare {}: {}", sql, e);
@@ -395,6 +1597,10 @@ impl Store {
                 id: row.get(0)?,
                 path: row.get(1)?,
                 date: row.get(2)?,
+                visits: row.get(3)?,
+                created: row.get(4)?,
+                hidden: row.get(5)?,
+                via: row.get(6)?,
             })
         }) {
             Ok(rows) => rows,
@@ -404,15 +1610,15 @@ impl Store {
             }
         };
 
-        let mut paths = Vec::new();
         for path in rows {
-            paths.push(path?);
+            f(path?);
         }
-        Ok(paths)
+        Ok(())
     }
 
     /// Adds a new shortcut to the database.
     /// If a shortcut with the same name already exists, it is deleted before adding the new one.
+    /// The name and the path are trimmed, and the shortcut is skipped if one of them is empty.
     ///
     /// ### Parameters
     /// name: the name of the shortcut
@@ -427,11 +1633,25 @@ impl Store {
         description: Option<&str>,
     ) -> Result<(), rusqlite::Error> {
         debug!("add_shortcut: {} {}", name, path);
+        let (name, path) = (name.trim(), path.trim());
+        if name.is_empty() || path.is_empty() {
+            info!("Skipping a shortcut with an empty name or path");
+            return Ok(());
+        }
         self.delete_shortcut(name)?;
         self.db_conn
             .execute(
-                "INSERT INTO shortcuts (name, path, description) VALUES ((?1),(?2),(?3))",
-                (name, path, description),
+                "INSERT INTO shortcuts (name, path, description, created) \
+                 VALUES ((?1),(?2),(?3),(?4))",
+                (
+                    name,
+                    path,
+                    description,
+                    SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs() as i64,
+                ),
             )
             .map_err(|e| {
                 error!(
@@ -477,21 +1697,55 @@ impl Store {
             .map(|_l| ())
     }
 
-    /// Deletes a shortcut from the database by its name.
-    /// If the shortcut does not exist, no action is taken.
+    /// Points an existing shortcut, found by its name, to a new path.
     ///
     /// ### Parameters
-    /// name: the name of the shortcut to delete
+    /// name: the name of the shortcut
+    /// new_path: the new path of the shortcut
     ///
     /// ### Returns
-    /// Ok(()) if the operation was successful, otherwise an error.
-    pub(crate) fn delete_shortcut(&self, name: &str) -> Result<(), rusqlite::Error> {
-        let mut stmt = self
+    /// Ok(()) if the shortcut was updated, `QueryReturnedNoRows` if there is no shortcut with
+    /// this name, otherwise an error.
+    pub(crate) fn update_shortcut_path(
+        &self,
+        name: &str,
+        new_path: &str,
+    ) -> Result<(), rusqlite::Error> {
+        debug!("update_shortcut_path: name={} path={}", name, new_path);
+        let updated = self
             .db_conn
-            .prepare("DELETE FROM shortcuts WHERE name=(?1)")?;
-        if let Err(err) = stmt.execute([name]) {
-            error!("Failed to delete shortcut '{}': {}", name, err);
-            return Err(err);
+            .execute(
+                "UPDATE shortcuts SET path = (?1) WHERE name = (?2)",
+                (new_path, name),
+            )
+            .map_err(|e| {
+                error!(
+                    "Failed to update the path of shortcut name='{}' path='{}': {}",
+                    name, new_path, e
+                );
+                e
+            })?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        Ok(())
+    }
+
+    /// Deletes a shortcut from the database by its name.
+    /// If the shortcut does not exist, no action is taken.
+    ///
+    /// ### Parameters
+    /// name: the name of the shortcut to delete
+    ///
+    /// ### Returns
+    /// Ok(()) if the operation was successful, otherwise an error.
+    pub(crate) fn delete_shortcut(&self, name: &str) -> Result<(), rusqlite::Error> {
+        let mut stmt = self
+            .db_conn
+            .prepare("DELETE FROM shortcuts WHERE name=(?1)")?;
+        if let Err(err) = stmt.execute([name]) {
+            error!("Failed to delete shortcut '{}': {}", name, err);
+            return Err(err);
         }
         Ok(())
     }
@@ -522,34 +1776,69 @@ impl Store {
     /// name: the name of the shortcut to find
     ///
     /// ### Returns
-    /// Some(path) if the shortcut is found, otherwise None.
-    pub(crate) fn find_shortcut(&self, name: &str) -> Option<Shortcut> {
+    /// Some(shortcut) if the shortcut is found, None if there is none, otherwise an error.
+    pub(crate) fn find_shortcut(&self, name: &str) -> Result<Option<Shortcut>, rusqlite::Error> {
         debug!("find_shortcut {}", name);
-
-        let mut stmt = match self
+        let oshort = self
             .db_conn
-            .prepare("SELECT id, path, description FROM shortcuts WHERE name=(?1)")
-        {
-            Ok(stmt) => stmt,
-            Err(e) => {
-                error!("find_shortcut failed in prepare: {}", e);
-                return None;
-            }
-        };
-
-        let oshort = match stmt.query_map([name], |row| {
-            Ok(Shortcut {
-                id: row.get(0)?,
-                name: name.to_string(),
-                path: row.get(1)?,
-                description: row.get(2)?,
-            })
-        }) {
-            Ok(mut rows) => rows.next().and_then(|row| row.ok()),
-            Err(_) => None,
-        };
+            .query_row(
+                "SELECT id, path, description, created FROM shortcuts WHERE name=(?1)",
+                [name],
+                |row| {
+                    Ok(Shortcut {
+                        id: row.get(0)?,
+                        name: name.to_string(),
+                        path: row.get(1)?,
+                        description: row.get(2)?,
+                        created: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .inspect_err(|e| error!("find_shortcut failed: {}", e))?;
         debug!("find_shortcut {:?}", oshort);
-        oshort
+        Ok(oshort)
+    }
+
+    /// Finds the shortcut a name typed by the user refers to: the shortcut with this name,
+    /// unless the lookup is lenient (see `with_lenient_shortcut_lookup`). The name is then
+    /// trimmed, and a shortcut with the same name regardless of the case is found if none has
+    /// exactly this name; the first one by name if there are several.
+    ///
+    /// ### Parameters
+    /// name: the name typed
+    ///
+    /// ### Returns
+    /// Some(shortcut) if a shortcut is found, None if there is none, otherwise an error.
+    pub(crate) fn resolve_shortcut(&self, name: &str) -> Result<Option<Shortcut>, rusqlite::Error> {
+        if !self.lenient_shortcut_lookup {
+            return self.find_shortcut(name);
+        }
+        let name = name.trim();
+        if let Some(shortcut) = self.find_shortcut(name)? {
+            return Ok(Some(shortcut));
+        }
+        debug!("resolve_shortcut {}", name);
+        let shortcut = self
+            .db_conn
+            .query_row(
+                "SELECT id, name, path, description, created FROM shortcuts \
+                 WHERE name=(?1) COLLATE NOCASE ORDER BY name LIMIT 1",
+                [name],
+                |row| {
+                    Ok(Shortcut {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        path: row.get(2)?,
+                        description: row.get(3)?,
+                        created: row.get(4)?,
+                    })
+                },
+            )
+            .optional()
+            .inspect_err(|e| error!("resolve_shortcut failed: {}", e))?;
+        debug!("resolve_shortcut {:?}", shortcut);
+        Ok(shortcut)
     }
 
     /// Lists shortcuts from the database with pagination and optional filtering.
@@ -557,8 +1846,9 @@ impl Store {
     /// If `like_text` is provided, only shortcuts with names or paths containing the text are returned.
     ///
     /// ### Parameters
-    /// pos: the starting position (offset) for pagination
-    /// len: the number of shortcuts to return
+    /// pos: the starting position (offset) for pagination, 0 for the first shortcut; nothing is
+    /// returned beyond the last one
+    /// len: the number of shortcuts to return, at least 1
     /// like_text: optional text to filter shortcuts (if empty, no filtering is applied)
     ///
     /// ### Returns
@@ -576,12 +1866,73 @@ impl Store {
         );
 
         if like_text.is_empty() || !fuzzy {
-            self.list_shortcuts_exact(pos, len, like_text)
+            self.list_shortcuts_exact(pos, len, like_text, ShortcutSort::Name)
         } else {
             self.list_shortcuts_fuzzy(pos, len, like_text)
         }
     }
 
+    /// Lists shortcuts from the database with pagination and optional filtering (exact match),
+    /// in the given order.
+    ///
+    /// ### Parameters
+    /// pos: the starting position (offset) for pagination
+    /// len: the number of shortcuts to return
+    /// like_text: optional text to filter shortcuts (if empty, no filtering is applied)
+    /// sort: the order of the shortcuts
+    ///
+    /// ### Returns
+    /// A vector of Shortcut entries if the operation was successful, otherwise an error.
+    pub(crate) fn list_shortcuts_sorted(
+        &self,
+        pos: usize,
+        len: usize,
+        like_text: &str,
+        sort: ShortcutSort,
+    ) -> Result<Vec<Shortcut>, rusqlite::Error> {
+        debug!(
+            "list_shortcuts_sorted pos={} len={} like_text={} sort={:?}",
+            pos, len, like_text, sort
+        );
+        self.list_shortcuts_exact(pos, len, like_text, sort)
+    }
+
+    /// Counts the shortcuts matching the search text, with the same semantics as
+    /// `list_shortcuts`.
+    ///
+    /// ### Parameters
+    /// like_text: optional text to filter shortcuts (if empty, no filtering is applied)
+    /// fuzzy: whether to perform a fuzzy search
+    ///
+    /// ### Returns
+    /// The number of matching shortcuts if the operation was successful, otherwise an error.
+    pub(crate) fn count_shortcuts(
+        &self,
+        like_text: &str,
+        fuzzy: bool,
+    ) -> Result<usize, rusqlite::Error> {
+        debug!("count_shortcuts like_text={} fuzzy={}", like_text, fuzzy);
+        if like_text.is_empty() || !fuzzy {
+            let mut sql = String::from("SELECT COUNT(*) FROM shortcuts");
+            let mut params: Vec<String> = vec![];
+            if !like_text.is_empty() {
+                sql.push_str(" WHERE path like '%' || (?1) || '%' OR name like '%' || (?1) || '%' OR description like '%' || (?1) || '%'");
+                params.push(like_text.to_string());
+            }
+            self.db_conn
+                .query_row(&sql, rusqlite::params_from_iter(params), |row| {
+                    row.get::<_, i64>(0)
+                })
+                .map(|count| count as usize)
+                .map_err(|e| {
+                    error!("count_shortcuts failed {}: {}", sql, e);
+                    e
+                })
+        } else {
+            Ok(self.list_shortcuts_fuzzy(0, usize::MAX, like_text)?.len())
+        }
+    }
+
     fn list_shortcuts_fuzzy(
         &self,
         pos: usize,
@@ -592,9 +1943,12 @@ impl Store {
             "list_shortcuts_fuzzy pos={} len={} like_text={}",
             pos, len, like_text
         );
+        let Some((pos, len)) = clamp_page(pos, len) else {
+            return Ok(vec![]);
+        };
 
         let sql = String::from(
-            "SELECT id, name, path, description FROM shortcuts ORDER BY name asc, id desc",
+            "SELECT id, name, path, description, created FROM shortcuts ORDER BY name asc, id desc",
         );
         let mut stmt = match self.db_conn.prepare(sql.as_str()) {
             Ok(stmt) => stmt,
@@ -613,6 +1967,7 @@ impl Store {
                 name: row.get(1)?,
                 path: row.get(2)?,
                 description: row.get(3)?,
+                created: row.get(4)?,
             })
         }) {
             Ok(rows) => rows.filter_map(|row| {
@@ -641,7 +1996,7 @@ impl Store {
 
         // Collect all (Path, score) pairs and sort by descending score
         let mut scored_shortcuts: Vec<(Shortcut, u32)> = rows.collect();
-        scored_shortcuts.sort_by(|a, b| b.1.cmp(&a.1));
+        scored_shortcuts.sort_by_key(|b| std::cmp::Reverse(b.1));
         // Paginate: skip `pos`, take `len`
         let paginated = scored_shortcuts
             .into_iter()
@@ -657,17 +2012,27 @@ impl Store {
         pos: usize,
         len: usize,
         like_text: &str,
+        sort: ShortcutSort,
     ) -> Result<Vec<Shortcut>, rusqlite::Error> {
         debug!("list_shortcuts pos={} len={} text={}", pos, len, like_text);
+        let Some((pos, len)) = clamp_page(pos, len) else {
+            return Ok(vec![]);
+        };
 
-        let mut sql = String::from("SELECT id, name, path, description FROM shortcuts");
+        let mut sql = String::from("SELECT id, name, path, description, created FROM shortcuts");
         let mut params: Vec<String> = vec![];
         if !like_text.is_empty() {
             sql.push_str(" WHERE path like '%' || (?1) || '%' OR name like '%' || (?1) || '%' OR description like '%' || (?1) || '%'");
-            sql.push_str(" ORDER BY name asc, id desc LIMIT (?2) OFFSET (?3)");
+            sql.push_str(&format!(
+                " ORDER BY {} LIMIT (?2) OFFSET (?3)",
+                shortcut_order_by(sort)
+            ));
             params.push(like_text.to_string());
         } else {
-            sql.push_str(" ORDER BY name asc, id desc LIMIT (?1) OFFSET (?2)");
+            sql.push_str(&format!(
+                " ORDER BY {} LIMIT (?1) OFFSET (?2)",
+                shortcut_order_by(sort)
+            ));
         }
         params.push(format!("{}", len));
         params.push(format!("{}", pos));
@@ -687,6 +2052,7 @@ impl Store {
                 name: row.get(1)?,
                 path: row.get(2)?,
                 description: row.get(3)?,
+                created: row.get(4)?,
             })
         }) {
             Ok(rows) => rows,
@@ -710,7 +2076,7 @@ impl Store {
     pub(crate) fn list_all_shortcuts(&self) -> Result<Vec<Shortcut>, rusqlite::Error> {
         debug!("list_all_shortcuts");
         let sql = String::from(
-            "SELECT id, name, path, description FROM shortcuts ORDER BY name asc, id desc",
+            "SELECT id, name, path, description, created FROM shortcuts ORDER BY name asc, id desc",
         );
 
         let mut stmt = match self.db_conn.prepare(sql.as_str()) {
@@ -728,6 +2094,7 @@ impl Store {
                 name: row.get(1)?,
                 path: row.get(2)?,
                 description: row.get(3)?,
+                created: row.get(4)?,
             })
         }) {
             Ok(rows) => rows,
@@ -744,21 +2111,27 @@ impl Store {
         Ok(shortcuts)
     }
 
+    /// Drops a table, for testing the handling of the database errors.
+    #[cfg(test)]
+    pub(crate) fn drop_table(&self, table: &str) {
+        self.db_conn
+            .execute(&format!("DROP TABLE {}", table), [])
+            .unwrap();
+    }
+
     /// Creates an in-memory store for testing purposes.
     #[allow(dead_code)]
-    pub(crate) fn setup_test_store() -> Store {
-        let store = Store {
-            db_conn: Rc::from(Connection::open_in_memory().unwrap()),
-        };
-        store.init_schema();
-        store
-    }
+    pub(crate) fn setup_test_store() -> Store { Store::in_memory().unwrap() }
 }
 
 impl Clone for Store {
     fn clone(&self) -> Self {
         Store {
             db_conn: Rc::clone(&self.db_conn),
+            revisit_window: self.revisit_window,
+            stored_frecency: self.stored_frecency,
+            normalize_unicode: self.normalize_unicode,
+            lenient_shortcut_lookup: self.lenient_shortcut_lookup,
         }
     }
 }
@@ -771,18 +2144,18 @@ mod tests {
     fn test_path() {
         let store = Store::setup_test_store();
 
-        let paths = store.list_paths(0, 10, "", false).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
         assert_eq!(paths.len(), 0);
 
         // A single entry
         store.add_path("test_path1").unwrap();
-        let paths = store.list_paths(0, 10, "", false).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
         assert_eq!(paths.len(), 1);
         assert_eq!(paths[0].path, "test_path1");
 
         // Two entries
         store.add_path("test_path2").unwrap();
-        let paths = store.list_paths(0, 10, "", false).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
         assert_eq!(paths.len(), 2);
         assert_eq!(paths[0].path, "test_path2");
         assert_eq!(paths[1].path, "test_path1");
@@ -793,7 +2166,7 @@ mod tests {
             .unwrap()
             .as_secs();
         store.add_path_with_time("test_path3", now + 7).unwrap();
-        let paths = store.list_paths(0, 10, "", false).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
         assert_eq!(paths.len(), 3);
         assert_eq!(paths[0].path, "test_path3");
         assert_eq!(paths[0].date, now as i64 + 7);
@@ -802,15 +2175,1083 @@ mod tests {
 
         // Delete the one in the middle
         store.delete_path_by_id(paths[1].id).unwrap();
-        let paths = store.list_paths(0, 10, "", false).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
         assert_eq!(paths.len(), 2);
         assert_eq!(paths[0].path, "test_path3");
         assert_eq!(paths[1].path, "test_path1");
 
         // Perform a search
-        let paths = store.list_paths(0, 10, "3", false).unwrap();
+        let paths = store.list_paths(0, 10, "3", false, false).unwrap();
         assert_eq!(paths.len(), 1);
         assert_eq!(paths[0].path, "test_path3");
+        assert_eq!(paths[0].visits, 1);
+
+        // Visiting again counts the visits
+        store.add_path("test_path3").unwrap();
+        store.add_path("test_path3").unwrap();
+        let paths = store.list_paths(0, 10, "3", false, false).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].visits, 3);
+        let paths = store.list_paths(0, 10, "", true, false).unwrap();
+        assert_eq!(paths.len(), 2);
+    }
+
+    #[test]
+    fn test_revisit_window() {
+        let store = Store::setup_test_store().with_revisit_window(5);
+        store.add_path_with_time("/a", 100).unwrap();
+
+        // Recorded again within the window: only the date is updated
+        store.add_path_with_time("/a", 103).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].visits, 1);
+        assert_eq!(paths[0].date, 103);
+
+        // Outside of the window: a new visit
+        store.add_path_with_time("/a", 109).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        assert_eq!(paths[0].visits, 2);
+        assert_eq!(paths[0].date, 109);
+
+        // Not the most recent path: a new visit, even within the window
+        store.add_path_with_time("/b", 110).unwrap();
+        store.add_path_with_time("/a", 111).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        assert_eq!(paths[0].path, "/a");
+        assert_eq!(paths[0].visits, 3);
+        assert_eq!(paths[1].visits, 1);
+
+        // Without a window, every recording is a visit
+        let store = Store::setup_test_store();
+        store.add_path_with_time("/a", 100).unwrap();
+        store.add_path_with_time("/a", 101).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        assert_eq!(paths[0].visits, 2);
+    }
+
+    #[test]
+    fn test_count() {
+        let store = Store::setup_test_store();
+        for i in 0..25 {
+            store.add_path(&format!("/dir/{}", i)).unwrap();
+        }
+        store.add_shortcut("one", "/dir/1", None).unwrap();
+        store.add_shortcut("two", "/dir/2", Some("second")).unwrap();
+
+        for (text, fuzzy) in [("", false), ("1", false), ("dir -2", false), ("d1", true)] {
+            assert_eq!(
+                store.count_paths(text, fuzzy, false).unwrap(),
+                store.list_paths(0, 100, text, fuzzy, false).unwrap().len()
+            );
+        }
+        assert_eq!(store.count_paths("1", false, false).unwrap(), 12);
+
+        for (text, fuzzy) in [("", false), ("o", false), ("second", false), ("tw", true)] {
+            assert_eq!(
+                store.count_shortcuts(text, fuzzy).unwrap(),
+                store.list_shortcuts(0, 100, text, fuzzy).unwrap().len()
+            );
+        }
+        assert_eq!(store.count_shortcuts("second", false).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_empty_paths_are_skipped() {
+        let store = Store::setup_test_store();
+        store.add_path("").unwrap();
+        store.add_path(" \t ").unwrap();
+        store.add_path("  /tmp/dir \n").unwrap();
+
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].path, "/tmp/dir");
+
+        store.add_shortcut(" ", "/tmp", None).unwrap();
+        store.add_shortcut("tmp", "  ", None).unwrap();
+        store.add_shortcut(" tmp ", " /tmp ", None).unwrap();
+        let shortcuts = store.list_all_shortcuts().unwrap();
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(shortcuts[0].name, "tmp");
+        assert_eq!(shortcuts[0].path, "/tmp");
+    }
+
+    #[test]
+    fn test_removal_dry_run() {
+        let dir = std::env::temp_dir();
+        let existing = dir.to_string_lossy().to_string();
+        let setup = || {
+            let store = Store::setup_test_store();
+            store.add_path_with_time("/missing/old", 100).unwrap();
+            store.add_path_with_time(&existing, 200).unwrap();
+            store.add_path_with_time("/missing/recent", 300).unwrap();
+            store
+        };
+        let count = |store: &Store| store.count_paths("", false, false).unwrap();
+        let names =
+            |paths: Vec<Path>| -> Vec<String> { paths.into_iter().map(|p| p.path).collect() };
+
+        let store = setup();
+        let dry = store.prune_paths(true).unwrap();
+        assert_eq!(count(&store), 3);
+        let removed = store.prune_paths(false).unwrap();
+        assert_eq!(names(dry), vec!["/missing/recent", "/missing/old"]);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(count(&store), 1);
+
+        let store = setup();
+        let dry = store.expire_paths(250, true).unwrap();
+        assert_eq!(count(&store), 3);
+        let removed = store.expire_paths(250, false).unwrap();
+        assert_eq!(
+            names(dry),
+            vec![existing.clone(), "/missing/old".to_string()]
+        );
+        assert_eq!(removed.len(), 2);
+        assert_eq!(count(&store), 1);
+
+        let store = setup();
+        let dry = store.trim_paths(1, true).unwrap();
+        assert_eq!(count(&store), 3);
+        let removed = store.trim_paths(1, false).unwrap();
+        assert_eq!(
+            names(dry),
+            vec![existing.clone(), "/missing/old".to_string()]
+        );
+        assert_eq!(removed.len(), 2);
+        assert_eq!(
+            names(store.list_paths(0, 10, "", false, false).unwrap()),
+            vec!["/missing/recent"]
+        );
+
+        // nothing to remove
+        assert!(store.trim_paths(5, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dedup_paths() {
+        let store = Store::setup_test_store();
+        // duplicates are not created by add_path, but e.g. by older imports
+        for (path, date, visits, created, dwell) in [
+            ("/a", 100, 2, 50, 10),
+            ("/a", 300, 3, 250, 20),
+            ("/b", 200, 1, 200, 0),
+            ("/a", 300, 1, 300, 5),
+        ] {
+            store
+                .db_conn
+                .execute(
+                    "INSERT INTO paths (path, date, visits, created, dwell_seconds) \
+                     VALUES ((?1),(?2),(?3),(?4),(?5))",
+                    params![path, date, visits, created, dwell],
+                )
+                .unwrap();
+        }
+        let count = || store.count_paths("", false, true).unwrap();
+
+        let dry = store.dedup_paths(true).unwrap();
+        assert_eq!(count(), 4);
+        let removed = store.dedup_paths(false).unwrap();
+        assert_eq!(dry.len(), 2);
+        assert_eq!(
+            removed.iter().map(|path| path.id).collect::<Vec<_>>(),
+            dry.iter().map(|path| path.id).collect::<Vec<_>>()
+        );
+        assert_eq!(count(), 2);
+
+        // the most recent entry is kept, with the visits of all of them
+        let paths = store.list_paths(0, 10, "", false, true).unwrap();
+        assert_eq!(paths[0].path, "/a");
+        assert_eq!(paths[0].id, 4);
+        assert_eq!(paths[0].date, 300);
+        assert_eq!(paths[0].visits, 6);
+        assert_eq!(paths[0].created, 50);
+        assert_eq!(paths[1].path, "/b");
+        assert_eq!(paths[1].visits, 1);
+
+        // nothing to merge
+        assert!(store.dedup_paths(false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_record_dwell() {
+        let store = Store::setup_test_store();
+        store.add_path_with_time("/a", 100).unwrap();
+        store.add_path_with_time("/b", 200).unwrap();
+        let dwell = |path: &str| -> i64 {
+            store
+                .db_conn
+                .query_row(
+                    "SELECT dwell_seconds FROM paths WHERE path=(?1)",
+                    [path],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+
+        // an enter/leave pair adds its duration
+        store.record_enter("/a", 1000).unwrap();
+        store.record_leave("/a", 1030).unwrap();
+        store.record_enter("/a", 2000).unwrap();
+        store.record_leave("/a", 2012).unwrap();
+        assert_eq!(dwell("/a"), 42);
+
+        // a leave without enter is ignored, as well as an enter without leave
+        store.record_leave("/b", 3000).unwrap();
+        assert_eq!(dwell("/b"), 0);
+        store.record_enter("/b", 3000).unwrap();
+        store.record_enter("/b", 5000).unwrap();
+        store.record_leave("/b", 5010).unwrap();
+        assert_eq!(dwell("/b"), 10);
+
+        // a too long visit is capped
+        store.record_enter("/b", 10000).unwrap();
+        store.record_leave("/b", 10000 + 3 * 86400).unwrap();
+        assert_eq!(dwell("/b"), 10 + MAX_DWELL_SECONDS as i64);
+
+        // the time spent is kept when the path is visited again
+        store.add_path_with_time("/a", 300).unwrap();
+        assert_eq!(dwell("/a"), 42);
+
+        let paths: Vec<String> = store
+            .list_paths_sorted(0, 10, "", SortMode::Dwell, false)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.path)
+            .collect();
+        assert_eq!(paths, vec!["/b", "/a"]);
+    }
+
+    #[test]
+    fn test_path_context() {
+        let store = Store::setup_test_store();
+        store
+            .add_path_with_time_and_context("/home/user/repo", 4, Some("git"))
+            .unwrap();
+        store
+            .add_path_with_time_and_context("/home/user/other", 3, Some("git checkout"))
+            .unwrap();
+        store
+            .add_path_with_time_and_context("/home/user/gitk", 2, Some("gitk"))
+            .unwrap();
+        store.add_path_with_time("/home/user/plain", 1).unwrap();
+
+        let via = |path: &str| -> Option<String> {
+            store
+                .list_paths(0, 10, path, false, false)
+                .unwrap()
+                .into_iter()
+                .find(|p| p.path == path)
+                .unwrap()
+                .via
+        };
+        assert_eq!(via("/home/user/repo"), Some(String::from("git")));
+        // omitted, the context is null
+        assert_eq!(via("/home/user/plain"), None);
+
+        let search = |text: &str| -> Vec<String> {
+            store
+                .list_paths(0, 10, text, false, false)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.path)
+                .collect()
+        };
+        // the command, with arguments or not
+        assert_eq!(
+            search("via:git"),
+            vec![
+                "/home/user/repo".to_string(),
+                "/home/user/other".to_string()
+            ]
+        );
+        assert_eq!(search("via:git repo"), vec!["/home/user/repo".to_string()]);
+        assert_eq!(
+            search(r#"via:"git checkout""#),
+            vec!["/home/user/other".to_string()]
+        );
+        assert_eq!(
+            search("-via:git"),
+            vec![
+                "/home/user/gitk".to_string(),
+                "/home/user/plain".to_string()
+            ]
+        );
+        assert_eq!(store.count_paths("via:gitk", false, false).unwrap(), 1);
+        assert_eq!(search("via:make").len(), 0);
+        // the wildcards of LIKE are not
+        assert_eq!(search("via:gi_").len(), 0);
+        assert_eq!(search("via:%").len(), 0);
+
+        // a visit without context keeps the previous one, a new context replaces it
+        store.add_path_with_time("/home/user/repo", 5).unwrap();
+        assert_eq!(via("/home/user/repo"), Some(String::from("git")));
+        store
+            .add_path_with_time_and_context("/home/user/repo", 6, Some("make"))
+            .unwrap();
+        assert_eq!(via("/home/user/repo"), Some(String::from("make")));
+        assert_eq!(
+            store.list_paths(0, 10, "", false, false).unwrap()[0].visits,
+            3
+        );
+    }
+
+    #[test]
+    fn test_hidden_paths() {
+        let store = Store::setup_test_store();
+        store.add_path_with_time("/a", 100).unwrap();
+        store.add_path_with_time("/b", 200).unwrap();
+        store.add_path_with_time("/ab", 300).unwrap();
+        let names =
+            |paths: Vec<Path>| -> Vec<String> { paths.into_iter().map(|p| p.path).collect() };
+
+        let id = store.list_paths(0, 10, "b", false, false).unwrap()[1].id;
+        store.set_path_hidden(id, true).unwrap();
+        assert_eq!(
+            names(store.list_paths(0, 10, "", false, false).unwrap()),
+            vec!["/ab", "/a"]
+        );
+        assert_eq!(store.count_paths("b", false, false).unwrap(), 1);
+        assert_eq!(store.count_paths("b", true, false).unwrap(), 1);
+
+        // listed on demand, with their flag
+        let paths = store.list_paths(0, 10, "b", true, true).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.iter().any(|p| p.path == "/b" && p.hidden));
+        assert_eq!(store.count_paths("b", false, true).unwrap(), 2);
+
+        // a new visit keeps the path hidden
+        store.add_path_with_time("/b", 400).unwrap();
+        assert_eq!(store.count_paths("", false, false).unwrap(), 2);
+
+        let id = store.list_paths(0, 1, "", false, true).unwrap()[0].id;
+        store.set_path_hidden(id, false).unwrap();
+        assert_eq!(store.count_paths("", false, false).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_in_memory() {
+        let store = Store::in_memory().unwrap();
+        assert_eq!(store.find_schema_version(), CURRENT_SCHEMA_VERSION);
+        assert_eq!(store.count_paths("", false, false).unwrap(), 0);
+
+        store.add_path("/home/user/src").unwrap();
+        store.add_shortcut("src", "/home/user/src", None).unwrap();
+        assert_eq!(
+            store.list_paths(0, 10, "", false, false).unwrap()[0].path,
+            "/home/user/src"
+        );
+        assert_eq!(
+            store.find_shortcut("src").unwrap().unwrap().path,
+            "/home/user/src"
+        );
+
+        // Each store has its own database
+        assert_eq!(
+            Store::in_memory()
+                .unwrap()
+                .count_paths("", false, false)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_store_error() {
+        let error = StoreError::Schema(rusqlite::Error::InvalidQuery);
+        assert!(
+            error
+                .to_string()
+                .starts_with("failed to create the database schema: ")
+        );
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn test_path_created() {
+        let store = Store::setup_test_store();
+        store.add_path_with_time("/a", 100).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        assert_eq!(paths[0].date, 100);
+        assert_eq!(paths[0].created, 100);
+
+        // Upserting updates the date but not the creation
+        store.add_path_with_time("/a", 200).unwrap();
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].date, 200);
+        assert_eq!(paths[0].created, 100);
+
+        // Also after a deletion, the path is a new one
+        store.delete_path_by_id(paths[0].id).unwrap();
+        store.add_path_with_time("/a", 300).unwrap();
+        let paths = store.list_paths(0, 10, "a", true, false).unwrap();
+        assert_eq!(paths[0].date, 300);
+        assert_eq!(paths[0].created, 300);
+    }
+
+    #[test]
+    fn test_for_each_path() {
+        let store = Store::setup_test_store();
+        for i in 0..25 {
+            store
+                .add_path_with_time(&format!("/dir/{}", i), 100 + i % 7)
+                .unwrap();
+        }
+
+        for text in ["", "1", "dir -2"] {
+            let mut streamed = vec![];
+            store
+                .for_each_path(text, false, |path| streamed.push(path.path))
+                .unwrap();
+            let listed: Vec<String> = store
+                .list_paths(0, 100, text, false, false)
+                .unwrap()
+                .into_iter()
+                .map(|path| path.path)
+                .collect();
+            assert!(!streamed.is_empty());
+            assert_eq!(streamed, listed);
+        }
+    }
+
+    #[test]
+    fn test_recent_searches() {
+        let store = Store::setup_test_store();
+        assert!(store.list_recent_searches().unwrap().is_empty());
+
+        // Empty searches are not recorded
+        store.add_recent_search("", 3).unwrap();
+        store.add_recent_search("  ", 3).unwrap();
+        assert!(store.list_recent_searches().unwrap().is_empty());
+
+        // The most recent first, consecutive duplicates are skipped
+        store.add_recent_search("src", 3).unwrap();
+        store.add_recent_search("doc", 3).unwrap();
+        store.add_recent_search("doc", 3).unwrap();
+        assert_eq!(store.list_recent_searches().unwrap(), vec!["doc", "src"]);
+
+        // Non consecutive duplicates are kept
+        store.add_recent_search("src", 3).unwrap();
+        assert_eq!(
+            store.list_recent_searches().unwrap(),
+            vec!["src", "doc", "src"]
+        );
+
+        // The history is capped
+        store.add_recent_search("tmp", 3).unwrap();
+        assert_eq!(
+            store.list_recent_searches().unwrap(),
+            vec!["tmp", "src", "doc"]
+        );
+        store.add_recent_search("etc", 0).unwrap();
+        assert!(store.list_recent_searches().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_last_view() {
+        let store = Store::setup_test_store();
+        assert_eq!(store.last_view().unwrap(), None);
+        store.set_last_view("shortcuts").unwrap();
+        store.set_last_view("history").unwrap();
+        assert_eq!(store.last_view().unwrap().as_deref(), Some("history"));
+    }
+
+    #[test]
+    fn test_resolve_shortcut() {
+        let store = Store::setup_test_store();
+        store.add_shortcut("work", "/home/user/work", None).unwrap();
+        store.add_shortcut("Docs", "/home/user/Docs", None).unwrap();
+        store.add_shortcut("docs", "/home/user/docs", None).unwrap();
+        let resolved = |store: &Store, name: &str| {
+            store
+                .resolve_shortcut(name)
+                .unwrap()
+                .map(|shortcut| (shortcut.name, shortcut.path))
+        };
+
+        // the exact name only by default
+        assert_eq!(
+            resolved(&store, "work"),
+            Some((String::from("work"), String::from("/home/user/work")))
+        );
+        assert_eq!(resolved(&store, "Work"), None);
+        assert_eq!(resolved(&store, "work "), None);
+
+        let store = store.with_lenient_shortcut_lookup(true);
+        for name in ["Work", "WORK", "work ", " wOrk\t"] {
+            assert_eq!(
+                resolved(&store, name),
+                Some((String::from("work"), String::from("/home/user/work"))),
+                "{:?}",
+                name
+            );
+        }
+        // the exact name first, else the first one by name
+        assert_eq!(resolved(&store, "docs").unwrap().1, "/home/user/docs");
+        assert_eq!(resolved(&store, "Docs").unwrap().1, "/home/user/Docs");
+        assert_eq!(resolved(&store, "DOCS").unwrap().1, "/home/user/Docs");
+        assert_eq!(resolved(&store, "wor"), None);
+        assert_eq!(resolved(&store, " "), None);
+
+        // a database error is not taken for a missing shortcut
+        store.drop_table("shortcuts");
+        assert!(store.find_shortcut("work").is_err());
+        assert!(store.resolve_shortcut("Work").is_err());
+    }
+
+    #[test]
+    fn test_toggle() {
+        let store = Store::setup_test_store();
+        assert_eq!(store.toggle("shorten_paths").unwrap(), None);
+        store.set_toggle("shorten_paths", false).unwrap();
+        store.set_toggle("substitute_home", true).unwrap();
+        assert_eq!(store.toggle("shorten_paths").unwrap(), Some(false));
+        assert_eq!(store.toggle("substitute_home").unwrap(), Some(true));
+        store.set_toggle("shorten_paths", true).unwrap();
+        assert_eq!(store.toggle("shorten_paths").unwrap(), Some(true));
+    }
+
+    #[test]
+    fn test_last_path() {
+        let store = Store::setup_test_store();
+        assert_eq!(store.last_path().unwrap(), None);
+        store.set_last_path("/tmp").unwrap();
+        store.set_last_path("/home/user/src").unwrap();
+        assert_eq!(
+            store.last_path().unwrap().as_deref(),
+            Some("/home/user/src")
+        );
+    }
+
+    #[test]
+    fn test_clamp_page() {
+        assert_eq!(clamp_page(0, 10), Some((0, 10)));
+        assert_eq!(clamp_page(5, 0), Some((5, 1)));
+        assert_eq!(clamp_page(5, usize::MAX), Some((5, MAX_PAGE_BOUND)));
+        assert_eq!(clamp_page(MAX_PAGE_BOUND, 10), None);
+        assert_eq!(clamp_page(usize::MAX, 10), None);
+    }
+
+    #[test]
+    fn test_list_pages() {
+        let store = Store::setup_test_store();
+        for i in 0..3 {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+            store
+                .add_shortcut(&format!("s{}", i), &format!("/{}", i), None)
+                .unwrap();
+        }
+        let paths = |pos, len, fuzzy| {
+            let text = if fuzzy { "/" } else { "" };
+            store
+                .list_paths(pos, len, text, fuzzy, false)
+                .unwrap()
+                .into_iter()
+                .map(|path| path.path)
+                .collect::<Vec<_>>()
+        };
+        let shortcuts = |pos, len, fuzzy| {
+            let text = if fuzzy { "s" } else { "" };
+            store
+                .list_shortcuts(pos, len, text, fuzzy)
+                .unwrap()
+                .into_iter()
+                .map(|shortcut| shortcut.name)
+                .collect::<Vec<_>>()
+        };
+        for fuzzy in [false, true] {
+            // a page of no entry reads one
+            assert_eq!(paths(1, 0, fuzzy).len(), 1);
+            assert_eq!(shortcuts(1, 0, fuzzy).len(), 1);
+            // the whole lists
+            assert_eq!(paths(0, usize::MAX, fuzzy).len(), 3);
+            assert_eq!(shortcuts(0, usize::MAX, fuzzy).len(), 3);
+            // beyond the lists
+            assert!(paths(3, 10, fuzzy).is_empty());
+            assert!(paths(usize::MAX, 10, fuzzy).is_empty());
+            assert!(shortcuts(i64::MAX as usize, 10, fuzzy).is_empty());
+            assert!(shortcuts(usize::MAX, usize::MAX, fuzzy).is_empty());
+        }
+        assert_eq!(paths(1, 0, false), vec!["/1"]);
+        assert!(
+            store
+                .list_paths_prefix(usize::MAX, 10, "/")
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(store.list_paths_prefix(0, 0, "/").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_max_date() {
+        let store = Store::setup_test_store();
+        assert_eq!(store.max_date().unwrap(), None);
+        store.add_path_with_time("/b", 2000).unwrap();
+        store.add_path_with_time("/a", 1000).unwrap();
+        assert_eq!(store.max_date().unwrap(), Some(2000));
+        store.add_path_with_time("/a", 3000).unwrap();
+        assert_eq!(store.max_date().unwrap(), Some(3000));
+    }
+
+    #[test]
+    fn test_sort_modes() {
+        let store = Store::setup_test_store();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let day = 86400;
+        // b: visited 3 times, 10 days ago
+        for _ in 0..3 {
+            store.add_path_with_time("/b", now - 10 * day).unwrap();
+        }
+        // c: visited twice, a few minutes ago
+        for _ in 0..2 {
+            store.add_path_with_time("/c", now - 300).unwrap();
+        }
+        // a: visited once, now
+        store.add_path_with_time("/a", now).unwrap();
+        // d: visited 5 times, 30 days ago
+        for _ in 0..5 {
+            store.add_path_with_time("/d", now - 30 * day).unwrap();
+        }
+
+        let list = |sort_mode: SortMode| -> Vec<String> {
+            store
+                .list_paths_sorted(0, 10, "", sort_mode, false)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.path)
+                .collect()
+        };
+        assert_eq!(list(SortMode::Recency), vec!["/a", "/c", "/b", "/d"]);
+        assert_eq!(list(SortMode::Frequency), vec!["/d", "/b", "/c", "/a"]);
+        // c: 2*4, a: 1*4, d: 5*0.25, b: 3*0.25
+        assert_eq!(list(SortMode::Frecency), vec!["/c", "/a", "/d", "/b"]);
+        assert_eq!(list(SortMode::Alphabetical), vec!["/a", "/b", "/c", "/d"]);
+
+        // the filter still applies
+        assert_eq!(
+            store
+                .list_paths_sorted(0, 10, "-a -b", SortMode::Frequency, false)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.path)
+                .collect::<Vec<String>>(),
+            vec!["/d", "/c"]
+        );
+
+        // the modes are cycled through
+        let mut sort_mode = SortMode::Recency;
+        for _ in 0..5 {
+            sort_mode = sort_mode.next();
+        }
+        assert_eq!(sort_mode, SortMode::Recency);
+    }
+
+    #[test]
+    fn test_normalize_unicode() {
+        let decomposed = "/tmp/cafe\u{301}";
+        let composed = "/tmp/caf\u{e9}";
+        let store = Store::setup_test_store().with_normalize_unicode(true);
+        store.add_path_with_time(decomposed, 1000).unwrap();
+        store.add_path_with_time(composed, 2000).unwrap();
+
+        // both spellings are the same directory
+        let paths = store.list_paths(0, 10, "", false, false).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].path, composed);
+        assert_eq!(paths[0].visits, 2);
+
+        // and each one finds it
+        for text in [decomposed, composed, "cafe\u{301}", "caf\u{e9}"] {
+            for fuzzy in [false, true] {
+                let paths = store.list_paths(0, 10, text, fuzzy, false).unwrap();
+                assert_eq!(paths.len(), 1, "{:?} fuzzy={}", text, fuzzy);
+                assert_eq!(store.count_paths(text, fuzzy, false).unwrap(), 1);
+            }
+            let paths = store
+                .list_paths_sorted(0, 10, text, SortMode::Frequency, false)
+                .unwrap();
+            assert_eq!(paths.len(), 1, "{:?}", text);
+        }
+
+        // without normalization, they are distinct
+        let store = Store::setup_test_store();
+        store.add_path_with_time(decomposed, 1000).unwrap();
+        store.add_path_with_time(composed, 2000).unwrap();
+        assert_eq!(store.list_paths(0, 10, "", false, false).unwrap().len(), 2);
+        assert_eq!(store.count_paths("caf\u{e9}", false, false).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_stored_frecency() {
+        let computed = Store::setup_test_store();
+        let stored = computed.clone().with_stored_frecency(true);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let day = 86400;
+        let visits = [
+            ("/a", now, 1),
+            ("/b", now - 10 * day, 3),
+            ("/c", now - 300, 2),
+            ("/d", now - 30 * day, 5),
+            ("/e", now - 2 * 3600, 1),
+            ("/f", now - 3 * day, 4),
+        ];
+        for (path, date, count) in visits {
+            for _ in 0..count {
+                stored.add_path_with_time(path, date).unwrap();
+            }
+        }
+        let list = |store: &Store, filter: &str| -> Vec<String> {
+            store
+                .list_paths_sorted(0, 10, filter, SortMode::Frecency, false)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.path)
+                .collect()
+        };
+
+        // the scores are computed when the paths are visited
+        assert_eq!(list(&stored, ""), list(&computed, ""));
+        assert_eq!(list(&stored, ""), vec!["/c", "/a", "/e", "/f", "/d", "/b"]);
+        assert_eq!(stored.reage_scores(now).unwrap(), 6);
+        assert_eq!(list(&stored, ""), list(&computed, ""));
+        assert_eq!(list(&stored, "-a -c"), list(&computed, "-a -c"));
+
+        // two days later: a, c and e are less recent, f is not yet a week old
+        stored.reage_scores(now + 2 * day).unwrap();
+        assert_eq!(list(&stored, ""), vec!["/f", "/d", "/c", "/b", "/a", "/e"]);
+
+        // the score of a visited path is up to date right away
+        stored.add_path("/b").unwrap();
+        assert_eq!(list(&stored, "")[0], "/b");
+
+        // the order uses the index
+        let plan: Vec<String> = stored
+            .db_conn
+            .prepare(&format!(
+                "EXPLAIN QUERY PLAN SELECT id FROM paths ORDER BY {}",
+                STORED_FRECENCY_ORDER
+            ))
+            .unwrap()
+            .query_map([], |row| row.get::<_, String>(3))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert!(
+            plan.iter().any(|step| step.contains("paths_score")),
+            "{:?}",
+            plan
+        );
+
+        // without stored scores, a visit doesn't compute the score
+        computed.add_path_with_time("/g", now).unwrap();
+        let score: f64 = computed
+            .db_conn
+            .query_row("SELECT score FROM paths WHERE path = '/g'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(score, 0.0);
+    }
+
+    #[test]
+    fn test_path_tokens() {
+        let store = Store::setup_test_store();
+        store.add_path_with_time("/home/user/src/app", 4).unwrap();
+        store
+            .add_path_with_time("/home/user/src/app/test", 3)
+            .unwrap();
+        store.add_path_with_time("/home/user/doc", 2).unwrap();
+        store.add_path_with_time("/tmp/test", 1).unwrap();
+
+        let search = |text: &str| -> Vec<String> {
+            store
+                .list_paths(0, 10, text, false, false)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.path)
+                .collect()
+        };
+
+        // Positive tokens must all match
+        assert_eq!(
+            search("src test"),
+            vec!["/home/user/src/app/test".to_string()]
+        );
+        assert_eq!(search("user   app").len(), 2);
+
+        // Negative tokens exclude
+        assert_eq!(search("src -test"), vec!["/home/user/src/app".to_string()]);
+        assert_eq!(search("-src -doc"), vec!["/tmp/test".to_string()]);
+        assert_eq!(search("user -src -doc").len(), 0);
+
+        // A bare '-' is ignored
+        assert_eq!(search("src -"), search("src"));
+        assert_eq!(search("-").len(), 4);
+
+        // Pagination still applies
+        let paths = store.list_paths(1, 1, "-doc", false, false).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].path, "/home/user/src/app/test");
+    }
+
+    #[test]
+    fn test_list_paths_prefix() {
+        let store = Store::setup_test_store();
+        store.add_path_with_time("/home/user/src", 1).unwrap();
+        store.add_path_with_time("/home/user/src/cdir", 2).unwrap();
+        store.add_path_with_time("/opt/home/user", 3).unwrap();
+        store.add_path_with_time("/home/my_dir", 4).unwrap();
+        store.add_path_with_time("/home/myXdir", 5).unwrap();
+        store.add_path_with_time("/home/user/old", 6).unwrap();
+        let hidden = store.list_paths_prefix(0, 1, "/home/user/old").unwrap()[0].id;
+        store.set_path_hidden(hidden, true).unwrap();
+        let paths =
+            |paths: Vec<Path>| -> Vec<String> { paths.into_iter().map(|p| p.path).collect() };
+
+        // anchored at the start, unlike the substring search
+        assert_eq!(
+            paths(store.list_paths_prefix(0, 10, "/home/user").unwrap()),
+            vec!["/home/user/src/cdir", "/home/user/src"]
+        );
+        assert_eq!(
+            paths(store.list_paths(0, 10, "/home/user", false, false).unwrap()),
+            vec!["/opt/home/user", "/home/user/src/cdir", "/home/user/src"]
+        );
+
+        // the wildcards are literal
+        assert_eq!(
+            paths(store.list_paths_prefix(0, 10, "/home/my_").unwrap()),
+            vec!["/home/my_dir"]
+        );
+        assert!(store.list_paths_prefix(0, 10, "/%").unwrap().is_empty());
+
+        // paginated, ignoring the case
+        assert_eq!(
+            paths(store.list_paths_prefix(1, 1, "/HOME/").unwrap()),
+            vec!["/home/my_dir"]
+        );
+    }
+
+    #[test]
+    fn test_prefix_index() {
+        let store = Store::setup_test_store();
+        let plan: Vec<String> = store
+            .db_conn
+            .prepare(&format!(
+                "EXPLAIN QUERY PLAN SELECT id FROM paths WHERE {}",
+                PREFIX_CLAUSE
+            ))
+            .unwrap()
+            .query_map(params![prefix_pattern("/home")], |row| {
+                row.get::<_, String>(3)
+            })
+            .unwrap()
+            .map(|detail| detail.unwrap())
+            .collect();
+        assert!(
+            plan.iter().any(|detail| detail.contains("paths_path")),
+            "{:?}",
+            plan
+        );
+    }
+
+    #[test]
+    fn test_tokenize_search_text() {
+        let terms = |text: &str| -> Vec<(String, bool)> {
+            tokenize_search_text(text)
+                .into_iter()
+                .map(|t| (t.text, t.negated))
+                .collect()
+        };
+        let term = |text: &str, negated: bool| (text.to_string(), negated);
+
+        assert_eq!(
+            terms("  src   app "),
+            vec![term("src", false), term("app", false)]
+        );
+        assert_eq!(terms(""), vec![]);
+
+        // Quoted phrases keep their spaces
+        assert_eq!(
+            terms(r#""My Drive" doc"#),
+            vec![term("My Drive", false), term("doc", false)]
+        );
+        assert_eq!(terms(r#"-"My Drive""#), vec![term("My Drive", true)]);
+        assert_eq!(terms(r#""-doc""#), vec![term("-doc", false)]);
+        assert_eq!(terms(r#""""#), vec![]);
+
+        // Quotes adjacent to other tokens
+        assert_eq!(terms(r#"my"dir name"s"#), vec![term("mydir names", false)]);
+        assert_eq!(
+            terms(r#""a b""c d" e"#),
+            vec![term("a bc d", false), term("e", false)]
+        );
+
+        // Escaped quotes are literal, inside or outside a phrase
+        assert_eq!(terms(r#"a\"b"#), vec![term("a\"b", false)]);
+        assert_eq!(
+            terms(r#""say \"hi\" now""#),
+            vec![term("say \"hi\" now", false)]
+        );
+
+        // An unterminated quote is a phrase up to the end
+        assert_eq!(terms(r#""My Drive"#), vec![term("My Drive", false)]);
+        assert_eq!(terms(r#""My "#), vec![term("My ", false)]);
+        assert_eq!(
+            terms(r#"a "b" -"c d \"e"#),
+            vec![term("a", false), term("b", false), term("c d \"e", true)]
+        );
+        assert_eq!(terms(r#"a ""#), vec![term("a", false)]);
+    }
+
+    #[test]
+    fn test_path_quoted_phrase() {
+        let store = Store::setup_test_store();
+        store
+            .add_path_with_time("/home/user/My Drive/doc", 3)
+            .unwrap();
+        store.add_path_with_time("/home/user/Drive/My", 2).unwrap();
+        store
+            .add_path_with_time("/tmp/My \"quoted\" dir", 1)
+            .unwrap();
+
+        let search = |text: &str| -> Vec<String> {
+            store
+                .list_paths(0, 10, text, false, false)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.path)
+                .collect()
+        };
+
+        assert_eq!(search("My Drive").len(), 2);
+        assert_eq!(
+            search(r#""My Drive""#),
+            vec!["/home/user/My Drive/doc".to_string()]
+        );
+        assert_eq!(
+            search(r#"-"My Drive" Drive"#),
+            vec!["/home/user/Drive/My".to_string()]
+        );
+        assert_eq!(
+            search(r#""\"quoted\" dir""#),
+            vec!["/tmp/My \"quoted\" dir".to_string()]
+        );
+        // being typed
+        assert_eq!(
+            search(r#""My Dri"#),
+            vec!["/home/user/My Drive/doc".to_string()]
+        );
+        assert_eq!(store.count_paths(r#""My Drive""#, false, false).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_shortcut_created() {
+        let store = Store::setup_test_store();
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        store.add_shortcut("docs", "/home/user/docs", None).unwrap();
+        let created = store.find_shortcut("docs").unwrap().unwrap().created;
+        assert!(created >= before);
+        assert_eq!(store.list_all_shortcuts().unwrap()[0].created, created);
+        assert_eq!(
+            store.list_shortcuts(0, 10, "doc", false).unwrap()[0].created,
+            created
+        );
+        assert_eq!(
+            store.list_shortcuts(0, 10, "doc", true).unwrap()[0].created,
+            created
+        );
+
+        // the shortcuts created before the creation date was recorded
+        store
+            .db_conn
+            .execute("UPDATE shortcuts SET created = 0", [])
+            .unwrap();
+        assert_eq!(store.find_shortcut("docs").unwrap().unwrap().created, 0);
+    }
+
+    #[test]
+    fn test_shortcut_sort_modes() {
+        let store = Store::setup_test_store();
+        store.add_shortcut("b", "/c", None).unwrap();
+        store.add_shortcut("c", "/a", None).unwrap();
+        store.add_shortcut("a", "/b", Some("x")).unwrap();
+        store.add_shortcut("d", "/b/d", Some("x")).unwrap();
+        for (name, created) in [("b", 30), ("c", 10), ("a", 20), ("d", 0)] {
+            store
+                .db_conn
+                .execute(
+                    "UPDATE shortcuts SET created = (?2) WHERE name = (?1)",
+                    params![name, created],
+                )
+                .unwrap();
+        }
+        let names = |text: &str, sort: ShortcutSort| -> Vec<String> {
+            store
+                .list_shortcuts_sorted(0, 10, text, sort)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.name)
+                .collect()
+        };
+
+        assert_eq!(names("", ShortcutSort::Name), vec!["a", "b", "c", "d"]);
+        assert_eq!(names("", ShortcutSort::Path), vec!["c", "a", "d", "b"]);
+        // the shortcuts without creation date last
+        assert_eq!(names("", ShortcutSort::Recent), vec!["b", "a", "c", "d"]);
+        assert_eq!(names("x", ShortcutSort::Recent), vec!["a", "d"]);
+        assert_eq!(names("/b", ShortcutSort::Path), vec!["a", "d"]);
+
+        // the default order
+        assert_eq!(
+            store
+                .list_shortcuts(0, 10, "", false)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.name)
+                .collect::<Vec<_>>(),
+            names("", ShortcutSort::Name)
+        );
+
+        let mut sort = ShortcutSort::Name;
+        for _ in 0..3 {
+            sort = sort.next();
+        }
+        assert_eq!(sort, ShortcutSort::Name);
+    }
+
+    #[test]
+    fn test_update_shortcut_path() {
+        let store = Store::setup_test_store();
+        store
+            .add_shortcut("docs", "/home/user/docs", Some("documents"))
+            .unwrap();
+        store.add_shortcut("tmp", "/tmp", None).unwrap();
+
+        store
+            .update_shortcut_path("docs", "/data/user/docs")
+            .unwrap();
+        let docs = store.find_shortcut("docs").unwrap().unwrap();
+        assert_eq!(docs.path, "/data/user/docs");
+        assert_eq!(docs.description, Some(String::from("documents")));
+        assert_eq!(store.find_shortcut("tmp").unwrap().unwrap().path, "/tmp");
+
+        assert!(matches!(
+            store.update_shortcut_path("unknown", "/data"),
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        ));
     }
 
     #[test]
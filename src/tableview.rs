@@ -1,25 +1,33 @@
 use std::{
     any::Any,
+    collections::BTreeMap,
+    process::{Command, ExitStatus},
     rc::Rc,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, Once},
 };
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use log::{debug, info, warn};
+use log::{debug, error, info, warn};
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
     prelude::Style,
     style::Stylize,
-    widgets::{Paragraph, Row, Table, TableState},
+    text::Span,
+    widgets::{Block, Paragraph, Row, Table, TableState, Wrap},
 };
 use tokio::sync::broadcast;
 
 use crate::{
-    config::Config,
+    clipboard::{Clipboard, Osc52Clipboard},
+    config::{Config, JumpRows},
     confirmation::Confirmation,
-    model::{DataViewModel, ListFunction},
+    details::Details,
+    dialog::InputDialog,
+    model::{CountFunction, DataError, DataViewModel, ListFunction},
+    path_syntax::{WINDOWS, is_absolute, is_separator, strip_directory, trim_separators},
     search_text_view::SearchDescriptionPayload,
+    store::{Identified, Ordered},
     theme::ThemeStyles,
     tui::{
         EventCaptured, GenericEvent, ManagerAction, View, ViewBuilder, ViewManager,
@@ -28,59 +36,283 @@ use crate::{
 };
 
 const TABLE_HEADER_LENGTH: usize = 1;
-const JUMP_OFFSET: usize = 10;
 
 const TABLE_COLUMN_SPACING: u16 = 1;
-const TABLE_HIGHLIGHT_SYMBOL: &str = "> ";
+
+/// Below this width, the table is not rendered
+const TABLE_MIN_WIDTH: u16 = 12;
+const TABLE_NARROW_WIDTH: u16 = 60;
+const TOO_SMALL_MESSAGE: &str = "terminal too small";
+// shown until the first entries are received
+const LOADING_MESSAGE: &str = "loading…";
+const OPEN_FAILED_MESSAGE: &str = "open failed";
+const DELETE_FAILED_MESSAGE: &str = "delete failed";
+const COPY_FAILED_MESSAGE: &str = "copy failed";
+const NO_LAST_CHOICE_MESSAGE: &str = "nothing chosen yet";
+const LAST_CHOICE_NOT_LISTED_MESSAGE: &str = "last choice not listed";
+// shown instead of the rows when they can't be built
+const DATABASE_ERROR_MESSAGE: &str = "database error";
+// replaced by the path in the open command
+const OPEN_COMMAND_PATH: &str = "{}";
 
 /// A function type that converts a vector of items of type T into a vector of table rows.
-pub type RowifyFn<T> = Box<dyn Fn(&[T], &[u16]) -> Vec<Row<'static>>>;
+pub type RowifyFn<T> = Box<dyn Fn(&[T], &[u16]) -> Result<Vec<Row<'static>>, rusqlite::Error>>;
+
+/// A function type that converts an item of type T into the string chosen, marked or copied
+/// (e.g. the path of a directory)
+pub type StringifyFn<T> = Box<dyn Fn(&T) -> String>;
+
+/// An operation on the selected entry of a table view, produced by the key handling.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    /// Choose the entry and exit (enter)
+    Choose,
+    /// Choose the entry and keep the GUI open for another choice (ctrl+k)
+    ChooseAndContinue,
+    /// Choose the canonical path of the entry and exit (ctrl+y)
+    ChooseCanonical,
+    /// Delete the entry, once confirmed (ctrl+d)
+    Delete,
+    /// Hide the entry from the lists, or show it again (ctrl+x)
+    ToggleHidden,
+    /// Create a shortcut with the given name, once typed (ctrl+b), to the path of the entry
+    /// selected when the dialog was opened
+    CreateShortcut { name: String, path: String },
+}
+
+impl Action {
+    /// The message shown in the footer when the action failed
+    fn failure_message(&self) -> &'static str {
+        match self {
+            Action::Choose | Action::ChooseAndContinue | Action::ChooseCanonical => {
+                DATABASE_ERROR_MESSAGE
+            }
+            Action::Delete => DELETE_FAILED_MESSAGE,
+            Action::ToggleHidden | Action::CreateShortcut { .. } => DATABASE_ERROR_MESSAGE,
+        }
+    }
+}
+
+/// What a table view does once an action has been performed
+#[derive(Debug, PartialEq)]
+pub enum ActionOutcome {
+    /// Exit the GUI with the result
+    Exit(Option<String>),
+    /// Reload the entries, the selection stays on the same row
+    Refresh,
+    /// Show a message in the footer
+    Message(String),
+}
+
+/// A function type that performs an action on an item of type T (e.g. into the store)
+pub type ActionFn<T> = Box<dyn Fn(Action, &T) -> Result<ActionOutcome, rusqlite::Error>>;
+
+/// The part of an item which is edited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditMode {
+    /// The fields of the item, e.g. the name and the description of a shortcut
+    Fields,
+    /// The path of the item
+    Path,
+    /// The name of a new shortcut to the item, typed in an input dialog
+    Shortcut,
+}
+
+/// A function type that builds the editor of an item of type T, if it can be edited so
+pub type EditorViewBuilder<T> = Box<dyn Fn(T, EditMode) -> Option<Box<ViewBuilder>>>;
+
+/// A function type that computes the width of a column from the loaded items of type T
+pub type ColumnWidthFn<T> = Box<dyn Fn(&[T]) -> u16>;
 
-/// A function type that deletes an item of type T into the store
-pub type DeleteFn<T> = Box<dyn Fn(&T)>;
+/// A function type that describes an item of type T: the labels and values of its fields
+pub type DetailsFn<T> = Box<dyn Fn(&T) -> Vec<(String, String)>>;
 
-pub type EditorViewBuilder<T> = Box<dyn Fn(T) -> Box<ViewBuilder>>;
+/// A function type that gives the result chosen with a search text whatever the selected item,
+/// if any (e.g. the path of the shortcut named so)
+pub type ExactChoiceFn = Box<dyn Fn(&str) -> Option<String>>;
+
+/// A function type that gives the result chosen the last time the GUI was quit with one, if
+/// any, compared to the string representation of the items
+pub type LastChoiceFn = Box<dyn Fn() -> Option<String>>;
+
+/// A function type called before the items are loaded again once modified or refreshed, e.g.
+/// to reload what their rows display besides the items
+pub type RefreshFn = Box<dyn Fn()>;
+
+// "footer.message"
+pub struct FooterMessagePayload {
+    pub objects_type: String,
+    pub message: String,
+}
+
+// "search.run"
+pub struct SearchRunPayload {
+    pub objects_type: String,
+}
+
+// "display.mode"
+pub struct DisplayModePayload {
+    pub display_with_shortcuts: bool,
+    pub display_with_home_tilde: bool,
+}
 
 pub struct TableViewState {
     pub display_with_shortcuts: bool,
+    /// whether the home directory is displayed as `~`
+    pub display_with_home_tilde: bool,
+    /// the text of the exact search, highlighted in the rows (empty in fuzzy mode)
+    pub match_text: String,
 }
 
 impl TableViewState {
     pub fn new() -> Self {
         TableViewState {
             display_with_shortcuts: true,
+            display_with_home_tilde: true,
+            match_text: String::new(),
         }
     }
 }
 
 /// A generic table view for displaying data in a tabular format within the GUI.
-pub struct TableView<T: Clone> {
+pub struct TableView<T: Clone + Ordered> {
     vm: Rc<ViewManager>,
     tx: broadcast::Sender<GenericEvent>,
     data_model: DataViewModel<T>,
     column_names: Vec<String>,
     column_constraints: Vec<Constraint>,
+    optional_columns: usize, // Trailing columns hidden when the terminal is narrow
+    fit_column: Option<(usize, ColumnWidthFn<T>)>, // A column fitting the loaded entries
     table_state: TableState,
     table_rows_count: u16, // Number of lines in the table, excluding header & footer
     rowify: RowifyFn<T>,
-    stringify: fn(&T) -> String,
+    stringify: StringifyFn<T>,
     styles: ThemeStyles,
     view_state: Arc<Mutex<TableViewState>>,
-    delete_fn: DeleteFn<T>,
+    action_fn: ActionFn<T>,
     editor_modal_view_builder: Option<EditorViewBuilder<T>>,
+    details_fn: DetailsFn<T>,
+    min_query_len: usize,
+    // the number of rows still visible after paging
+    page_overlap: u16,
+    // the number of rows moved by shift+up/down
+    jump_rows: JumpRows,
+    type_ahead: bool,
+    show_header: bool,
+    open_command: String,
+    // the marked entries by absolute position, i.e. in the order of the list, with their string
+    // representation (they may not be loaded anymore)
+    marked: BTreeMap<usize, String>,
+    clipboard: Box<dyn Clipboard>,
+    // the search text and fuzzy mode of a search announced before its query, run on the next
+    // "search.run" event
+    pending_search: Option<(String, bool)>,
+    print_abbreviated: bool,
+    // the home directory, replaced by `~` in the paths copied
+    home: Option<String>,
+    // the search text given at launch, run with the first resize, once the rows are known
+    search_string: Option<String>,
+    // whether the search text given at launch is fuzzy
+    fuzzy_search: bool,
+    exact_choice_fn: Option<ExactChoiceFn>,
+    last_choice_fn: Option<LastChoiceFn>,
+    refresh_fn: Option<RefreshFn>,
+    // the entry selected when the dialog naming a new shortcut to it was opened
+    shortcut_target: Option<T>,
+    // whether Yes is highlighted first in the confirmations, rather than No
+    confirm_default_yes: bool,
+}
+
+/// Returns the canonical absolute form of a path (symbolic links resolved), or the path itself
+/// if it cannot be resolved, e.g. when it no longer exists.
+pub(crate) fn canonical_path(path: &str) -> String {
+    match std::fs::canonicalize(path) {
+        Ok(canonical) => canonical.to_string_lossy().to_string(),
+        Err(e) => {
+            warn!("Failed to canonicalize {}: {}", path, e);
+            path.to_string()
+        }
+    }
+}
+
+/// Returns the home directory given by the HOME environment variable (or USERPROFILE on
+/// Windows), without trailing separator. It is `None` if it is unset, empty, the root or not
+/// absolute (e.g. in a container), and then the paths are displayed without `~`.
+pub(crate) fn home_directory() -> Option<String> {
+    let home = usable_home(std::env::var("HOME").ok().as_deref(), WINDOWS).or_else(|| {
+        // HOME is usually only set by the Unix-like shells on Windows
+        WINDOWS
+            .then(|| usable_home(std::env::var("USERPROFILE").ok().as_deref(), WINDOWS))
+            .flatten()
+    });
+    if home.is_none() {
+        static NO_HOME: Once = Once::new();
+        NO_HOME.call_once(|| debug!("No usable HOME, the paths are not abbreviated with ~"));
+    }
+    home
 }
 
-impl<T: Clone + 'static> View for TableView<T> {
-    fn init(&mut self) { self.table_state.select_cell(Some((0, 0))); }
+/// Returns the home directory from the value of HOME, if it can abbreviate the paths.
+fn usable_home(home: Option<&str>, windows: bool) -> Option<String> {
+    let home = trim_separators(home?, windows);
+    // the root keeps its separator
+    (is_absolute(home, windows) && !home.ends_with(|c| is_separator(c, windows)))
+        .then(|| home.to_string())
+}
+
+/// Returns a path where the home directory, if any, is replaced by `~`.
+fn abbreviate_home(path: &str, home: Option<String>, windows: bool) -> String {
+    match home
+        .as_deref()
+        .and_then(|home| strip_directory(path, home, windows))
+    {
+        Some(rest) => format!("~{}", rest),
+        None => path.to_string(),
+    }
+}
+
+/// Returns the path to print (or copy) for a chosen entry: its full path, unless the
+/// `print_abbreviated` option is set and the home directory is displayed as `~`.
+///
+/// ### Parameters
+/// - `path`: the path of the entry
+/// - `print_abbreviated`: whether the path is printed as displayed
+/// - `view_state`: the current display of the paths
+/// - `home`: the home directory, see `home_directory`
+pub(crate) fn printed_path(
+    path: &str,
+    print_abbreviated: bool,
+    view_state: &TableViewState,
+    home: Option<String>,
+) -> String {
+    if print_abbreviated && view_state.display_with_home_tilde {
+        abbreviate_home(path, home, WINDOWS)
+    } else {
+        path.to_string()
+    }
+}
+
+impl<T: Clone + Identified + Ordered + 'static> View for TableView<T> {
+    fn init(&mut self) { self.set_selected(Some(0)); }
 
     fn resize(&mut self, area: Rect) -> Vec<(u16, Rect)> {
         debug!("resize {}", area);
 
-        self.table_rows_count = area.height - TABLE_HEADER_LENGTH as u16;
+        self.table_rows_count = self
+            .table_inner_area(area)
+            .height
+            .saturating_sub(self.header_length());
         debug!("self.table_rows_count={}", self.table_rows_count);
 
-        self.data_model
-            .update(self.data_model.first, self.table_rows_count, true);
+        if let Some(search_string) = self.search_string.take() {
+            self.search(&search_string, self.fuzzy_search);
+        }
+
+        // the selected entry stays visible
+        let result = self.data_model.resize(self.table_rows_count);
+        self.report(result);
+        // the window loaded is exactly the visible rows
+        *self.table_state.offset_mut() = 0;
         vec![]
     }
 
@@ -102,22 +334,35 @@ impl<T: Clone + 'static> View for TableView<T> {
             frame.render_widget(background, left);
         }
 
+        if self.table_rows_count == 0 || main.width < TABLE_MIN_WIDTH {
+            debug!("no room to render the table");
+            frame.render_widget(
+                Paragraph::new(TOO_SMALL_MESSAGE).style(self.styles.text_style),
+                main,
+            );
+            return;
+        }
+
+        if self.data_model.is_loading() {
+            frame.render_widget(
+                Paragraph::new(LOADING_MESSAGE).style(self.styles.text_style),
+                main,
+            );
+            return;
+        }
+
         self.render_table(frame, main);
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) -> (EventCaptured, ManagerAction) {
         debug!("handle_key_event");
         match key_event.code {
-            KeyCode::Enter => {
-                debug!("send exit event");
-                let event =
-                    GenericEvent::ViewManagerEvent(ViewManagerEvent::Exit(self.handle_chosen()));
-                let _ = self.tx.send(event);
-            }
+            KeyCode::Enter => self.dispatch(Action::Choose),
             KeyCode::Home => {
-                self.data_model.update(0, self.table_rows_count, true);
-                self.table_state.select_cell(Some((0, 0)))
+                let result = self.data_model.select_home(self.table_rows_count);
+                self.report(result);
             }
+            KeyCode::End => self.handle_end(),
             KeyCode::Down => {
                 self.handle_down(key_event.modifiers.contains(KeyModifiers::SHIFT), false);
             }
@@ -130,18 +375,36 @@ impl<T: Clone + 'static> View for TableView<T> {
             KeyCode::PageUp => {
                 self.handle_up(key_event.modifiers.contains(KeyModifiers::SHIFT), true);
             }
+            KeyCode::F(5) => self.handle_refresh(),
             KeyCode::Char(c) => {
                 if key_event.modifiers == KeyModifiers::CONTROL {
                     match c {
-                        'a' => {
-                            let mut view_state_lock = self.view_state.lock().unwrap();
-                            view_state_lock.display_with_shortcuts =
-                                !view_state_lock.display_with_shortcuts;
-                        }
+                        ' ' => self.handle_toggle_mark(),
+                        'a' => self.handle_toggle_display(|view_state| {
+                            view_state.display_with_shortcuts = !view_state.display_with_shortcuts
+                        }),
+                        'b' => self.handle_modal_event(EditMode::Shortcut),
+                        'c' => self.handle_copy(),
                         'd' => self.handle_delete(),
-                        'e' => self.handle_modal_event(),
+                        'e' => self.handle_modal_event(EditMode::Fields),
+                        'g' => self.handle_select_last_choice(),
+                        'k' => self.dispatch(Action::ChooseAndContinue),
+                        'l' => self.handle_modal_event(EditMode::Path),
+                        'n' => self.handle_down(false, false),
+                        'o' => self.handle_open(),
+                        'p' => self.handle_up(false, false),
+                        's' => self.handle_cycle_sort_mode(),
+                        't' => self.handle_toggle_include_hidden(),
+                        'u' => self.handle_toggle_display(|view_state| {
+                            view_state.display_with_home_tilde = !view_state.display_with_home_tilde
+                        }),
+                        'v' => self.handle_details(),
+                        'x' => self.dispatch(Action::ToggleHidden),
+                        'y' => self.dispatch(Action::ChooseCanonical),
                         _ => {}
                     }
+                } else if self.type_ahead {
+                    self.handle_type_ahead(c);
                 }
             }
             _ => {
@@ -157,148 +420,361 @@ impl<T: Clone + 'static> View for TableView<T> {
         if ae.id == "search.description"
             && let Some(payload) = &ae.payload
             && let Some(payload) = payload.downcast_ref::<SearchDescriptionPayload>()
+            && payload
+                .objects_type
+                .as_ref()
+                .is_none_or(|objects_type| objects_type == self.data_model.objects_type())
         {
             debug!(
                 "event text={} fuzzy={}",
                 payload.search_string, payload.fuzzy_match
             );
-            self.data_model.set_fuzzy_match(payload.fuzzy_match);
-            self.data_model.update_filter(
-                self.table_rows_count,
-                payload.search_string.as_str(),
-                payload.fuzzy_match,
-            );
-
-            let _ = self
-                .tx
-                .send(GenericEvent::ViewManagerEvent(ViewManagerEvent::Redraw));
+            if self.data_model.is_searching() {
+                // the announced search runs with the latest text
+                self.pending_search = Some((payload.search_string.clone(), payload.fuzzy_match));
+            } else if self.data_model.is_slow() {
+                // the searching state is drawn before the query blocks the GUI
+                self.pending_search = Some((payload.search_string.clone(), payload.fuzzy_match));
+                self.data_model.begin_search();
+                let _ = self
+                    .tx
+                    .send(GenericEvent::ApplicationEvent(ApplicationEvent {
+                        id: String::from("search.run"),
+                        payload: Some(Arc::new(SearchRunPayload {
+                            objects_type: self.data_model.objects_type().to_string(),
+                        })),
+                    }));
+            } else {
+                self.search(&payload.search_string, payload.fuzzy_match);
+            }
+        }
+        if ae.id == "search.run"
+            && let Some(payload) = &ae.payload
+            && let Some(payload) = payload.downcast_ref::<SearchRunPayload>()
+            && payload.objects_type == self.data_model.objects_type()
+            && let Some((search_string, fuzzy_match)) = self.pending_search.take()
+        {
+            self.search(&search_string, fuzzy_match);
+        }
+        if ae.id == "data.refresh" && !self.data_model.is_searching() {
+            // e.g. new paths recorded by another shell, in watch mode
+            self.handle_refresh();
         }
     }
 }
 
-impl<T: Clone + 'static> TableView<T> {
+impl<T: Clone + Identified + Ordered + 'static> TableView<T> {
     /// Create a ViewBuilder for a new TableView instance.
     ///
     /// ### Parameters
     /// - `column_names`: A vector of strings representing the names of the table columns.
+    /// - `column_constraints`: The width constraints of the columns.
+    /// - `optional_columns`: The number of trailing columns hidden when the terminal is narrow.
+    /// - `fit_column`: The index of a column whose width is computed from the loaded items, with
+    ///   the function computing it, if any
     /// - `list_fn`: A boxed function that lists items of type T from the store
+    /// - `count_fn`: A boxed function that counts the items of type T matching a filter
+    /// - `sort_mode`: The initial order of the items, or `None` if they can't be sorted
+    /// - `include_hidden`: Whether the hidden items are initially listed, or `None` if there are
+    ///   no hidden items
     /// - `rowify`: A boxed function that converts a vector of items of type T into a vector of table rows.
     /// - `stringify`: A function that converts an item of type T into a string
     /// - `config`: A reference to the configuration object containing color settings.
     /// - `view_state`: A reference-counted, mutable boolean indicating the current view state.
-    /// - `delete_fn`: A boxed function that deletes an item of type T from the store
+    /// - `action_fn`: A boxed function that performs the actions on an item of type T (e.g. its
+    ///   deletion from the store)
+    /// - `editor_modal_view_builder`: An optional function building the editor of an item
+    /// - `details_fn`: A boxed function that describes an item, for the details popup
+    /// - `exact_choice_fn`: An optional function giving the result of Enter from the search
+    ///   text, before the selected item
+    /// - `last_choice_fn`: An optional function giving the last chosen result, selected with
+    ///   ctrl+g
+    /// - `refresh_fn`: An optional function called before the items are loaded again once
+    ///   modified or refreshed
+    /// - `search_string`: The search text the items are initially filtered with, if any
     ///
     /// ### Returns
     /// A ViewBuilder for the TableView.
+    #[allow(clippy::too_many_arguments)]
     pub fn builder(
         vm: Rc<ViewManager>,
         objects_type: String,
         column_names: Vec<String>,
         column_constraints: Vec<Constraint>,
+        optional_columns: usize,
+        fit_column: Option<(usize, ColumnWidthFn<T>)>,
         list_fn: Box<ListFunction<T>>,
+        count_fn: Box<CountFunction>,
+        sort_mode: Option<T::Order>,
+        include_hidden: Option<bool>,
+        more_hint: bool,
         rowify: RowifyFn<T>,
-        stringify: fn(&T) -> String,
+        stringify: StringifyFn<T>,
         config: Arc<Config>,
         view_state: Arc<Mutex<TableViewState>>,
-        delete_fn: DeleteFn<T>,
+        action_fn: ActionFn<T>,
         editor_modal_view_builder: Option<EditorViewBuilder<T>>,
+        details_fn: DetailsFn<T>,
+        exact_choice_fn: Option<ExactChoiceFn>,
+        last_choice_fn: Option<LastChoiceFn>,
+        refresh_fn: Option<RefreshFn>,
+        search_string: Option<String>,
     ) -> ViewBuilder {
-        ViewBuilder::from(Box::new(TableView {
+        let mut table_view = Self::new(
+            vm,
+            objects_type,
+            column_names,
+            column_constraints,
+            optional_columns,
+            fit_column,
+            list_fn,
+            count_fn,
+            sort_mode,
+            include_hidden,
+            more_hint,
+            rowify,
+            stringify,
+            config,
+            view_state,
+            action_fn,
+            editor_modal_view_builder,
+            details_fn,
+        );
+        table_view.exact_choice_fn = exact_choice_fn;
+        table_view.last_choice_fn = last_choice_fn;
+        table_view.refresh_fn = refresh_fn;
+        table_view.search_string = search_string;
+        ViewBuilder::from(Box::new(table_view))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        vm: Rc<ViewManager>,
+        objects_type: String,
+        column_names: Vec<String>,
+        column_constraints: Vec<Constraint>,
+        optional_columns: usize,
+        fit_column: Option<(usize, ColumnWidthFn<T>)>,
+        list_fn: Box<ListFunction<T>>,
+        count_fn: Box<CountFunction>,
+        sort_mode: Option<T::Order>,
+        include_hidden: Option<bool>,
+        more_hint: bool,
+        rowify: RowifyFn<T>,
+        stringify: StringifyFn<T>,
+        config: Arc<Config>,
+        view_state: Arc<Mutex<TableViewState>>,
+        action_fn: ActionFn<T>,
+        editor_modal_view_builder: Option<EditorViewBuilder<T>>,
+        details_fn: DetailsFn<T>,
+    ) -> Self {
+        let mut data_model = DataViewModel::new(
+            objects_type,
+            vm.tx(),
+            list_fn,
+            Some(count_fn),
+            false,
+            sort_mode,
+            include_hidden,
+        );
+        if more_hint {
+            data_model.set_more_hint();
+        }
+        TableView {
             vm: vm.clone(),
             tx: vm.tx(),
-            data_model: DataViewModel::new(objects_type, vm.tx(), list_fn, false),
+            data_model,
             column_names,
             column_constraints,
+            optional_columns,
+            fit_column,
             table_state: TableState::default(),
             table_rows_count: 0,
             rowify,
             stringify,
             styles: config.styles.clone(),
             view_state,
-            delete_fn,
+            action_fn,
             editor_modal_view_builder,
-        }))
+            details_fn,
+            min_query_len: config.min_query_len,
+            page_overlap: config.navigation.page_overlap,
+            jump_rows: config.navigation.jump_rows,
+            type_ahead: config.type_ahead,
+            show_header: config.show_header,
+            open_command: config.open_command.clone(),
+            marked: BTreeMap::new(),
+            clipboard: Box::new(Osc52Clipboard),
+            pending_search: None,
+            print_abbreviated: config.print_abbreviated,
+            home: home_directory(),
+            search_string: None,
+            fuzzy_search: config.fuzzy_search,
+            exact_choice_fn: None,
+            last_choice_fn: None,
+            refresh_fn: None,
+            shortcut_target: None,
+            confirm_default_yes: config.confirm_default_yes,
+        }
     }
 
-    /// Get the index of the currently selected row, if any.
-    fn selected_row(&self) -> Option<usize> {
-        let selected = self.table_state.selected_cell();
-        selected.map(|pos| pos.0)
+    /// Filters the entries with a search text, shown from the first page.
+    fn search(&mut self, search_string: &str, fuzzy_match: bool) {
+        // on error, the previous results remain visible and the footer reports it
+        let _ = self.data_model.set_fuzzy_match(fuzzy_match);
+        // a too short query shows the whole list
+        let filter = if search_string.chars().count() < self.min_query_len {
+            ""
+        } else {
+            search_string
+        };
+        if let Err(e) = self
+            .data_model
+            .update_filter(self.table_rows_count, filter, fuzzy_match)
+        {
+            warn!("invalid pattern '{}': {}", search_string, e);
+        }
+        // the positions of the entries changed
+        self.marked.clear();
+        self.view_state.lock().unwrap().match_text = if fuzzy_match {
+            String::new()
+        } else {
+            filter.to_string()
+        };
+        // the new result set is shown from its first page
+        *self.table_state.offset_mut() = 0;
+
+        let _ = self
+            .tx
+            .send(GenericEvent::ViewManagerEvent(ViewManagerEvent::Redraw));
     }
 
+    /// Get the index of the currently selected row, relative to the rows loaded by the data
+    /// model, if any. The selection is only made of a row: no column is selected.
+    fn selected(&self) -> Option<usize> { self.data_model.selected_row() }
+
+    /// Select a row, relative to the rows loaded by the data model, or nothing.
+    fn set_selected(&mut self, row: Option<usize>) { self.data_model.select_row(row); }
+
     /// Handle the chosen item and return its string representation.
     fn handle_chosen(&self) -> Option<String> {
         debug!("handle_chosen");
-        if let Some(items) = &self.data_model.entries {
-            let current_row = self.selected_row();
-            current_row.map(|row| (self.stringify)(&items[row]))
+        if let Some(items) = self.data_model.entries() {
+            let current_row = self.selected();
+            current_row
+                .and_then(|row| items.get(row))
+                .map(|item| (self.stringify)(item))
         } else {
             warn!("No data!");
             None
         }
     }
 
+    /// Clamp the selection into the rows loaded by the data model, e.g. after a deletion
+    /// returned fewer rows.
+    fn clamp_selection(&mut self) { self.data_model.clamp_selection(); }
+
+    /// The number of rows scrolled by a page: the visible rows but the overlapping ones, which
+    /// stay visible at the other end of the table. At least one row is scrolled.
+    fn page_length(&self) -> usize {
+        self.table_rows_count
+            .saturating_sub(self.page_overlap)
+            .max(1) as usize
+    }
+
+    /// The number of rows a move goes through: a page, a jump or a single row.
+    fn move_rows(&self, jump: bool, page: bool) -> usize {
+        if page {
+            self.page_length()
+        } else if jump {
+            self.jump_rows.rows(self.table_rows_count)
+        } else {
+            1
+        }
+    }
+
     /// Handle moving the selection down in the table.
     fn handle_down(&mut self, jump: bool, page: bool) {
-        if self.data_model.entries.is_none() {
-            debug!("No data");
-            return;
-        }
-        let current_row = self.selected_row();
-        if let Some(current_row) = current_row {
-            let mut offset = if jump { JUMP_OFFSET } else { 1 };
-            offset = if page {
-                self.table_rows_count as usize
-            } else {
-                offset
-            };
-            debug!(
-                "current row={} length={}",
-                current_row, self.data_model.length
-            );
-            if (current_row == (self.table_rows_count - 1) as usize) || page {
-                self.data_model
-                    .update_to_offset(offset as i64, self.table_rows_count);
-            }
-            let mut next = current_row + offset;
-            if next >= self.data_model.length as usize {
-                next = (self.data_model.length - 1) as usize;
-            }
-            self.table_state.select(Some(next));
+        let rows = self.move_rows(jump, page);
+        let result = if page {
+            self.data_model
+                .select_page_down(rows, self.table_rows_count)
         } else {
-            debug!("no current row");
-        }
+            self.data_model.select_next(rows, self.table_rows_count)
+        };
+        self.report(result);
     }
 
     /// Handle moving the selection up in the table.
     fn handle_up(&mut self, jump: bool, page: bool) {
-        if self.data_model.entries.is_none() {
+        let rows = self.move_rows(jump, page);
+        let result = if page {
+            self.data_model.select_page_up(rows, self.table_rows_count)
+        } else {
+            self.data_model.select_prev(rows, self.table_rows_count)
+        };
+        self.report(result);
+    }
+
+    /// Whether the last component of a path starts with a character (ignoring case).
+    fn last_component_starts_with(path: &str, c: char) -> bool {
+        let name = std::path::Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        name.chars()
+            .next()
+            .is_some_and(|first| first.to_lowercase().eq(c.to_lowercase()))
+    }
+
+    /// Handle the type-ahead: select the next row whose path's last component starts with `c`.
+    /// The rows after the selection are searched first, then the next page, and finally the
+    /// rows from the top of the current page (so that repeated presses cycle).
+    fn handle_type_ahead(&mut self, c: char) {
+        let (Some(current_row), Some(items)) = (self.selected(), self.data_model.entries()) else {
             debug!("No data");
             return;
-        }
-        let current_row = self.selected_row();
-        if let Some(current_row) = current_row {
-            let mut offset = if jump { JUMP_OFFSET } else { 1 };
-            offset = if page {
-                self.table_rows_count as usize
-            } else {
-                offset
-            };
-            debug!(
-                "current row={} length={}",
-                current_row, self.data_model.length
-            );
-            if (current_row == 0) || page {
-                self.data_model
-                    .update_to_offset(-(offset as i64), self.table_rows_count);
-            }
-            let mut next: i64 = (current_row as i64) - offset as i64;
-            if next < 0 {
-                next = 0;
+        };
+        let first = self.data_model.first;
+        let matches = |item: &T| Self::last_component_starts_with(&(self.stringify)(item), c);
+
+        let target = items
+            .iter()
+            .enumerate()
+            .skip(current_row + 1)
+            .find(|(_, item)| matches(item))
+            .map(|(row, _)| first + row)
+            .or_else(|| {
+                let next_first = first + items.len();
+                match self.data_model.peek(next_first, self.table_rows_count) {
+                    Ok(next_items) => next_items
+                        .iter()
+                        .position(matches)
+                        .map(|row| next_first + row),
+                    Err(e) => {
+                        warn!("Failed to fetch the next page: {}", e);
+                        None
+                    }
+                }
+            })
+            .or_else(|| {
+                items
+                    .iter()
+                    .take(current_row + 1)
+                    .position(matches)
+                    .map(|row| first + row)
+            });
+
+        if let Some(target) = target {
+            debug!("type-ahead '{}' target={}", c, target);
+            if target >= first + self.data_model.length as usize {
+                // in the next page: scroll so that the target is on the last row
+                let new_first = target + 1 - self.table_rows_count as usize;
+                let _ = self
+                    .data_model
+                    .update(new_first, self.table_rows_count, true);
             }
-            self.table_state.select(Some(next as usize));
-        } else {
-            debug!("no current row");
+            self.data_model.select(target);
         }
     }
 
@@ -307,25 +783,186 @@ impl<T: Clone + 'static> TableView<T> {
         confirmation_view: &Confirmation,
     ) -> ManagerAction {
         debug!("confirmation_callback={}", confirmation_view.is_yes());
-        if confirmation_view.is_yes()
-            && let Some(items) = &self.data_model.entries
-        {
-            let current_row = self.selected_row();
-            info!("deletion items at row='{:?}'", current_row);
-            (self.delete_fn)(&items[current_row.unwrap()]);
-            self.data_model.reload();
+        if confirmation_view.is_yes() {
+            info!("deletion items at row='{:?}'", self.selected());
+            self.dispatch(Action::Delete);
         }
         ManagerAction::new(true)
     }
 
+    /// Performs an action on the selected entry with the action function, then applies its
+    /// outcome. Without a selected entry, choosing exits without result and the other actions
+    /// do nothing.
+    fn dispatch(&mut self, action: Action) {
+        debug!("dispatch {:?}", action);
+        let exact_choice = match (&self.exact_choice_fn, &action) {
+            (Some(exact_choice_fn), Action::Choose) => exact_choice_fn(self.data_model.filter()),
+            _ => None,
+        };
+        let item = self
+            .data_model
+            .entries()
+            .and_then(|items| self.selected().and_then(|row| items.get(row)));
+        let outcome = match (exact_choice, item, &action) {
+            // the search text chooses the result, whatever the selected item
+            (Some(result), _, _) => Ok(ActionOutcome::Exit(Some(result))),
+            (None, Some(item), _) => (self.action_fn)(action.clone(), item),
+            (None, None, Action::Choose | Action::ChooseCanonical) => Ok(ActionOutcome::Exit(None)),
+            (None, None, _) => return,
+        };
+        self.apply_outcome(&action, outcome);
+    }
+
+    /// Applies the outcome of an action performed by the action function.
+    fn apply_outcome(&mut self, action: &Action, outcome: Result<ActionOutcome, rusqlite::Error>) {
+        match outcome {
+            Ok(ActionOutcome::Exit(result)) => {
+                debug!("send exit event");
+                let _ = self
+                    .tx
+                    .send(GenericEvent::ViewManagerEvent(ViewManagerEvent::Exit(
+                        result,
+                    )));
+            }
+            Ok(ActionOutcome::Refresh) => {
+                self.notify_refresh();
+                let result = self.data_model.reload();
+                self.report(result);
+                self.marked.clear();
+                self.clamp_selection();
+            }
+            Ok(ActionOutcome::Message(message)) => self.publish_footer_message(&message),
+            Err(e) => {
+                warn!("Failed to perform {:?}: {}", action, e);
+                self.publish_footer_message(action.failure_message());
+                // the entry may have been changed anyway
+                self.notify_refresh();
+                let _ = self.data_model.reload();
+                self.marked.clear();
+                self.clamp_selection();
+            }
+        }
+    }
+
+    /// Handle switching to the next sort mode: the list is shown again from its first entry.
+    fn handle_cycle_sort_mode(&mut self) {
+        debug!("handle_cycle_sort_mode");
+        if self.data_model.sort_mode.is_none() {
+            return;
+        }
+        let result = self.data_model.cycle_sort_mode(self.table_rows_count);
+        self.report(result);
+        self.marked.clear();
+        *self.table_state.offset_mut() = 0;
+    }
+
+    /// Handle listing the hidden items or not: the list is shown again from its first entry, as
+    /// the positions of the items change.
+    fn handle_toggle_include_hidden(&mut self) {
+        debug!("handle_toggle_include_hidden");
+        if self.data_model.include_hidden.is_none() {
+            return;
+        }
+        let result = self.data_model.toggle_include_hidden(self.table_rows_count);
+        self.report(result);
+        self.marked.clear();
+        *self.table_state.offset_mut() = 0;
+    }
+
+    /// Handle opening the selected item with the configured command. The terminal is handed
+    /// over to the command until it exits; a failure is reported in the footer.
+    fn handle_open(&mut self) {
+        debug!("handle_open");
+        let Some(path) = self.handle_chosen() else {
+            return;
+        };
+        let Some((program, args)) = Self::build_open_command(&self.open_command, &path) else {
+            warn!("Empty open command");
+            return;
+        };
+        let status = self
+            .vm
+            .suspend(|| Command::new(&program).args(&args).status());
+        if let Some(failure) = Self::open_failure(&program, status) {
+            warn!("Failed to open '{}': {}", path, failure);
+            self.publish_footer_message(&format!("{}: {}", OPEN_FAILED_MESSAGE, failure));
+        }
+        let _ = self
+            .tx
+            .send(GenericEvent::ViewManagerEvent(ViewManagerEvent::Redraw));
+    }
+
+    /// Describes the failure of the open command, if it could not be run or did not succeed.
+    ///
+    /// ### Returns
+    /// The error, e.g. `xdg-open exited with exit status: 4`, or `None` on success.
+    fn open_failure(program: &str, status: std::io::Result<ExitStatus>) -> Option<String> {
+        match status {
+            Ok(status) if status.success() => None,
+            Ok(status) => Some(format!("{} exited with {}", program, status)),
+            Err(e) => Some(format!("{}: {}", program, e)),
+        }
+    }
+
+    /// Reports the failure of a query in the footer, if any: the previous entries remain
+    /// visible.
+    fn report<R>(&self, result: Result<R, DataError>) {
+        if let Err(e) = result {
+            warn!("Failed to fetch the entries: {}", e);
+            self.publish_footer_message(DATABASE_ERROR_MESSAGE);
+        }
+    }
+
+    /// Shows a message (e.g. a failure) in the footer, until the list changes.
+    fn publish_footer_message(&self, message: &str) {
+        let _ = self
+            .tx
+            .send(GenericEvent::ApplicationEvent(ApplicationEvent {
+                id: String::from("footer.message"),
+                payload: Some(Arc::new(FooterMessagePayload {
+                    objects_type: self.data_model.objects_type().to_string(),
+                    message: String::from(message),
+                })),
+            }));
+    }
+
+    /// Builds the command opening a path from the command template: the `{}` arguments are
+    /// replaced by the path, which is appended if there is none.
+    ///
+    /// ### Returns
+    /// The program and its arguments, or `None` if the template is empty.
+    fn build_open_command(template: &str, path: &str) -> Option<(String, Vec<String>)> {
+        let mut words = template.split_whitespace();
+        let program = words.next()?.to_string();
+        let mut args: Vec<String> = words
+            .map(|word| word.replace(OPEN_COMMAND_PATH, path))
+            .collect();
+        if !template.contains(OPEN_COMMAND_PATH) {
+            args.push(path.to_string());
+        }
+        Some((program, args))
+    }
+
+    /// Handle showing the complete fields of the selected item in a popup.
+    fn handle_details(&mut self) {
+        debug!("handle_details");
+        if let Some(items) = self.data_model.entries()
+            && let Some(item) = self.selected().and_then(|row| items.get(row))
+        {
+            let vb = Details::builder((self.details_fn)(item), self.styles.clone());
+            self.vm.show_modal_generic(vb, None);
+        }
+    }
+
     /// Handle deleting the currently selected item.
     fn handle_delete(&mut self) {
         debug!("handle_delete");
-        if let Some(items) = &self.data_model.entries {
-            let current_row = self.selected_row();
+        if let Some(items) = self.data_model.entries()
+            && let Some(item) = self.selected().and_then(|row| items.get(row))
+        {
             let vb = Confirmation::builder(
-                String::from("Deletion of?\n")
-                    + (self.stringify)(&items[current_row.unwrap()]).as_str(),
+                String::from("Deletion of?\n") + (self.stringify)(item).as_str(),
+                self.confirm_default_yes,
                 self.styles.clone(),
             );
             self.vm
@@ -333,17 +970,184 @@ impl<T: Clone + 'static> TableView<T> {
         }
     }
 
-    fn modal_editor_callback(this: &mut dyn View, _: &dyn View) -> ManagerAction {
+    /// Changes the way the paths are displayed, and publishes the new display mode.
+    fn handle_toggle_display(&mut self, toggle: impl FnOnce(&mut TableViewState)) {
+        let payload = {
+            let mut view_state = self.view_state.lock().unwrap();
+            toggle(&mut view_state);
+            DisplayModePayload {
+                display_with_shortcuts: view_state.display_with_shortcuts,
+                display_with_home_tilde: view_state.display_with_home_tilde,
+            }
+        };
+        debug!(
+            "display with shortcuts={} home tilde={}",
+            payload.display_with_shortcuts, payload.display_with_home_tilde
+        );
+        if let Err(e) = self
+            .tx
+            .send(GenericEvent::ApplicationEvent(ApplicationEvent {
+                id: String::from("display.mode"),
+                payload: Some(Arc::new(payload)),
+            }))
+        {
+            error!("Failed to send 'display.mode' event: {}", e);
+        }
+    }
+
+    /// Handle marking the selected entry, or unmarking it if it is marked, then selects the next
+    /// one to mark several entries in a row.
+    fn handle_toggle_mark(&mut self) {
+        let Some(row) = self.selected() else {
+            return;
+        };
+        let Some(item) = self.data_model.entries().and_then(|items| items.get(row)) else {
+            return;
+        };
+        let position = self.data_model.first + row;
+        debug!("handle_toggle_mark position={}", position);
+        if self.marked.remove(&position).is_none() {
+            self.marked.insert(position, (self.stringify)(item));
+        }
+        self.handle_down(false, false);
+    }
+
+    /// Returns the text to copy: the marked entries in the order of the list, one per line, or
+    /// the selected entry if none is marked.
+    fn clipboard_text(&self) -> Option<String> {
+        let view_state = self.view_state.lock().unwrap();
+        let printed = |path: &String| {
+            printed_path(path, self.print_abbreviated, &view_state, self.home.clone())
+        };
+        if self.marked.is_empty() {
+            self.handle_chosen().map(|path| printed(&path))
+        } else {
+            Some(
+                self.marked
+                    .values()
+                    .map(printed)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        }
+    }
+
+    /// Handle copying the marked entries, or the selected one, to the clipboard.
+    fn handle_copy(&mut self) {
+        let Some(text) = self.clipboard_text() else {
+            return;
+        };
+        debug!("handle_copy text={}", text);
+        let message = match self.clipboard.copy(&text) {
+            Ok(()) if self.marked.len() > 1 => format!("{} copied", self.marked.len()),
+            Ok(()) => String::from("copied"),
+            Err(e) => {
+                warn!("Failed to copy to the clipboard: {}", e);
+                String::from(COPY_FAILED_MESSAGE)
+            }
+        };
+        self.publish_footer_message(&message);
+    }
+
+    /// Shows the last entries of the list and selects the last one, if the entries can be
+    /// counted.
+    fn handle_end(&mut self) {
+        debug!("handle_end");
+        let result = self.data_model.select_end(self.table_rows_count);
+        self.report(result);
+    }
+
+    /// Handle selecting the entry chosen the last time, if it is listed with the current search
+    /// text and order.
+    fn handle_select_last_choice(&mut self) {
+        debug!("handle_select_last_choice");
+        let Some(last_choice_fn) = &self.last_choice_fn else {
+            return;
+        };
+        let Some(last_choice) = last_choice_fn() else {
+            self.publish_footer_message(NO_LAST_CHOICE_MESSAGE);
+            return;
+        };
+        let stringify = &self.stringify;
+        let result = self
+            .data_model
+            .select_matching(self.table_rows_count, |item| stringify(item) == last_choice);
+        match result {
+            Ok(true) => *self.table_state.offset_mut() = 0,
+            Ok(false) => self.publish_footer_message(LAST_CHOICE_NOT_LISTED_MESSAGE),
+            Err(_) => self.report(result),
+        }
+    }
+
+    /// Reloads the entries from the database, e.g. the ones added by another shell. The selected
+    /// entry stays selected if it is still loaded, otherwise the selected row is kept.
+    fn handle_refresh(&mut self) {
+        debug!("handle_refresh");
+        self.notify_refresh();
+        let selected_key = self
+            .selected()
+            .and_then(|row| self.data_model.entries()?.get(row))
+            .map(|item| item.key());
+        // on error, the previous entries remain visible and the footer reports it
+        let result = self.data_model.refresh(self.table_rows_count);
+        if result.is_err() {
+            self.report(result);
+            return;
+        }
+        self.marked.clear();
+        if let Some(key) = selected_key
+            && let Some(row) = self
+                .data_model
+                .entries()
+                .and_then(|items| items.iter().position(|item| item.key() == key))
+        {
+            self.set_selected(Some(row));
+        } else if self.selected().is_none() && self.data_model.entries().is_some() {
+            // the list was empty
+            self.set_selected(Some(0));
+        } else {
+            self.clamp_selection();
+        }
+    }
+
+    /// Calls the refresh function, if any, before the items are loaded again.
+    fn notify_refresh(&self) {
+        if let Some(refresh_fn) = &self.refresh_fn {
+            refresh_fn();
+        }
+    }
+
+    fn modal_editor_callback(this: &mut dyn View, modal: &dyn View) -> ManagerAction {
         let this = (this as &mut dyn Any).downcast_mut::<Self>().unwrap();
-        this.data_model.reload();
+        if let Some(dialog) = (modal as &dyn Any).downcast_ref::<InputDialog>() {
+            let target = this.shortcut_target.take();
+            // the name of a new shortcut, unless cancelled
+            if let Some(name) = dialog.value()
+                && let Some(item) = target
+            {
+                let action = Action::CreateShortcut {
+                    name: name.to_string(),
+                    path: (this.stringify)(&item),
+                };
+                debug!("dispatch {:?}", action);
+                let outcome = (this.action_fn)(action.clone(), &item);
+                this.apply_outcome(&action, outcome);
+            }
+            return ManagerAction::new(true);
+        }
+        this.notify_refresh();
+        let result = this.data_model.reload();
+        this.report(result);
+        this.marked.clear();
+        this.clamp_selection();
         ManagerAction::new(true)
     }
 
-    fn handle_modal_event(&mut self) {
-        debug!("handle_modal_event");
+    fn handle_modal_event(&mut self, mode: EditMode) {
+        debug!("handle_modal_event {:?}", mode);
         let mut current_row: usize = 0;
-        if self.data_model.entries.is_some() {
-            current_row = match self.selected_row() {
+        if self.data_model.entries().is_some() {
+            current_row = match self.selected() {
                 Some(row) => row,
                 None => {
                     debug!("No row selected");
@@ -351,11 +1155,22 @@ impl<T: Clone + 'static> TableView<T> {
                 }
             };
         }
+        let Some(item) = self
+            .data_model
+            .entries()
+            .and_then(|items| items.get(current_row))
+            .cloned()
+        else {
+            return;
+        };
         if let Some(modal_view_builder) = &mut self.editor_modal_view_builder
-            && let Some(items) = &self.data_model.entries
+            && let Some(vb) = modal_view_builder(item.clone(), mode)
         {
+            if mode == EditMode::Shortcut {
+                // the shortcut goes to this entry, even if the list changes meanwhile
+                self.shortcut_target = Some(item);
+            }
             debug!("calling show_modal_generic");
-            let vb = modal_view_builder(items[current_row].clone());
             self.vm
                 .show_modal_generic(*vb, Some(Box::new(Self::modal_editor_callback)));
         }
@@ -408,6 +1223,23 @@ impl<T: Clone + 'static> TableView<T> {
         widths
     }
 
+    /// Returns the number of rows taken by the header.
+    fn header_length(&self) -> u16 {
+        if self.show_header {
+            TABLE_HEADER_LENGTH as u16
+        } else {
+            0
+        }
+    }
+
+    /// Returns the area of the table inside its borders, if any.
+    fn table_inner_area(&self, area: Rect) -> Rect {
+        match self.styles.table_border_type {
+            Some(_) => Block::bordered().inner(area),
+            None => area,
+        }
+    }
+
     /// Render a table with some rows and columns.
     pub fn render_table(&mut self, frame: &mut Frame, area: Rect) {
         debug!(
@@ -415,36 +1247,1512 @@ impl<T: Clone + 'static> TableView<T> {
             self.data_model.first, self.data_model.length
         );
 
+        // the optional columns are the last ones: the cells beyond the visible columns are
+        // not rendered
+        let visible_columns = if area.width < TABLE_NARROW_WIDTH {
+            self.column_constraints
+                .len()
+                .saturating_sub(self.optional_columns)
+        } else {
+            self.column_constraints.len()
+        };
+        let mut column_constraints = self.column_constraints[..visible_columns].to_vec();
+        if let Some((column, width_fn)) = &self.fit_column
+            && let Some(constraint) = column_constraints.get_mut(*column)
+            && let Some(entries) = self.data_model.entries()
+        {
+            *constraint = Constraint::Length(width_fn(entries));
+        }
+        let column_names = &self.column_names[..visible_columns.min(self.column_names.len())];
+
+        if let Some(border_type) = self.styles.table_border_type {
+            let mut block = Block::bordered().border_type(border_type);
+            if let Some(border_color) = self.styles.table_border_color {
+                block = block.border_style(Style::default().fg(border_color));
+            }
+            frame.render_widget(block, area);
+        }
+        let area = self.table_inner_area(area);
+        let highlight_symbol = self.styles.highlight_symbol.clone();
+
         let actual_width = Self::resolve_column_widths(
-            &self.column_constraints,
-            area.width - TABLE_HIGHLIGHT_SYMBOL.len() as u16 - TABLE_COLUMN_SPACING * 2,
+            &column_constraints,
+            area.width.saturating_sub(
+                Span::raw(&highlight_symbol).width() as u16 + TABLE_COLUMN_SPACING * 2,
+            ),
         );
         debug!("area widht={} col_width={:?}", area.width, actual_width);
 
-        let rows: Vec<Row> = self
+        let rows: Vec<Row> = match self
             .data_model
-            .entries
-            .as_ref()
-            .map_or(vec![], |entries| (self.rowify)(entries, &actual_width));
-
-        let table = Table::new(rows, self.column_constraints.clone())
-            .header(
-                Row::new(self.column_names.clone()).style(
-                    Style::new()
-                        .bg(self.styles.header_bg_color.unwrap())
-                        .fg(self.styles.header_fg_color.unwrap())
-                        .bold(),
-                ),
-            )
+            .entries()
+            .map_or(Ok(vec![]), |entries| (self.rowify)(entries, &actual_width))
+        {
+            Ok(rows) => rows
+                .into_iter()
+                .enumerate()
+                .map(|(row, cells)| {
+                    if self.marked.contains_key(&(self.data_model.first + row)) {
+                        cells.reversed()
+                    } else {
+                        cells
+                    }
+                })
+                .collect(),
+            Err(e) => {
+                // the next draw tries again
+                error!("Failed to build the rows: {}", e);
+                frame.render_widget(
+                    Paragraph::new(format!("{}: {}", DATABASE_ERROR_MESSAGE, e))
+                        .style(self.styles.text_em_style)
+                        .wrap(Wrap { trim: true }),
+                    area,
+                );
+                return;
+            }
+        };
+
+        let mut table = Table::new(rows, column_constraints);
+        if self.show_header {
+            table = table.header(Row::new(column_names.to_vec()).style(self.styles.header_style));
+        }
+        let table = table
             .column_spacing(TABLE_COLUMN_SPACING)
             .row_highlight_style(Style::new().bg(self.styles.highlight_color.unwrap()).bold())
-            .highlight_symbol(TABLE_HIGHLIGHT_SYMBOL);
+            .highlight_symbol(highlight_symbol);
 
-        if self.selected_row().is_none() && self.data_model.length > 0 {
-            self.table_state.select(Some(0));
+        if self.selected().is_none() && self.data_model.length > 0 {
+            self.set_selected(Some(0));
             debug!("No row selected: select 0")
         }
+        // the model keeps the selection, the table only shows it
+        self.table_state.select(self.selected());
 
         frame.render_stateful_widget(table, area, &mut self.table_state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend, style::Color};
+
+    use super::*;
+    use crate::{
+        model::{DataState, DataStatePayload},
+        store::{Path, Store},
+    };
+
+    /// Build a table view over `count` paths named "/0", "/1", ... (most recent first)
+    fn build_table_view(count: u64, rows: u16) -> TableView<Path> {
+        let store = Store::setup_test_store();
+        for i in 0..count {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+        }
+        build_table_view_over(store, rows)
+    }
+
+    fn build_table_view_over(store: Store, rows: u16) -> TableView<Path> {
+        let mut table_view = TableView::new(
+            Rc::new(ViewManager::new()),
+            "path".to_string(),
+            vec!["date".to_string(), "path".to_string(), "visits".to_string()],
+            vec![
+                Constraint::Length(20),
+                Constraint::Fill(1),
+                Constraint::Length(6),
+            ],
+            1,
+            None,
+            Box::new({
+                let store = store.clone();
+                move |pos, len, text, fuzzy, _, include_hidden| {
+                    Ok(store.list_paths(pos, len, text, fuzzy, include_hidden)?)
+                }
+            }),
+            Box::new(move |text, fuzzy, include_hidden| {
+                Ok(store.count_paths(text, fuzzy, include_hidden)?)
+            }),
+            None,
+            Some(false),
+            false,
+            Box::new(|paths: &[Path], _| {
+                Ok(paths
+                    .iter()
+                    .map(|path| {
+                        Row::new(vec![
+                            path.date.to_string(),
+                            path.path.clone(),
+                            format!("#{}", path.visits),
+                        ])
+                    })
+                    .collect())
+            }),
+            Box::new(|path: &Path| path.path.clone()),
+            Arc::new(Config::default()),
+            Arc::new(Mutex::new(TableViewState::new())),
+            Box::new(|action, path: &Path| match action {
+                Action::Choose => Ok(ActionOutcome::Exit(Some(path.path.clone()))),
+                Action::ChooseAndContinue => Ok(ActionOutcome::Message(path.path.clone())),
+                Action::ChooseCanonical => {
+                    Ok(ActionOutcome::Exit(Some(canonical_path(&path.path))))
+                }
+                Action::Delete | Action::ToggleHidden | Action::CreateShortcut { .. } => {
+                    Ok(ActionOutcome::Refresh)
+                }
+            }),
+            None,
+            Box::new(|path: &Path| vec![(String::from("path"), path.path.clone())]),
+        );
+        table_view.init();
+        table_view.resize(Rect::new(0, 0, 80, rows + TABLE_HEADER_LENGTH as u16));
+        table_view
+    }
+
+    fn press(table_view: &mut TableView<Path>, code: KeyCode, modifiers: KeyModifiers) {
+        table_view.handle_key_event(KeyEvent::new(code, modifiers));
+    }
+
+    #[test]
+    fn test_down_at_bottom_edge_keeps_selected() {
+        let mut table_view = build_table_view(15, 10);
+        for _ in 0..9 {
+            press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        }
+        assert_eq!(table_view.selected(), Some(9));
+        assert_eq!(table_view.handle_chosen(), Some("/9".to_string()));
+
+        // The window scrolls by one, the highlight stays on the last visual row
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 1);
+        assert_eq!(table_view.selected(), Some(9));
+        assert_eq!(table_view.handle_chosen(), Some("/10".to_string()));
+
+        // Scroll until the end of the data
+        for _ in 0..10 {
+            press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        }
+        assert_eq!(table_view.data_model.first, 5);
+        assert_eq!(table_view.selected(), Some(9));
+        assert_eq!(table_view.handle_chosen(), Some("/14".to_string()));
+    }
+
+    #[test]
+    fn test_ctrl_n_ctrl_p_navigation() {
+        let mut table_view = build_table_view(15, 10);
+        press(&mut table_view, KeyCode::Char('n'), KeyModifiers::CONTROL);
+        press(&mut table_view, KeyCode::Char('n'), KeyModifiers::CONTROL);
+        assert_eq!(table_view.handle_chosen(), Some("/2".to_string()));
+        press(&mut table_view, KeyCode::Char('p'), KeyModifiers::CONTROL);
+        assert_eq!(table_view.handle_chosen(), Some("/1".to_string()));
+
+        // Same as the arrow keys
+        press(&mut table_view, KeyCode::Up, KeyModifiers::NONE);
+        press(&mut table_view, KeyCode::Char('p'), KeyModifiers::CONTROL);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+    }
+
+    #[test]
+    fn test_page_down_keeps_the_same_visual_row() {
+        let mut table_view = build_table_view(25, 10);
+        for _ in 0..3 {
+            press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        }
+        // The bottom row "/9" is now at the top
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 9);
+        assert_eq!(table_view.selected(), Some(3));
+        assert_eq!(table_view.handle_chosen(), Some("/12".to_string()));
+
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 18);
+        assert_eq!(table_view.handle_chosen(), Some("/21".to_string()));
+
+        // Only 7 entries remain on the last page: the selection is clamped to the last one
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 18);
+        assert_eq!(table_view.handle_chosen(), Some("/24".to_string()));
+
+        // The top row "/18" is now at the bottom
+        press(&mut table_view, KeyCode::PageUp, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 9);
+        assert_eq!(table_view.selected(), Some(6));
+        assert_eq!(table_view.handle_chosen(), Some("/15".to_string()));
+    }
+
+    /// Returns the number of entries after the visible ones, from the last published payload.
+    fn published_more(rx: &mut broadcast::Receiver<GenericEvent>) -> Option<usize> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|event| match event {
+                GenericEvent::ApplicationEvent(ae) if ae.id == "data.payload" => ae
+                    .payload
+                    .and_then(|payload| payload.downcast_ref::<DataStatePayload>().map(|p| p.more)),
+                _ => None,
+            })
+            .last()
+            .expect("no data.payload")
+    }
+
+    #[test]
+    fn test_more_hint() {
+        let mut table_view = build_table_view(25, 10);
+        let mut rx = table_view.tx.subscribe();
+        table_view.data_model.set_more_hint();
+        table_view.resize(Rect::new(0, 0, 80, 10 + TABLE_HEADER_LENGTH as u16));
+        assert_eq!(published_more(&mut rx), Some(15));
+
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(published_more(&mut rx), Some(6));
+
+        // All the remaining entries fit
+        table_view.resize(Rect::new(0, 0, 80, 20 + TABLE_HEADER_LENGTH as u16));
+        assert_eq!(published_more(&mut rx), None);
+
+        // Filtered: the entries after the matching ones
+        search(&mut table_view, "1");
+        assert_eq!(table_view.data_model.count, Some(12));
+        table_view.resize(Rect::new(0, 0, 80, 5 + TABLE_HEADER_LENGTH as u16));
+        assert_eq!(published_more(&mut rx), Some(7));
+
+        // Not hinted
+        let mut table_view = build_table_view(25, 10);
+        let mut rx = table_view.tx.subscribe();
+        table_view.resize(Rect::new(0, 0, 80, 10 + TABLE_HEADER_LENGTH as u16));
+        assert_eq!(published_more(&mut rx), None);
+    }
+
+    /// A clipboard recording the copied texts
+    struct RecordingClipboard(Rc<std::cell::RefCell<Vec<String>>>);
+
+    impl Clipboard for RecordingClipboard {
+        fn copy(&self, text: &str) -> std::io::Result<()> {
+            self.0.borrow_mut().push(text.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_copy_marked() {
+        let mut table_view = build_table_view(25, 10);
+        let copied = Rc::new(std::cell::RefCell::new(vec![]));
+        table_view.clipboard = Box::new(RecordingClipboard(copied.clone()));
+
+        // Nothing marked: the selected entry
+        press(&mut table_view, KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(*copied.borrow(), vec!["/0"]);
+
+        // Marked out of order and across pages: in the order of the list
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        let selected = table_view.handle_chosen().unwrap();
+        press(&mut table_view, KeyCode::Char(' '), KeyModifiers::CONTROL);
+        press(&mut table_view, KeyCode::Home, KeyModifiers::NONE);
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        press(&mut table_view, KeyCode::Char(' '), KeyModifiers::CONTROL);
+        press(&mut table_view, KeyCode::Char(' '), KeyModifiers::CONTROL);
+        assert_eq!(table_view.handle_chosen(), Some("/3".to_string()));
+        press(&mut table_view, KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(
+            copied.borrow().last().unwrap(),
+            &format!("/1\n/2\n{}", selected)
+        );
+
+        // Unmarked
+        press(&mut table_view, KeyCode::Up, KeyModifiers::NONE);
+        press(&mut table_view, KeyCode::Char(' '), KeyModifiers::CONTROL);
+        press(&mut table_view, KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(
+            copied.borrow().last().unwrap(),
+            &format!("/1\n{}", selected)
+        );
+
+        // A new search clears the marks
+        search(&mut table_view, "2");
+        press(&mut table_view, KeyCode::Home, KeyModifiers::NONE);
+        press(&mut table_view, KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(copied.borrow().last().unwrap(), "/2");
+    }
+
+    #[test]
+    fn test_end() {
+        let mut table_view = build_table_view(25, 10);
+        press(&mut table_view, KeyCode::End, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 15);
+        assert_eq!(table_view.handle_chosen(), Some("/24".to_string()));
+
+        press(&mut table_view, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+
+        // Filtered: the last matching entry
+        search(&mut table_view, "1");
+        press(&mut table_view, KeyCode::End, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 2);
+        assert_eq!(table_view.handle_chosen(), Some("/21".to_string()));
+
+        // Fewer entries than rows
+        let mut table_view = build_table_view(3, 10);
+        press(&mut table_view, KeyCode::End, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 0);
+        assert_eq!(table_view.handle_chosen(), Some("/2".to_string()));
+    }
+
+    #[test]
+    fn test_page_overlap() {
+        // No overlap: a full page
+        let mut table_view = build_table_view(25, 10);
+        table_view.page_overlap = 0;
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 10);
+        assert_eq!(table_view.handle_chosen(), Some("/10".to_string()));
+
+        let mut table_view = build_table_view(25, 10);
+        table_view.page_overlap = 3;
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 7);
+        press(&mut table_view, KeyCode::PageUp, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 0);
+
+        // An overlap of the whole table still scrolls by one row
+        table_view.page_overlap = 10;
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 1);
+        assert_eq!(table_view.handle_chosen(), Some("/1".to_string()));
+    }
+
+    #[test]
+    fn test_home_page_down_up_sequence() {
+        let mut table_view = build_table_view(25, 10);
+        assert_eq!(table_view.selected(), Some(0));
+
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 9);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/9".to_string()));
+
+        // Up from the first row scrolls by one
+        press(&mut table_view, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 8);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/8".to_string()));
+
+        for _ in 0..3 {
+            press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        }
+        assert_eq!(table_view.selected(), Some(3));
+        assert_eq!(table_view.handle_chosen(), Some("/11".to_string()));
+
+        press(&mut table_view, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 0);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+
+        // Up at the top stays there
+        press(&mut table_view, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 0);
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 18);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/18".to_string()));
+
+        // The selection is made of a row only
+        assert_eq!(table_view.table_state.selected_column(), None);
+    }
+
+    #[test]
+    fn test_resize_keeps_selection_visible() {
+        let mut table_view = build_table_view(30, 10);
+        for _ in 0..8 {
+            press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        }
+        assert_eq!(table_view.handle_chosen(), Some("/8".to_string()));
+
+        // Shrink below the selection: the selected entry is now on the last row
+        table_view.resize(Rect::new(0, 0, 80, 5 + TABLE_HEADER_LENGTH as u16));
+        assert_eq!(table_view.data_model.first, 4);
+        assert_eq!(table_view.data_model.length, 5);
+        assert_eq!(table_view.selected(), Some(4));
+        assert_eq!(table_view.table_state.offset(), 0);
+        assert_eq!(table_view.handle_chosen(), Some("/8".to_string()));
+
+        // Grow again: the window keeps its first entry
+        table_view.resize(Rect::new(0, 0, 80, 10 + TABLE_HEADER_LENGTH as u16));
+        assert_eq!(table_view.data_model.first, 4);
+        assert_eq!(table_view.data_model.length, 10);
+        assert_eq!(table_view.selected(), Some(4));
+        assert_eq!(table_view.handle_chosen(), Some("/8".to_string()));
+
+        // Shrink while the selection is still visible: nothing moves
+        table_view.resize(Rect::new(0, 0, 80, 6 + TABLE_HEADER_LENGTH as u16));
+        assert_eq!(table_view.data_model.first, 4);
+        assert_eq!(table_view.selected(), Some(4));
+        assert_eq!(table_view.handle_chosen(), Some("/8".to_string()));
+
+        // Shrink to a single row
+        table_view.resize(Rect::new(0, 0, 80, 1 + TABLE_HEADER_LENGTH as u16));
+        assert_eq!(table_view.data_model.first, 8);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/8".to_string()));
+
+        // And navigation goes on from there
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/9".to_string()));
+    }
+
+    fn render(table_view: &mut TableView<Path>, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        table_view.resize(Rect::new(0, 0, width, height));
+        terminal
+            .draw(|frame| table_view.draw(frame, frame.area(), true))
+            .unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
+    #[test]
+    fn test_enter_after_filter_narrowed_the_list() {
+        let mut table_view = build_table_view(15, 10);
+        for _ in 0..9 {
+            press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        }
+        assert_eq!(table_view.selected(), Some(9));
+
+        // Only "/3" and "/13" match
+        search(&mut table_view, "3");
+        assert_eq!(table_view.data_model.length, 2);
+        assert_eq!(table_view.selected(), Some(1));
+        assert_eq!(table_view.handle_chosen(), Some("/13".to_string()));
+
+        // Nothing matches
+        search(&mut table_view, "nothing");
+        assert_eq!(table_view.selected(), None);
+        assert!(table_view.handle_chosen().is_none());
+        press(&mut table_view, KeyCode::Enter, KeyModifiers::NONE);
+    }
+
+    fn search(table_view: &mut TableView<Path>, text: &str) {
+        search_objects(table_view, text, None);
+    }
+
+    fn search_objects(table_view: &mut TableView<Path>, text: &str, objects_type: Option<&str>) {
+        table_view.handle_application_event(&ApplicationEvent {
+            id: String::from("search.description"),
+            payload: Some(Arc::new(SearchDescriptionPayload {
+                search_string: String::from(text),
+                fuzzy_match: false,
+                objects_type: objects_type.map(String::from),
+            })),
+        });
+    }
+
+    #[test]
+    fn test_search_of_another_view() {
+        let mut table_view = build_table_view(30, 10);
+
+        // the search of the other view is ignored
+        search_objects(&mut table_view, "12", Some("shortcut"));
+        assert_eq!(table_view.data_model.length, 10);
+
+        search_objects(&mut table_view, "12", Some("path"));
+        assert_eq!(table_view.data_model.length, 1);
+        search_objects(&mut table_view, "1", None);
+        assert_eq!(table_view.data_model.length, 10);
+        assert_eq!(table_view.view_state.lock().unwrap().match_text, "1");
+    }
+
+    #[test]
+    fn test_abbreviate_without_home() {
+        // HOME unset, empty or the root: the paths are unchanged
+        for home in [None, Some(""), Some("/"), Some("relative/home")] {
+            let home = usable_home(home, false);
+            assert_eq!(home, None);
+            assert_eq!(
+                abbreviate_home("/home/user/src", home.clone(), false),
+                "/home/user/src"
+            );
+            assert_eq!(abbreviate_home("/", home, false), "/");
+        }
+
+        let home = usable_home(Some("/home/user/"), false);
+        assert_eq!(home.as_deref(), Some("/home/user"));
+        assert_eq!(
+            abbreviate_home("/home/user/src", home.clone(), false),
+            "~/src"
+        );
+        assert_eq!(abbreviate_home("/home/user", home.clone(), false), "~");
+        assert_eq!(
+            abbreviate_home("/home/username", home, false),
+            "/home/username"
+        );
+    }
+
+    #[test]
+    fn test_abbreviate_windows_home() {
+        for home in [Some("C:\\"), Some("C:"), Some("Users\\user")] {
+            assert_eq!(usable_home(home, true), None);
+        }
+
+        let home = usable_home(Some("C:\\Users\\user\\"), true);
+        assert_eq!(home.as_deref(), Some("C:\\Users\\user"));
+        assert_eq!(
+            abbreviate_home("C:\\Users\\user\\src", home.clone(), true),
+            "~\\src"
+        );
+        // the separators and the case don't matter
+        assert_eq!(
+            abbreviate_home("c:/users/user/src", home.clone(), true),
+            "~/src"
+        );
+        assert_eq!(abbreviate_home("C:\\USERS\\USER", home.clone(), true), "~");
+        assert_eq!(
+            abbreviate_home("D:\\Users\\user", home.clone(), true),
+            "D:\\Users\\user"
+        );
+        // not on Unix
+        assert_eq!(
+            abbreviate_home(
+                "C:\\Users\\user\\src",
+                usable_home(Some("C:\\Users\\user"), false),
+                false
+            ),
+            "C:\\Users\\user\\src"
+        );
+    }
+
+    #[test]
+    fn test_printed_path() {
+        let home = || Some(String::from("/home/testuser"));
+        let mut view_state = TableViewState::new();
+        assert_eq!(
+            abbreviate_home("/home/testuser/work", home(), false),
+            "~/work"
+        );
+        assert_eq!(abbreviate_home("/home/testuser", home(), false), "~");
+        assert_eq!(
+            abbreviate_home("/home/testuser2", home(), false),
+            "/home/testuser2"
+        );
+
+        // the full path by default
+        assert_eq!(
+            printed_path("/home/testuser/work", false, &view_state, home()),
+            "/home/testuser/work"
+        );
+        assert_eq!(
+            printed_path("/home/testuser/work", true, &view_state, home()),
+            "~/work"
+        );
+        // as displayed
+        view_state.display_with_home_tilde = false;
+        assert_eq!(
+            printed_path("/home/testuser/work", true, &view_state, home()),
+            "/home/testuser/work"
+        );
+    }
+
+    #[test]
+    fn test_copy_abbreviated() {
+        let store = Store::setup_test_store();
+        store.add_path("/home/testuser/work").unwrap();
+        let mut table_view = build_table_view_over(store, 10);
+        table_view.home = Some(String::from("/home/testuser"));
+        let copied = Rc::new(std::cell::RefCell::new(vec![]));
+        table_view.clipboard = Box::new(RecordingClipboard(copied.clone()));
+
+        press(&mut table_view, KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(copied.borrow().last().unwrap(), "/home/testuser/work");
+
+        table_view.print_abbreviated = true;
+        press(&mut table_view, KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(copied.borrow().last().unwrap(), "~/work");
+        press(&mut table_view, KeyCode::Char(' '), KeyModifiers::CONTROL);
+        press(&mut table_view, KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(copied.borrow().last().unwrap(), "~/work");
+    }
+
+    #[test]
+    fn test_select_last_choice() {
+        let mut table_view = build_table_view(30, 10);
+        let mut rx = table_view.tx.subscribe();
+        let last_choice = Rc::new(std::cell::RefCell::new(None::<String>));
+        table_view.last_choice_fn = Some(Box::new({
+            let last_choice = last_choice.clone();
+            move || last_choice.borrow().clone()
+        }));
+        let selected_path = |table_view: &TableView<Path>| {
+            table_view
+                .selected()
+                .and_then(|row| table_view.data_model.entries()?.get(row))
+                .map(|path| path.path.clone())
+        };
+        let messages = |rx: &mut broadcast::Receiver<GenericEvent>| {
+            std::iter::from_fn(|| rx.try_recv().ok())
+                .filter_map(|event| match event {
+                    GenericEvent::ApplicationEvent(ae) => ae
+                        .payload?
+                        .downcast_ref::<FooterMessagePayload>()
+                        .map(|payload| payload.message.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        press(&mut table_view, KeyCode::Char('g'), KeyModifiers::CONTROL);
+        assert_eq!(messages(&mut rx), vec![NO_LAST_CHOICE_MESSAGE]);
+        assert_eq!(selected_path(&table_view).as_deref(), Some("/0"));
+
+        // the last choice is selected, even if it is not loaded
+        *last_choice.borrow_mut() = Some(String::from("/25"));
+        press(&mut table_view, KeyCode::Char('g'), KeyModifiers::CONTROL);
+        assert_eq!(selected_path(&table_view).as_deref(), Some("/25"));
+        *last_choice.borrow_mut() = Some(String::from("/3"));
+        press(&mut table_view, KeyCode::Char('g'), KeyModifiers::CONTROL);
+        assert_eq!(selected_path(&table_view).as_deref(), Some("/3"));
+        assert!(messages(&mut rx).is_empty());
+
+        // unless it is filtered out
+        search(&mut table_view, "2");
+        messages(&mut rx);
+        let selected = selected_path(&table_view);
+        press(&mut table_view, KeyCode::Char('g'), KeyModifiers::CONTROL);
+        assert_eq!(messages(&mut rx), vec![LAST_CHOICE_NOT_LISTED_MESSAGE]);
+        assert_eq!(selected_path(&table_view), selected);
+    }
+
+    #[test]
+    fn test_query_failure_in_footer() {
+        let mut table_view = build_table_view(30, 10);
+        let mut rx = table_view.tx.subscribe();
+        table_view.data_model.list_fn = Box::new(|_, _, _, _, _, _| Err("disk I/O error".into()));
+
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        let mut messages = vec![];
+        while let Ok(event) = rx.try_recv() {
+            if let GenericEvent::ApplicationEvent(ae) = event
+                && let Some(payload) = ae.payload
+                && let Some(payload) = payload.downcast_ref::<FooterMessagePayload>()
+            {
+                messages.push(payload.message.clone());
+            }
+        }
+        assert_eq!(messages, vec![DATABASE_ERROR_MESSAGE]);
+        // the previous entries remain
+        assert_eq!(table_view.data_model.first, 0);
+        assert_eq!(table_view.data_model.length, 10);
+    }
+
+    #[test]
+    fn test_one_query_per_keystroke() {
+        let mut table_view = build_table_view(100, 10);
+        let queries = Rc::new(std::cell::Cell::new(0));
+        let list_fn = std::mem::replace(
+            &mut table_view.data_model.list_fn,
+            Box::new(|_, _, _, _, _, _| Ok(vec![])),
+        );
+        table_view.data_model.list_fn = Box::new({
+            let queries = queries.clone();
+            move |pos, len, text, fuzzy, sort_mode, include_hidden| {
+                queries.set(queries.get() + 1);
+                list_fn(pos, len, text, fuzzy, sort_mode, include_hidden)
+            }
+        });
+        let count = |table_view: &mut TableView<Path>, keystroke: &dyn Fn(&mut TableView<Path>)| {
+            let before = queries.get();
+            keystroke(table_view);
+            // the view is drawn again after each event, resized to the same area
+            table_view.resize(Rect::new(0, 0, 80, 10 + TABLE_HEADER_LENGTH as u16));
+            queries.get() - before
+        };
+
+        assert_eq!(count(&mut table_view, &|tv| search(tv, "1")), 1);
+        assert_eq!(count(&mut table_view, &|tv| search(tv, "12")), 1);
+        // the same search again, e.g. from the other view
+        assert_eq!(count(&mut table_view, &|tv| search(tv, "12")), 0);
+        assert_eq!(count(&mut table_view, &|tv| search(tv, "")), 1);
+        assert_eq!(
+            count(&mut table_view, &|tv| press(
+                tv,
+                KeyCode::Down,
+                KeyModifiers::NONE
+            )),
+            0
+        );
+        assert_eq!(
+            count(&mut table_view, &|tv| press(
+                tv,
+                KeyCode::Home,
+                KeyModifiers::NONE
+            )),
+            0
+        );
+        assert_eq!(
+            count(&mut table_view, &|tv| press(
+                tv,
+                KeyCode::PageDown,
+                KeyModifiers::NONE
+            )),
+            1
+        );
+        assert_eq!(
+            count(&mut table_view, &|tv| press(
+                tv,
+                KeyCode::F(5),
+                KeyModifiers::NONE
+            )),
+            1
+        );
+    }
+
+    #[test]
+    fn test_search_string_at_launch() {
+        let store = Store::setup_test_store();
+        for i in 0..30 {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+        }
+        let mut table_view = build_table_view_over(store.clone(), 10);
+        table_view.search_string = Some(String::from("2"));
+        table_view.resize(Rect::new(0, 0, 80, 10 + TABLE_HEADER_LENGTH as u16));
+
+        // only the matching entries are listed from the start, highlighted
+        assert_eq!(table_view.data_model.count, Some(12));
+        assert_eq!(table_view.data_model.length, 10);
+        assert_eq!(table_view.handle_chosen(), Some("/2".to_string()));
+        assert_eq!(table_view.view_state.lock().unwrap().match_text, "2");
+
+        // the search runs once
+        search(&mut table_view, "");
+        table_view.resize(Rect::new(0, 0, 80, 10 + TABLE_HEADER_LENGTH as u16));
+        assert_eq!(table_view.data_model.count, None);
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+    }
+
+    #[test]
+    fn test_slow_search() {
+        let mut table_view = build_table_view(30, 10);
+        let mut rx = table_view.tx.subscribe();
+        // any query is slow
+        table_view.data_model.slow_query = std::time::Duration::ZERO;
+        search(&mut table_view, "1");
+        assert!(table_view.data_model.is_slow());
+        assert!(!table_view.data_model.is_searching());
+
+        // the next search is announced, and runs on the "search.run" event
+        search(&mut table_view, "12");
+        assert!(table_view.data_model.is_searching());
+        assert_eq!(table_view.data_model.length, 10);
+        let mut run = None;
+        while let Ok(event) = rx.try_recv() {
+            if let GenericEvent::ApplicationEvent(ae) = event
+                && ae.id == "search.run"
+            {
+                run = Some(ae);
+            }
+        }
+        // a search typed meanwhile replaces the announced one
+        search(&mut table_view, "13");
+        assert!(rx.try_recv().is_err());
+        table_view.handle_application_event(&run.unwrap());
+        assert!(!table_view.data_model.is_searching());
+        assert_eq!(table_view.data_model.length, 1);
+        assert_eq!(table_view.view_state.lock().unwrap().match_text, "13");
+    }
+
+    #[test]
+    fn test_navigation_after_filter_narrowed_the_list() {
+        let mut table_view = build_table_view(30, 10);
+        for _ in 0..15 {
+            press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        }
+        assert_eq!(table_view.data_model.first, 6);
+        assert_eq!(table_view.handle_chosen(), Some("/15".to_string()));
+
+        // "/1", "/10" ... "/19", "/21": the window goes back to the first page
+        search(&mut table_view, "1");
+        assert_eq!(table_view.data_model.first, 0);
+        assert_eq!(table_view.data_model.length, 10);
+        assert_eq!(table_view.table_state.offset(), 0);
+        assert_eq!(table_view.selected(), Some(9));
+        assert_eq!(table_view.handle_chosen(), Some("/18".to_string()));
+
+        // Down goes on from the clamped selection
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 1);
+        assert_eq!(table_view.selected(), Some(9));
+        assert_eq!(table_view.handle_chosen(), Some("/19".to_string()));
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/21".to_string()));
+
+        // At the end of the list, Down does nothing
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/21".to_string()));
+
+        // Narrow to fewer entries than rows
+        search(&mut table_view, "2");
+        assert_eq!(table_view.data_model.length, 10);
+        search(&mut table_view, "22");
+        assert_eq!(table_view.data_model.length, 1);
+        assert_eq!(table_view.selected(), Some(0));
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/22".to_string()));
+        press(&mut table_view, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/22".to_string()));
+    }
+
+    #[test]
+    fn test_min_query_len() {
+        let mut table_view = build_table_view(15, 10);
+        table_view.min_query_len = 2;
+
+        // Below the threshold, the list is not filtered
+        search(&mut table_view, "3");
+        assert_eq!(table_view.data_model.length, 10);
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+
+        // At the threshold, it is
+        search(&mut table_view, "13");
+        assert_eq!(table_view.data_model.length, 1);
+        assert_eq!(table_view.handle_chosen(), Some("/13".to_string()));
+
+        // And back below
+        search(&mut table_view, "1");
+        assert_eq!(table_view.data_model.length, 10);
+    }
+
+    #[test]
+    fn test_type_ahead() {
+        let mut table_view = build_table_view(15, 10);
+
+        // Not in type-ahead mode: nothing happens
+        press(&mut table_view, KeyCode::Char('5'), KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+
+        table_view.type_ahead = true;
+        press(&mut table_view, KeyCode::Char('5'), KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/5".to_string()));
+
+        // Repeated presses go to the next matches, fetching the next page
+        press(&mut table_view, KeyCode::Char('1'), KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/10".to_string()));
+        assert_eq!(table_view.data_model.first, 1);
+        assert_eq!(table_view.selected(), Some(9));
+        press(&mut table_view, KeyCode::Char('1'), KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/11".to_string()));
+        assert_eq!(table_view.data_model.first, 2);
+
+        // And cycle back to the top of the page when nothing else matches
+        press(&mut table_view, KeyCode::Char('3'), KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/3".to_string()));
+        assert_eq!(table_view.data_model.first, 2);
+        assert_eq!(table_view.selected(), Some(1));
+        press(&mut table_view, KeyCode::Char('3'), KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/3".to_string()));
+
+        // No match at all
+        press(&mut table_view, KeyCode::Char('x'), KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/3".to_string()));
+    }
+
+    #[test]
+    fn test_last_component_starts_with() {
+        assert!(TableView::<Path>::last_component_starts_with(
+            "/home/user/src",
+            's'
+        ));
+        assert!(TableView::<Path>::last_component_starts_with(
+            "/home/user/Src",
+            's'
+        ));
+        assert!(TableView::<Path>::last_component_starts_with(
+            "/home/user/src",
+            'S'
+        ));
+        assert!(!TableView::<Path>::last_component_starts_with(
+            "/home/user/src",
+            'h'
+        ));
+        assert!(TableView::<Path>::last_component_starts_with("/", '/'));
+        assert!(!TableView::<Path>::last_component_starts_with("", 'a'));
+    }
+
+    #[test]
+    fn test_tiny_terminal() {
+        let mut table_view = build_table_view(5, 10);
+
+        // No room for any row below the header
+        for height in [0, 1] {
+            table_view.resize(Rect::new(0, 0, 80, height));
+            assert_eq!(table_view.table_rows_count, 0);
+            assert!(table_view.handle_chosen().is_none());
+            press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+            press(&mut table_view, KeyCode::PageUp, KeyModifiers::NONE);
+        }
+        assert!(render(&mut table_view, 80, 1).starts_with(TOO_SMALL_MESSAGE));
+
+        // Too narrow
+        assert!(render(&mut table_view, 10, 5).starts_with("terminal t"));
+        assert!(render(&mut table_view, 0, 5).is_empty());
+
+        // Back to a usable size, the optional column is hidden while narrow
+        let content = render(&mut table_view, 40, 5);
+        assert!(!content.contains(TOO_SMALL_MESSAGE));
+        assert!(content.contains("/0"));
+        assert!(!content.contains("visits"));
+        assert!(!content.contains("#1"));
+        let content = render(&mut table_view, 80, 5);
+        assert!(content.contains("visits"));
+        assert!(content.contains("#1"));
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+    }
+
+    #[test]
+    fn test_build_open_command() {
+        let build = |template: &str| TableView::<Path>::build_open_command(template, "/my dir");
+        assert_eq!(
+            build("xdg-open {}"),
+            Some((String::from("xdg-open"), vec![String::from("/my dir")]))
+        );
+        assert_eq!(
+            build("code --new-window {}"),
+            Some((
+                String::from("code"),
+                vec![String::from("--new-window"), String::from("/my dir")]
+            ))
+        );
+        // the path is appended when not placed
+        assert_eq!(
+            build("nautilus"),
+            Some((String::from("nautilus"), vec![String::from("/my dir")]))
+        );
+        assert_eq!(build("  "), None);
+    }
+
+    #[test]
+    fn test_open_failure() {
+        let failure = |program: &str| {
+            TableView::<Path>::open_failure(program, Command::new(program).status())
+        };
+        assert_eq!(failure("true"), None);
+        assert_eq!(
+            failure("false"),
+            Some(String::from("false exited with exit status: 1"))
+        );
+        let not_found = failure("cdir-no-such-program").unwrap();
+        assert!(
+            not_found.starts_with("cdir-no-such-program: No such file or directory"),
+            "{}",
+            not_found
+        );
+    }
+
+    #[test]
+    fn test_yank_canonical_path() {
+        let dir = std::env::temp_dir().join(format!("cdir_yank_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("real")).unwrap();
+        std::os::unix::fs::symlink(dir.join("real"), dir.join("link")).unwrap();
+        let link = format!("{}/link/.", dir.to_string_lossy());
+
+        let store = Store::setup_test_store();
+        store.add_path(&link).unwrap();
+        // the row is displayed with the shortcut name
+        store.add_shortcut("lnk", &link, None).unwrap();
+        let mut table_view = build_table_view_over(store, 10);
+        assert!(table_view.view_state.lock().unwrap().display_with_shortcuts);
+
+        let mut rx = table_view.tx.subscribe();
+        press(&mut table_view, KeyCode::Char('y'), KeyModifiers::CONTROL);
+        let expected = std::fs::canonicalize(dir.join("real")).unwrap();
+        match rx.try_recv() {
+            Ok(GenericEvent::ViewManagerEvent(ViewManagerEvent::Exit(Some(path)))) => {
+                assert_eq!(path, expected.to_string_lossy())
+            }
+            _ => panic!("no exit event"),
+        }
+
+        // a missing path is returned as is
+        assert_eq!(canonical_path("/does/not/exist"), "/does/not/exist");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_editor_modes() {
+        let mut table_view = build_table_view(3, 10);
+        let modes = Rc::new(std::cell::RefCell::new(vec![]));
+        let styles = table_view.styles.clone();
+        table_view.editor_modal_view_builder = Some(Box::new({
+            let modes = modes.clone();
+            move |path: Path, mode| {
+                modes.borrow_mut().push((path.path, mode));
+                Some(Box::new(Confirmation::builder(
+                    String::from("edit"),
+                    false,
+                    styles.clone(),
+                )))
+            }
+        }));
+
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        press(&mut table_view, KeyCode::Char('e'), KeyModifiers::CONTROL);
+        press(&mut table_view, KeyCode::Char('l'), KeyModifiers::CONTROL);
+        assert_eq!(
+            *modes.borrow(),
+            vec![
+                (String::from("/1"), EditMode::Fields),
+                (String::from("/1"), EditMode::Path)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_refresh() {
+        let store = Store::setup_test_store();
+        for i in 0..3 {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+        }
+        let mut table_view = build_table_view_over(store.clone(), 10);
+        let refreshes = Rc::new(std::cell::Cell::new(0));
+        table_view.refresh_fn = Some(Box::new({
+            let refreshes = refreshes.clone();
+            move || refreshes.set(refreshes.get() + 1)
+        }));
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(table_view.handle_chosen(), Some("/1".to_string()));
+        assert_eq!(refreshes.get(), 0);
+
+        // the paths added by another shell are listed, and the selected one stays selected
+        store.add_path_with_time("/new", 2000).unwrap();
+        press(&mut table_view, KeyCode::F(5), KeyModifiers::NONE);
+        assert_eq!(refreshes.get(), 1);
+        assert_eq!(table_view.data_model.length, 4);
+        assert_eq!(table_view.selected(), Some(2));
+        assert_eq!(table_view.handle_chosen(), Some("/1".to_string()));
+
+        // the selected path was visited again, with another id: it stays selected at the top
+        let id = table_view.data_model.entries().unwrap()[2].id;
+        store.add_path_with_time("/1", 3000).unwrap();
+        press(&mut table_view, KeyCode::F(5), KeyModifiers::NONE);
+        assert_ne!(table_view.data_model.entries().unwrap()[0].id, id);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/1".to_string()));
+
+        // the selected path was deleted: the row is kept
+        let id = table_view.data_model.entries().unwrap()[0].id;
+        store.delete_path_by_id(id).unwrap();
+        press(&mut table_view, KeyCode::F(5), KeyModifiers::NONE);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/new".to_string()));
+    }
+
+    #[test]
+    fn test_refresh_event() {
+        let store = Store::setup_test_store();
+        for i in 0..3 {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+        }
+        let mut table_view = build_table_view_over(store.clone(), 10);
+        let refreshes = Rc::new(std::cell::Cell::new(0));
+        table_view.refresh_fn = Some(Box::new({
+            let refreshes = refreshes.clone();
+            move || refreshes.set(refreshes.get() + 1)
+        }));
+
+        // in watch mode, the new paths appear at the top without a key
+        store.add_path_with_time("/new", 2000).unwrap();
+        table_view.handle_application_event(&ApplicationEvent {
+            id: String::from("data.refresh"),
+            payload: None,
+        });
+        assert_eq!(refreshes.get(), 1);
+        assert_eq!(table_view.data_model.length, 4);
+        assert_eq!(table_view.selected(), Some(1));
+        assert_eq!(table_view.data_model.entries().unwrap()[0].path, "/new");
+    }
+
+    #[test]
+    fn test_toggle_display() {
+        let mut table_view = build_table_view(3, 10);
+        let mut rx = table_view.tx.subscribe();
+        let mut published_mode = |table_view: &mut TableView<Path>, c: char| {
+            press(table_view, KeyCode::Char(c), KeyModifiers::CONTROL);
+            match rx.try_recv() {
+                Ok(GenericEvent::ApplicationEvent(ae)) => {
+                    let payload = ae.payload.unwrap();
+                    let payload = payload.downcast_ref::<DisplayModePayload>().unwrap();
+                    (
+                        payload.display_with_shortcuts,
+                        payload.display_with_home_tilde,
+                    )
+                }
+                _ => panic!("no display mode"),
+            }
+        };
+
+        // the home substitution is toggled independently of the shortcuts one
+        assert_eq!(published_mode(&mut table_view, 'u'), (true, false));
+        assert_eq!(published_mode(&mut table_view, 'a'), (false, false));
+        assert_eq!(published_mode(&mut table_view, 'u'), (false, true));
+        let view_state = table_view.view_state.lock().unwrap();
+        assert!(!view_state.display_with_shortcuts);
+        assert!(view_state.display_with_home_tilde);
+    }
+
+    /// Returns the result of the exit event sent, if any.
+    fn exit_result(rx: &mut broadcast::Receiver<GenericEvent>) -> Option<Option<String>> {
+        std::iter::from_fn(|| rx.try_recv().ok()).find_map(|event| match event {
+            GenericEvent::ViewManagerEvent(ViewManagerEvent::Exit(result)) => Some(result),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_capturing_stringify() {
+        let mut table_view = build_table_view(30, 10);
+        // the string of an entry depends on the context captured, e.g. the known mount points
+        let mounts = Rc::new(std::cell::RefCell::new(vec![(String::from("/1"), "usb")]));
+        table_view.stringify = Box::new({
+            let mounts = mounts.clone();
+            move |path: &Path| {
+                let mounts = mounts.borrow();
+                match mounts
+                    .iter()
+                    .find(|(point, _)| path.path.starts_with(point))
+                {
+                    Some((point, name)) => format!("{}:{}", name, &path.path[point.len()..]),
+                    None => path.path.clone(),
+                }
+            }
+        });
+        assert_eq!(table_view.handle_chosen().as_deref(), Some("/0"));
+
+        // marked with the context at the time
+        press(&mut table_view, KeyCode::Char(' '), KeyModifiers::CONTROL);
+        press(&mut table_view, KeyCode::Char(' '), KeyModifiers::CONTROL);
+        assert_eq!(table_view.clipboard_text().as_deref(), Some("/0\nusb:"));
+
+        mounts.borrow_mut().push((String::from("/2"), "nfs"));
+        assert_eq!(table_view.handle_chosen().as_deref(), Some("nfs:"));
+    }
+
+    #[test]
+    fn test_exact_choice() {
+        let mut table_view = build_table_view(30, 10);
+        let mut rx = table_view.tx.subscribe();
+        table_view.exact_choice_fn = Some(Box::new(|search_string: &str| {
+            (search_string == "docs").then(|| String::from("/home/user/docs"))
+        }));
+
+        // the selected entry is chosen
+        search(&mut table_view, "1");
+        press(&mut table_view, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(exit_result(&mut rx), Some(Some(String::from("/1"))));
+
+        // unless the search text names a shortcut: its path is chosen, even if nothing matches
+        search(&mut table_view, "docs");
+        assert!(table_view.data_model.entries().is_none());
+        press(&mut table_view, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(
+            exit_result(&mut rx),
+            Some(Some(String::from("/home/user/docs")))
+        );
+
+        // only with Enter
+        table_view.dispatch(Action::ChooseAndContinue);
+        assert_eq!(exit_result(&mut rx), None);
+    }
+
+    #[test]
+    fn test_create_shortcut() {
+        let mut table_view = build_table_view(5, 10);
+        let performed = Arc::new(Mutex::new(vec![]));
+        table_view.action_fn = {
+            let performed = performed.clone();
+            Box::new(move |action, path: &Path| {
+                performed
+                    .lock()
+                    .unwrap()
+                    .push((action.clone(), path.path.clone()));
+                Ok(ActionOutcome::Message(String::from("created")))
+            })
+        };
+        table_view.editor_modal_view_builder = Some(Box::new(|path: Path, mode| {
+            (mode == EditMode::Shortcut).then(|| {
+                Box::new(InputDialog::builder(
+                    "New shortcut",
+                    &path.path,
+                    "",
+                    Box::new(|name: &str| Ok(name.to_string())),
+                    Config::default().styles,
+                ))
+            })
+        }));
+        let dialog = |name: &str| {
+            let mut dialog = InputDialog::new(
+                "New shortcut",
+                "Name",
+                name,
+                Box::new(|name: &str| Ok(name.to_string())),
+                Config::default().styles,
+            );
+            dialog.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+            dialog
+        };
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        press(&mut table_view, KeyCode::Char('b'), KeyModifiers::CONTROL);
+
+        // the shortcut goes to the entry of the dialog, even if the selection moved meanwhile
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        TableView::<Path>::modal_editor_callback(&mut table_view, &dialog("one"));
+        assert_eq!(
+            *performed.lock().unwrap(),
+            vec![(
+                Action::CreateShortcut {
+                    name: String::from("one"),
+                    path: String::from("/1")
+                },
+                String::from("/1")
+            )]
+        );
+
+        // once
+        TableView::<Path>::modal_editor_callback(&mut table_view, &dialog("two"));
+        assert_eq!(performed.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let mut table_view = build_table_view(5, 10);
+        let mut rx = table_view.tx.subscribe();
+        let performed = Arc::new(Mutex::new(vec![]));
+        table_view.action_fn = {
+            let performed = performed.clone();
+            Box::new(move |action, path: &Path| {
+                performed
+                    .lock()
+                    .unwrap()
+                    .push((action.clone(), path.path.clone()));
+                match action {
+                    Action::Choose => Ok(ActionOutcome::Message(String::from("chosen"))),
+                    Action::ChooseAndContinue => Ok(ActionOutcome::Message(String::from("sent"))),
+                    Action::ChooseCanonical => {
+                        Ok(ActionOutcome::Message(String::from("canonical")))
+                    }
+                    Action::CreateShortcut { name, .. } => Ok(ActionOutcome::Message(name)),
+                    Action::Delete | Action::ToggleHidden => Err(rusqlite::Error::InvalidQuery),
+                }
+            })
+        };
+        press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+
+        // the action is performed on the selected entry, its outcome is applied
+        press(&mut table_view, KeyCode::Enter, KeyModifiers::NONE);
+        table_view.dispatch(Action::Delete);
+        press(&mut table_view, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        press(&mut table_view, KeyCode::Char('y'), KeyModifiers::CONTROL);
+        table_view.dispatch(Action::CreateShortcut {
+            name: String::from("one"),
+            path: String::from("/1"),
+        });
+        assert_eq!(
+            *performed.lock().unwrap(),
+            vec![
+                (Action::Choose, String::from("/1")),
+                (Action::Delete, String::from("/1")),
+                (Action::ChooseAndContinue, String::from("/1")),
+                (Action::ChooseCanonical, String::from("/1")),
+                (
+                    Action::CreateShortcut {
+                        name: String::from("one"),
+                        path: String::from("/1")
+                    },
+                    String::from("/1")
+                )
+            ]
+        );
+        let mut messages = vec![];
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                GenericEvent::ApplicationEvent(ae) => {
+                    if let Some(payload) = &ae.payload
+                        && let Some(payload) = payload.downcast_ref::<FooterMessagePayload>()
+                    {
+                        messages.push(payload.message.clone());
+                    }
+                }
+                // the GUI stays open
+                GenericEvent::ViewManagerEvent(ViewManagerEvent::Exit(_)) => panic!("exit"),
+                GenericEvent::ViewManagerEvent(_) => {}
+            }
+        }
+        assert_eq!(
+            messages,
+            vec!["chosen", DELETE_FAILED_MESSAGE, "sent", "canonical", "one"]
+        );
+
+        // without selection, choosing exits without result
+        search(&mut table_view, "nothing");
+        table_view.dispatch(Action::Delete);
+        table_view.dispatch(Action::ChooseAndContinue);
+        press(&mut table_view, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(performed.lock().unwrap().len(), 5);
+        let exit = std::iter::from_fn(|| rx.try_recv().ok()).find_map(|event| match event {
+            GenericEvent::ViewManagerEvent(ViewManagerEvent::Exit(result)) => Some(result),
+            _ => None,
+        });
+        assert_eq!(exit, Some(None));
+    }
+
+    #[test]
+    fn test_toggle_include_hidden() {
+        let store = Store::setup_test_store();
+        for i in 0..30 {
+            store
+                .add_path_with_time(&format!("/{}", i), 1000 - i)
+                .unwrap();
+        }
+        for path in store.list_paths(0, 5, "", false, false).unwrap() {
+            store.set_path_hidden(path.id, true).unwrap();
+        }
+        let mut table_view = build_table_view_over(store, 10);
+        assert_eq!(table_view.handle_chosen(), Some("/5".to_string()));
+        press(&mut table_view, KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 9);
+
+        // the hidden paths are listed from the first page
+        press(&mut table_view, KeyCode::Char('t'), KeyModifiers::CONTROL);
+        assert_eq!(table_view.data_model.include_hidden, Some(true));
+        assert_eq!(table_view.data_model.first, 0);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+
+        press(&mut table_view, KeyCode::Char('t'), KeyModifiers::CONTROL);
+        assert_eq!(table_view.data_model.include_hidden, Some(false));
+        assert_eq!(table_view.handle_chosen(), Some("/5".to_string()));
+    }
+
+    #[test]
+    fn test_header() {
+        let mut table_view = build_table_view(20, 10);
+        table_view.styles.header_style = Style::new().fg(Color::Red).bg(Color::Green);
+        let mut terminal = Terminal::new(TestBackend::new(40, 8)).unwrap();
+        table_view.resize(Rect::new(0, 0, 40, 8));
+        terminal
+            .draw(|frame| table_view.draw(frame, frame.area(), true))
+            .unwrap();
+        assert_eq!(table_view.data_model.length, 7);
+        let cell = &terminal.backend().buffer()[(3, 0)];
+        assert_eq!(cell.fg, Color::Red);
+        assert_eq!(cell.bg, Color::Green);
+
+        // without header, one more entry is visible
+        table_view.show_header = false;
+        let content = render(&mut table_view, 40, 8);
+        assert_eq!(table_view.data_model.length, 8);
+        assert!(!content.contains("date"));
+        assert!(content.contains("/7"));
+    }
+
+    #[test]
+    fn test_fit_column() {
+        let mut table_view = build_table_view(10, 10);
+        let path_column = |content: String| content[..60].find("path").unwrap();
+        let fixed = path_column(render(&mut table_view, 60, 5));
+
+        // the date column fits the loaded dates ("1000", "999", ...) instead of 20 cells
+        table_view.fit_column = Some((
+            0,
+            Box::new(|paths: &[Path]| {
+                paths
+                    .iter()
+                    .map(|path| path.date.to_string().len() as u16)
+                    .max()
+                    .unwrap()
+            }),
+        ));
+        let fitted = path_column(render(&mut table_view, 60, 5));
+        assert_eq!(fixed - fitted, 16);
+    }
+
+    #[test]
+    fn test_table_borders() {
+        let mut table_view = build_table_view(10, 10);
+        table_view.styles.table_border_type = Some(ratatui::widgets::BorderType::Rounded);
+        table_view.styles.highlight_symbol = String::from("* ");
+
+        let content = render(&mut table_view, 40, 8);
+        assert!(content.starts_with('╭'));
+        assert!(content.contains("│* "));
+        // the borders take two rows
+        assert_eq!(table_view.data_model.length, 5);
+    }
+
+    #[test]
+    fn test_rows_error() {
+        let mut table_view = build_table_view(3, 5);
+        table_view.rowify = Box::new(|_, _| Err(rusqlite::Error::InvalidQuery));
+        // a banner instead of the rows
+        assert!(render(&mut table_view, 80, 6).starts_with(DATABASE_ERROR_MESSAGE));
+    }
+
+    #[test]
+    fn test_loading() {
+        let mut table_view = build_table_view(3, 5);
+        // no entry received yet
+        table_view.data_model.data = DataState::Loading;
+        let mut terminal = Terminal::new(TestBackend::new(80, 6)).unwrap();
+        terminal
+            .draw(|frame| table_view.draw(frame, frame.area(), true))
+            .unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(content.starts_with(LOADING_MESSAGE));
+
+        // once received, the table is shown, even when empty
+        search(&mut table_view, "nothing");
+        assert!(!table_view.data_model.is_loading());
+        assert!(render(&mut table_view, 80, 6).starts_with("date"));
+    }
+
+    #[test]
+    fn test_up_at_top_edge_keeps_selected() {
+        let mut table_view = build_table_view(15, 10);
+        for _ in 0..12 {
+            press(&mut table_view, KeyCode::Down, KeyModifiers::NONE);
+        }
+        assert_eq!(table_view.data_model.first, 3);
+        for _ in 0..9 {
+            press(&mut table_view, KeyCode::Up, KeyModifiers::NONE);
+        }
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/3".to_string()));
+
+        press(&mut table_view, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(table_view.data_model.first, 2);
+        assert_eq!(table_view.selected(), Some(0));
+        assert_eq!(table_view.handle_chosen(), Some("/2".to_string()));
+
+        // A jump near the top stops on the first entry
+        press(&mut table_view, KeyCode::Up, KeyModifiers::SHIFT);
+        assert_eq!(table_view.data_model.first, 0);
+        assert_eq!(table_view.handle_chosen(), Some("/0".to_string()));
+    }
+}
@@ -1,4 +1,10 @@
-use ratatui::style::{Color, Modifier, Style};
+use std::collections::HashMap;
+
+use log::warn;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    widgets::BorderType,
+};
 use serde::{Deserialize, Serialize};
 
 const DEFAULT_TITLE: fn() -> Option<String> = || Some(String::from("#1d5cba"));
@@ -14,6 +20,11 @@ const DEFAULT_COLOR_SHORTCUT_NAME: fn() -> Option<String> = || Some(String::from
 
 const DEFAULT_COLOR_FG_HEADER: fn() -> Option<String> = || Some(String::from("#ffffff"));
 const DEFAULT_COLOR_BG_HEADER: fn() -> Option<String> = || Some(String::from("#2741b7"));
+const DEFAULT_HEADER_BOLD: fn() -> Option<bool> = || Some(true);
+
+const DEFAULT_COLOR_MATCH: fn() -> Option<String> = || Some(String::from("#d7005f"));
+
+const DEFAULT_HIGHLIGHT_SYMBOL: fn() -> Option<String> = || Some(String::from("> "));
 
 const DEFAULT_COLOR_DESCRIPTION: fn() -> Option<String> = || Some(String::from("#808080"));
 
@@ -21,6 +32,9 @@ const DEFAULT_FREE_TEXT_AREA_BG: fn() -> Option<String> = || Some(String::from("
 
 const DEFAULT_HOME_TILD: fn() -> Option<String> = || Some(String::from("#888888"));
 
+const DEFAULT_COLOR_FG_EMPTY_MESSAGE: fn() -> Option<String> = || Some(String::from("black"));
+const DEFAULT_COLOR_BG_EMPTY_MESSAGE: fn() -> Option<String> = || Some(String::from("red"));
+
 const DEFAULT_NONE: fn() -> Option<String> = || None;
 const DEFAULT_BOOL_NONE: fn() -> Option<bool> = || None;
 
@@ -84,6 +98,9 @@ pub struct Theme {
     #[serde(default = "DEFAULT_NONE")]
     pub highlight: Option<String>,
 
+    #[serde(default = "DEFAULT_NONE", rename = "match")]
+    pub match_color: Option<String>,
+
     #[serde(default = "DEFAULT_NONE")]
     pub shortcut_name: Option<String>,
 
@@ -99,6 +116,18 @@ pub struct Theme {
     #[serde(default = "DEFAULT_NONE")]
     pub header_bg: Option<String>,
 
+    #[serde(default = "DEFAULT_BOOL_NONE")]
+    pub header_bold: Option<bool>,
+
+    #[serde(default = "DEFAULT_NONE")]
+    pub highlight_symbol: Option<String>,
+
+    #[serde(default = "DEFAULT_NONE")]
+    pub table_border_type: Option<String>,
+
+    #[serde(default = "DEFAULT_NONE")]
+    pub table_border: Option<String>,
+
     #[serde(default = "DEFAULT_NONE")]
     pub description: Option<String>,
 
@@ -119,6 +148,15 @@ pub struct Theme {
 
     #[serde(default = "DEFAULT_BOOL_NONE")]
     pub home_tilde_italic: Option<bool>,
+
+    #[serde(default = "DEFAULT_NONE")]
+    pub empty_message_fg: Option<String>,
+
+    #[serde(default = "DEFAULT_NONE")]
+    pub empty_message_bg: Option<String>,
+
+    #[serde(default = "DEFAULT_NONE")]
+    pub accent: Option<String>,
 }
 
 impl Default for Theme {
@@ -143,11 +181,16 @@ impl Default for Theme {
             path_bold: DEFAULT_BOOL_NONE(),
             path_italic: DEFAULT_BOOL_NONE(),
             highlight: DEFAULT_COLOR_HIGHLIGHT(),
+            match_color: DEFAULT_COLOR_MATCH(),
             shortcut_name: DEFAULT_COLOR_SHORTCUT_NAME(),
             shortcut_name_bold: DEFAULT_BOOL_NONE(),
             shortcut_name_italic: DEFAULT_BOOL_NONE(),
             header_fg: DEFAULT_COLOR_FG_HEADER(),
             header_bg: DEFAULT_COLOR_BG_HEADER(),
+            header_bold: DEFAULT_HEADER_BOLD(),
+            highlight_symbol: DEFAULT_HIGHLIGHT_SYMBOL(),
+            table_border_type: DEFAULT_NONE(),
+            table_border: DEFAULT_NONE(),
             description: DEFAULT_COLOR_DESCRIPTION(),
             description_bold: DEFAULT_BOOL_NONE(),
             description_italic: DEFAULT_BOOL_NONE(),
@@ -155,6 +198,9 @@ impl Default for Theme {
             home_tilde: DEFAULT_HOME_TILD(),
             home_tilde_bold: DEFAULT_BOOL_NONE(),
             home_tilde_italic: DEFAULT_BOOL_NONE(),
+            empty_message_fg: DEFAULT_COLOR_FG_EMPTY_MESSAGE(),
+            empty_message_bg: DEFAULT_COLOR_BG_EMPTY_MESSAGE(),
+            accent: DEFAULT_NONE(),
         }
     }
 }
@@ -232,6 +278,11 @@ impl Theme {
                 .clone()
                 .or(theme.highlight.clone())
                 .or(DEFAULT_COLOR_HIGHLIGHT()),
+            match_color: self
+                .match_color
+                .clone()
+                .or(theme.match_color.clone())
+                .or(DEFAULT_COLOR_MATCH()),
             shortcut_name: self
                 .shortcut_name
                 .clone()
@@ -255,6 +306,25 @@ impl Theme {
                 .clone()
                 .or(theme.header_bg.clone())
                 .or(DEFAULT_COLOR_BG_HEADER()),
+            header_bold: self
+                .header_bold
+                .or(theme.header_bold)
+                .or(DEFAULT_HEADER_BOLD()),
+            highlight_symbol: self
+                .highlight_symbol
+                .clone()
+                .or(theme.highlight_symbol.clone())
+                .or(DEFAULT_HIGHLIGHT_SYMBOL()),
+            table_border_type: self
+                .table_border_type
+                .clone()
+                .or(theme.table_border_type.clone())
+                .or(DEFAULT_NONE()),
+            table_border: self
+                .table_border
+                .clone()
+                .or(theme.table_border.clone())
+                .or(DEFAULT_NONE()),
             description: self
                 .description
                 .clone()
@@ -286,6 +356,21 @@ impl Theme {
                 .home_tilde_italic
                 .or(theme.home_tilde_italic)
                 .or(DEFAULT_BOOL_NONE()),
+            empty_message_fg: self
+                .empty_message_fg
+                .clone()
+                .or(theme.empty_message_fg.clone())
+                .or(DEFAULT_COLOR_FG_EMPTY_MESSAGE()),
+            empty_message_bg: self
+                .empty_message_bg
+                .clone()
+                .or(theme.empty_message_bg.clone())
+                .or(DEFAULT_COLOR_BG_EMPTY_MESSAGE()),
+            accent: self
+                .accent
+                .clone()
+                .or(theme.accent.clone())
+                .or(DEFAULT_NONE()),
         }
     }
 }
@@ -301,17 +386,39 @@ pub struct ThemeStyles {
     pub date_style: Style,
     pub path_style: Style,
     pub highlight_color: Option<Color>,
+    pub match_style: Style,
     pub shortcut_name_style: Style,
+    /// The styles of the names of the shortcuts having their own color, by shortcut name
+    pub shortcut_name_styles: HashMap<String, Style>,
     pub header_fg_color: Option<Color>,
     pub header_bg_color: Option<Color>,
+    pub header_style: Style,
+    pub highlight_symbol: String,
+    /// None when the table has no borders
+    pub table_border_type: Option<BorderType>,
+    pub table_border_color: Option<Color>,
     pub description_style: Style,
     pub free_text_area_bg_color: Option<Color>,
     pub home_tilde_style: Style,
+    pub empty_message_fg_color: Option<Color>,
+    pub empty_message_bg_color: Option<Color>,
+    /// The color of the answer selected in the dialogs
+    pub accent_color: Option<Color>,
 }
 
 impl ThemeStyles {
     fn build_color(color: Option<&String>) -> Option<Color> { color.map(|c| c.parse().unwrap()) }
 
+    fn build_border_type(border_type: Option<&String>) -> Option<BorderType> {
+        match border_type.map(|b| b.as_str()) {
+            Some("plain") => Some(BorderType::Plain),
+            Some("rounded") => Some(BorderType::Rounded),
+            Some("double") => Some(BorderType::Double),
+            Some("thick") => Some(BorderType::Thick),
+            _ => None,
+        }
+    }
+
     fn build_style(color: Option<&String>, bold: Option<bool>, italic: Option<bool>) -> Style {
         let mut style = Style::new();
         if let Some(color) = Self::build_color(color) {
@@ -349,13 +456,28 @@ impl ThemeStyles {
             date_style: Self::build_style(theme.date.as_ref(), theme.date_bold, theme.date_italic),
             path_style: Self::build_style(theme.path.as_ref(), theme.path_bold, theme.path_italic),
             highlight_color: Self::build_color(theme.highlight.as_ref()),
+            match_style: Self::build_style(theme.match_color.as_ref(), Some(true), None),
             shortcut_name_style: Self::build_style(
                 theme.shortcut_name.as_ref(),
                 theme.shortcut_name_bold,
                 theme.shortcut_name_italic,
             ),
+            shortcut_name_styles: HashMap::new(),
             header_fg_color: Self::build_color(theme.header_fg.as_ref()),
             header_bg_color: Self::build_color(theme.header_bg.as_ref()),
+            header_style: {
+                let mut style =
+                    Self::build_style(theme.header_fg.as_ref(), theme.header_bold, None);
+                if let Some(bg_color) = Self::build_color(theme.header_bg.as_ref()) {
+                    style = style.bg(bg_color);
+                }
+                style
+            },
+            highlight_symbol: theme.highlight_symbol.clone().unwrap_or_default(),
+            table_border_type: Self::build_border_type(theme.table_border_type.as_ref()),
+            table_border_color: Self::build_color(
+                theme.table_border.as_ref().or(theme.border.as_ref()),
+            ),
             description_style: Self::build_style(
                 theme.description.as_ref(),
                 theme.description_bold,
@@ -367,10 +489,39 @@ impl ThemeStyles {
                 theme.home_tilde_bold,
                 theme.home_tilde_italic,
             ),
+            empty_message_fg_color: Self::build_color(theme.empty_message_fg.as_ref()),
+            empty_message_bg_color: Self::build_color(theme.empty_message_bg.as_ref()),
+            accent_color: Self::build_color(theme.accent.as_ref().or(theme.text_em.as_ref())),
         }
     }
 }
 
+impl ThemeStyles {
+    /// Sets the colors of the names of some shortcuts, by shortcut name. The invalid colors are
+    /// ignored.
+    pub fn set_shortcut_colors(&mut self, shortcut_colors: &HashMap<String, String>) {
+        self.shortcut_name_styles = shortcut_colors
+            .iter()
+            .filter_map(|(name, color)| match color.parse::<Color>() {
+                Ok(color) => Some((name.clone(), self.shortcut_name_style.fg(color))),
+                Err(e) => {
+                    warn!("Invalid color '{}' of shortcut '{}': {}", color, name, e);
+                    None
+                }
+            })
+            .collect();
+    }
+
+    /// Returns the style of the name of a shortcut: its own color if any, otherwise the
+    /// `shortcut_name` style.
+    pub fn shortcut_name_style_of(&self, name: &str) -> Style {
+        self.shortcut_name_styles
+            .get(name)
+            .copied()
+            .unwrap_or(self.shortcut_name_style)
+    }
+}
+
 impl Default for ThemeStyles {
     fn default() -> Self { ThemeStyles::from(&Theme::default()) }
 }
@@ -7,6 +7,7 @@ pub(crate) use crate::tui::{
 
 pub(crate) mod event;
 pub(crate) mod managed_view;
+pub(crate) mod overlay;
 pub(crate) mod view;
 pub(crate) mod view_builder;
 pub(crate) mod view_manager;
@@ -0,0 +1,46 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Clear, Paragraph},
+};
+
+/// Prepares the area of a modal view drawn over the other views: what is drawn below is
+/// cleared, and the area is filled with the background color, if any.
+///
+/// ### Parameters
+/// - `frame`: the frame drawn
+/// - `area`: the area of the modal view
+/// - `background`: the background color of the modal view, if any
+pub(crate) fn clear_overlay(frame: &mut Frame, area: Rect, background: Option<Color>) {
+    frame.render_widget(Clear, area);
+    if let Some(background) = background {
+        frame.render_widget(
+            Paragraph::new("").style(Style::default().bg(background)),
+            area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend, widgets::Paragraph};
+
+    use super::*;
+
+    #[test]
+    fn test_clear_overlay() {
+        let mut terminal = Terminal::new(TestBackend::new(6, 3)).unwrap();
+        terminal
+            .draw(|frame| {
+                frame.render_widget(Paragraph::new("xxxxxx\nxxxxxx\nxxxxxx"), frame.area());
+                clear_overlay(frame, Rect::new(1, 1, 4, 1), Some(Color::Blue));
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let row: String = (0..6).map(|x| buffer[(x, 1)].symbol()).collect();
+        assert_eq!(row, "x    x");
+        assert_eq!(buffer[(1, 1)].bg, Color::Blue);
+        assert_eq!(buffer[(0, 1)].bg, Color::Reset);
+    }
+}
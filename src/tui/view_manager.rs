@@ -1,14 +1,24 @@
-use std::{cell::RefCell, collections::HashSet, ops::Add, rc::Rc};
+use std::{
+    cell::RefCell, collections::HashSet, io::stdout, ops::Add, rc::Rc, sync::Once, time::Duration,
+};
 
-use crossterm::event::{
-    DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEvent, KeyModifiers,
-    MouseEvent,
+use crossterm::{
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEvent,
+        KeyModifiers, MouseEvent,
+    },
+    terminal::{EnterAlternateScreen, enable_raw_mode},
 };
 use log::{debug, info, trace, warn};
-use ratatui::layout::{Position, Rect};
+use ratatui::{
+    DefaultTerminal, Terminal,
+    backend::CrosstermBackend,
+    layout::{Position, Rect},
+};
 use tokio::{
     select,
     sync::{broadcast, broadcast::error::RecvError},
+    time::{MissedTickBehavior, interval, timeout},
 };
 use tokio_stream::StreamExt;
 
@@ -21,6 +31,12 @@ use crate::tui::{
 
 type ModalCallBack = Box<dyn FnOnce(&mut dyn View, &dyn View) -> ManagerAction>;
 type HelpViewBuilderCallBack = Box<dyn Fn() -> ViewBuilder>;
+type ActiveViewPath = Vec<Rc<RefCell<ManagedView>>>;
+type TickCallBack = Box<dyn FnMut() -> bool>;
+
+// The maximum number of keys typed in a row handled before the resulting events, so that
+// the events channel does not overflow
+const DEBOUNCE_MAX_KEYS: usize = 8;
 
 /// Represents a modal view entry with its associated parent and close callback.
 struct ModalEntry {
@@ -39,13 +55,22 @@ pub struct ViewManager {
     top_level_view_idx: RefCell<usize>,
 
     receive_events_views: RefCell<Vec<Rc<RefCell<ManagedView>>>>,
-    active_view: RefCell<Vec<Option<Vec<Rc<RefCell<ManagedView>>>>>>,
+    active_view: RefCell<Vec<Option<ActiveViewPath>>>,
     modal_views: RefCell<Vec<Rc<RefCell<ModalEntry>>>>,
     context_view: RefCell<Option<Rc<RefCell<ManagedView>>>>,
 
     global_help_view_builder_cb: Option<HelpViewBuilderCallBack>,
 
     exit_string: RefCell<Option<String>>,
+
+    // the delay to wait for another typed key before handling the resulting events
+    debounce: Duration,
+
+    // the terminal was handed over to another program: it must be fully redrawn
+    suspended: RefCell<bool>,
+
+    // the period and the function called periodically while no event is received
+    tick: Option<(Duration, RefCell<TickCallBack>)>,
 }
 
 #[allow(unused)]
@@ -66,6 +91,9 @@ impl ViewManager {
             context_view: RefCell::new(None),
             global_help_view_builder_cb: None,
             exit_string: RefCell::new(None),
+            debounce: Duration::ZERO,
+            suspended: RefCell::new(false),
+            tick: None,
         }
     }
 
@@ -75,6 +103,70 @@ impl ViewManager {
         self.global_help_view_builder_cb = Some(help_view);
     }
 
+    /// Sets the delay to wait for another typed key before handling the events resulting from
+    /// the keys typed in a row (e.g. the queries triggered by a search text): only the last
+    /// application event of each id is then handled. A zero delay disables the debounce.
+    pub fn set_debounce(&mut self, debounce: Duration) { self.debounce = debounce; }
+
+    /// Sets a function called periodically by the event loop, e.g. to poll for changes made
+    /// outside of the application. The function returns whether it sent events: they are then
+    /// handled, and the views redrawn, without waiting for a key.
+    pub fn set_tick(&mut self, period: Duration, tick: TickCallBack) {
+        self.tick = Some((period, RefCell::new(tick)));
+    }
+
+    /// Returns the index of the top level view currently displayed, in the order of addition.
+    pub fn top_level_view_idx(&self) -> usize { *self.top_level_view_idx.borrow() }
+
+    /// Hands the terminal over to a function, typically running an interactive program: the
+    /// terminal is restored before calling it, and set up again afterwards.
+    /// The views are then resized and redrawn at the next iteration of the event loop.
+    ///
+    /// ### Returns
+    /// The result of the function.
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        debug!("suspending the terminal");
+        Self::restore_terminal();
+
+        let result = f();
+
+        debug!("resuming the terminal");
+        if let Err(e) = enable_raw_mode() {
+            warn!("Failed to enable the raw mode: {}", e);
+        }
+        let _ = crossterm::execute!(stdout(), EnterAlternateScreen, EnableMouseCapture);
+        self.suspended.replace(true);
+        result
+    }
+
+    /// Sets the terminal up for the views: raw mode, alternate screen and mouse capture. If the
+    /// program panics afterwards, the terminal is restored before the panic is printed.
+    fn init_terminal() -> std::io::Result<DefaultTerminal> {
+        Self::install_panic_hook();
+        enable_raw_mode()?;
+        crossterm::execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Terminal::new(CrosstermBackend::new(stdout()))
+    }
+
+    /// Installs the panic hook restoring the terminal, once whatever the number of event loops
+    /// (`ratatui::try_init` would add a hook at each one).
+    fn install_panic_hook() {
+        static PANIC_HOOK: Once = Once::new();
+        PANIC_HOOK.call_once(|| {
+            let hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                Self::restore_terminal();
+                hook(info);
+            }));
+        });
+    }
+
+    /// Restores the terminal as it was before the views, whatever its current state.
+    fn restore_terminal() {
+        let _ = crossterm::execute!(stdout(), DisableMouseCapture);
+        ratatui::restore();
+    }
+
     /// Returns a centered rectangle of the specified width and height within the given area.
     ///
     /// If the requested width or height is larger than the area, it will be clamped
@@ -582,10 +674,14 @@ impl ViewManager {
         manager_action
     }
 
+    /// Handles an event read from the terminal.
+    ///
+    /// ### Returns
+    /// What the views need, or the error which occurred while reading the terminal.
     pub fn handle_crossterm_event(
         &self,
         crossterm_event: Option<std::io::Result<Event>>,
-    ) -> ManagerAction {
+    ) -> std::io::Result<ManagerAction> {
         debug!("received crossterm event: {:?}", crossterm_event);
         let mut manager_action: ManagerAction = ManagerAction::new(false);
         match crossterm_event {
@@ -626,12 +722,71 @@ impl ViewManager {
                     manager_action.redraw = true;
                 }
             },
-            Some(Err(e)) => println!("Error: {e:?}\r"),
+            Some(Err(e)) => return Err(e),
             None => {}
         };
+        Ok(manager_action)
+    }
+
+    /// Returns whether a key is typed text, whose resulting events can be debounced.
+    fn is_typing_key(event: &Option<std::io::Result<Event>>) -> bool {
+        matches!(
+            event,
+            Some(Ok(Event::Key(key_event)))
+                if !key_event.modifiers.contains(KeyModifiers::CONTROL)
+                    && matches!(
+                        key_event.code,
+                        KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete
+                    )
+        )
+    }
+
+    /// Keeps only the last application event of each id (they describe a state, and the last
+    /// one supersedes the previous ones); the other events are all kept, in order.
+    fn coalesce_events(events: Vec<GenericEvent>) -> Vec<GenericEvent> {
+        let mut coalesced: Vec<GenericEvent> = vec![];
+        let mut seen_ids: HashSet<String> = HashSet::new();
+        for event in events.into_iter().rev() {
+            if let GenericEvent::ApplicationEvent(ae) = &event
+                && !seen_ids.insert(ae.id.clone())
+            {
+                trace!("coalescing application event '{}'", ae.id);
+                continue;
+            }
+            coalesced.push(event);
+        }
+        coalesced.reverse();
+        coalesced
+    }
+
+    /// Handles the pending broadcast events, coalesced.
+    fn flush_broadcast_events(&self, rx: &mut broadcast::Receiver<GenericEvent>) -> ManagerAction {
+        let mut events = vec![];
+        loop {
+            match rx.try_recv() {
+                Ok(event) => events.push(event),
+                Err(broadcast::error::TryRecvError::Lagged(n)) => {
+                    warn!("broadcast lagged: {} events lost", n);
+                }
+                Err(_) => break,
+            }
+        }
+        let mut manager_action = ManagerAction::new(false);
+        for event in Self::coalesce_events(events) {
+            manager_action.merge(&self.handle_broadcast_event(&Ok(event)));
+        }
         manager_action
     }
 
+    /// Selects the top level view displayed, by index in the order of addition, e.g. before
+    /// the event loop to start with another view than the first one.
+    pub fn select_top_level_view(&self, idx: usize) {
+        if idx < self.views.borrow().len() {
+            debug!("selecting top level view idx={}", idx);
+            self.top_level_view_idx.replace(idx);
+        }
+    }
+
     fn switch_to_next_top_level_view(&self) {
         let tlvi = self.top_level_view_idx.borrow();
         let idx = tlvi.add(1) % self.views.borrow().len();
@@ -640,21 +795,42 @@ impl ViewManager {
         self.top_level_view_idx.replace(idx);
     }
 
-    pub async fn event_loop(&self) -> Option<String> {
-        let mut stdout = std::io::stdout();
-        crossterm::execute!(stdout, EnableMouseCapture).expect("failed to enable mouse capture");
+    /// Runs the application in the terminal until it is closed, the terminal being restored
+    /// whatever the way it ends.
+    ///
+    /// ### Returns
+    /// The exit string, if any, or the error which ended the application, e.g. when the
+    /// terminal can't be read.
+    pub async fn event_loop(&self) -> std::io::Result<Option<String>> {
+        let result = match Self::init_terminal() {
+            Ok(mut term) => self.run_event_loop(&mut term).await,
+            Err(e) => Err(e),
+        };
+        // on every exit path, the terminal may have been set up partially
+        Self::restore_terminal();
+        result?;
+        Ok(self.exit_string.take())
+    }
 
-        let mut term = ratatui::init();
+    /// Draws the views and handles the events until the application is closed.
+    async fn run_event_loop(&self, term: &mut DefaultTerminal) -> std::io::Result<()> {
         let init_rect = term.get_frame().area();
         self.resize(init_rect.width, init_rect.height);
 
         // initial draw
-        let _ = term.draw(|frame| {
+        term.draw(|frame| {
             self.draw(frame);
-        });
+        })?;
 
         let mut crossterm_reader = EventStream::new();
         let mut rx = self.tx.subscribe();
+        // without tick function, the ticks are disabled in the select
+        let mut ticks = interval(
+            self.tick
+                .as_ref()
+                .map_or(Duration::from_secs(3600), |(period, _)| *period),
+        );
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
         let mut manager_action: ManagerAction = ManagerAction::new(false);
         while !manager_action.close {
@@ -663,8 +839,39 @@ impl ViewManager {
                 broadcast_event = rx.recv() => {
                     manager_action = self.handle_broadcast_event(&broadcast_event);
                 }
+                _ = ticks.tick(), if self.tick.is_some() => {
+                    manager_action = ManagerAction::new(false);
+                    if let Some((_, tick)) = &self.tick
+                        && (tick.borrow_mut())()
+                    {
+                        manager_action = self.flush_broadcast_events(&mut rx);
+                        manager_action.redraw = true;
+                    }
+                }
                 crossterm_event = crossterm_event_next => {
-                    manager_action = self.handle_crossterm_event(crossterm_event);
+                    let debounced = !self.debounce.is_zero() && Self::is_typing_key(&crossterm_event);
+                    manager_action = self.handle_crossterm_event(crossterm_event)?;
+                    if debounced {
+                        // handle the keys typed in a row before the events they trigger
+                        let mut pending_event = None;
+                        for _ in 0..DEBOUNCE_MAX_KEYS {
+                            match timeout(self.debounce, crossterm_reader.next()).await {
+                                Ok(event) if Self::is_typing_key(&event) => {
+                                    manager_action.merge(&self.handle_crossterm_event(event)?);
+                                }
+                                Ok(event) => {
+                                    // e.g. Enter: handled once the list is up to date
+                                    pending_event = Some(event);
+                                    break;
+                                }
+                                Err(_) => break,
+                            }
+                        }
+                        manager_action.merge(&self.flush_broadcast_events(&mut rx));
+                        if let Some(event) = pending_event {
+                            manager_action.merge(&self.handle_crossterm_event(event)?);
+                        }
+                    }
                 }
             }
 
@@ -676,6 +883,11 @@ impl ViewManager {
                     manager_action.redraw = true;
                 }
             }
+            if self.suspended.replace(false) {
+                // the screen was used by another program, which may also have been resized
+                term.clear()?;
+                manager_action.resize = true;
+            }
             if manager_action.resize() {
                 debug!("ViewManager resizing");
                 let init_rect = term.get_frame().area();
@@ -684,16 +896,12 @@ impl ViewManager {
             }
             if manager_action.redraw() {
                 debug!("ViewManager redrawing");
-                let _ = term.draw(|frame| {
+                term.draw(|frame| {
                     self.draw(frame);
-                });
+                })?;
             }
         }
-        let mut stdout = std::io::stdout();
-        crossterm::execute!(stdout, DisableMouseCapture).expect("failed to disable mouse capture");
-        ratatui::restore();
-
-        self.exit_string.take()
+        Ok(())
     }
 }
 
@@ -861,4 +1069,85 @@ mod tests {
         assert_eq!(centered.width, 50);
         assert_eq!(centered.height, 50);
     }
+
+    fn application_event(id: &str, payload: &str) -> GenericEvent {
+        GenericEvent::ApplicationEvent(crate::tui::event::ApplicationEvent {
+            id: id.to_string(),
+            payload: Some(std::sync::Arc::new(payload.to_string())),
+        })
+    }
+
+    struct ReceiverView {
+        received: Rc<RefCell<Vec<String>>>,
+    }
+    impl View for ReceiverView {
+        fn draw(&mut self, _: &mut ratatui::Frame, _: Rect, _: bool) {}
+        fn handle_application_event(&mut self, ae: &crate::tui::event::ApplicationEvent) {
+            let payload = ae
+                .payload
+                .as_ref()
+                .unwrap()
+                .downcast_ref::<String>()
+                .unwrap();
+            self.received.borrow_mut().push(payload.clone());
+        }
+    }
+
+    #[test]
+    fn test_terminal_error() {
+        // an error reading the terminal ends the event loop
+        let vm = ViewManager::new();
+        let result = vm.handle_crossterm_event(Some(Err(std::io::Error::other("no tty"))));
+        assert_eq!(result.unwrap_err().to_string(), "no tty");
+        assert!(!vm.handle_crossterm_event(None).unwrap().close());
+    }
+
+    #[test]
+    fn test_debounce() {
+        let key = |code: KeyCode, modifiers: KeyModifiers| {
+            Some(Ok(Event::Key(KeyEvent::new(code, modifiers))))
+        };
+        assert!(ViewManager::is_typing_key(&key(
+            KeyCode::Char('a'),
+            KeyModifiers::NONE
+        )));
+        assert!(ViewManager::is_typing_key(&key(
+            KeyCode::Backspace,
+            KeyModifiers::NONE
+        )));
+        assert!(!ViewManager::is_typing_key(&key(
+            KeyCode::Enter,
+            KeyModifiers::NONE
+        )));
+        assert!(!ViewManager::is_typing_key(&key(
+            KeyCode::Char('f'),
+            KeyModifiers::CONTROL
+        )));
+
+        let received = Rc::new(RefCell::new(vec![]));
+        let vm = ViewManager::new();
+        vm.add_view(
+            0,
+            ViewBuilder::from(Box::new(ReceiverView {
+                received: received.clone(),
+            }))
+            .with_publish_events(true),
+            &[],
+        );
+        let mut rx = vm.tx().subscribe();
+        for event in [
+            application_event("search", "a"),
+            application_event("other", "x"),
+            application_event("search", "ab"),
+            GenericEvent::ViewManagerEvent(ViewManagerEvent::Redraw),
+            application_event("search", "abc"),
+        ] {
+            vm.tx().send(event).unwrap();
+        }
+
+        // only the last event of each id is handled
+        let action = vm.flush_broadcast_events(&mut rx);
+        assert!(action.redraw);
+        assert_eq!(*received.borrow(), vec!["x", "abc"]);
+    }
 }
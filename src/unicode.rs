@@ -0,0 +1,42 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// Normalizes a text to its composed form (NFC), so that the two spellings of an accented
+/// letter, e.g. `é` and `e` followed by a combining acute accent, are the same string, as
+/// the file systems storing the names decomposed (e.g. macOS) spell them differently.
+///
+/// ### Parameters
+/// - `text`: the text to normalize
+///
+/// ### Returns
+/// The text in Unicode Normalization Form C.
+pub(crate) fn normalize_unicode(text: &str) -> String { text.nfc().collect() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_unicode() {
+        assert_eq!(normalize_unicode("/tmp/cafe\u{301}"), "/tmp/café");
+        assert_eq!(normalize_unicode("/tmp/café"), "/tmp/café");
+        assert_eq!(
+            normalize_unicode("/A\u{30a}rhus/C\u{327}a/z\u{30c}"),
+            "/Århus/Ça/ž"
+        );
+        assert_eq!(normalize_unicode("/tmp/src"), "/tmp/src");
+        assert_eq!(normalize_unicode(""), "");
+
+        // a mark without composition, or without letter, is kept
+        assert_eq!(normalize_unicode("/x\u{301}"), "/x\u{301}");
+        assert_eq!(normalize_unicode("\u{301}e"), "\u{301}e");
+        assert_eq!(normalize_unicode("/日本"), "/日本");
+
+        // the Korean syllables stored as decomposed jamo
+        assert_eq!(normalize_unicode("/\u{1112}\u{1161}\u{11ab}"), "/한");
+        // the Cyrillic short i and yo
+        assert_eq!(normalize_unicode("/\u{438}\u{306}/\u{435}\u{308}"), "/й/ё");
+        // the marks in canonical order: the dot below before the circumflex
+        assert_eq!(normalize_unicode("/e\u{302}\u{323}"), "/ệ");
+        assert_eq!(normalize_unicode("/e\u{323}\u{302}"), "/ệ");
+    }
+}
@@ -0,0 +1,52 @@
+use std::{path::Path, process::Command};
+
+/// Runs cdir with a configuration and a database of a directory, which is also the home
+/// directory, so that nothing is written outside of it.
+fn cdir(dir: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_cdir"))
+        .arg("--config-file")
+        .arg(dir.join("config.yaml"))
+        .arg("--db")
+        .arg(dir.join("cdir.db"))
+        .args(args)
+        .env("HOME", dir)
+        .env("XDG_CONFIG_HOME", dir.join(".config"))
+        .env("XDG_DATA_HOME", dir.join(".local/share"))
+        .env_remove("CDIR_CONFIG")
+        .env_remove("CDIR_LOG")
+        .env_remove("CDIR_PROFILE")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_shortcut_names() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("config.yaml"), "{}").unwrap();
+
+    // nothing without shortcut
+    let output = cdir(dir.path(), &["shortcut", "names"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "");
+
+    for (name, path) in [
+        ("work", "/home/user/work"),
+        ("docs", "/home/user/docs"),
+        ("tmp", "/tmp"),
+    ] {
+        assert!(
+            cdir(dir.path(), &["add-shortcut", name, path])
+                .status
+                .success()
+        );
+    }
+
+    // the names only, sorted, one per line
+    let output = cdir(dir.path(), &["shortcut", "names"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "docs\ntmp\nwork\n"
+    );
+    assert!(output.stderr.is_empty());
+}